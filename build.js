@@ -134,6 +134,7 @@ window.ftd = (function () {
         let element = document.getElementById(data);
         if (!!element) {
             ftd_data[id] = JSON.parse(element.innerText);
+            init_loops(id);
             window.ftd.post_init();
         }
     };
@@ -180,15 +181,22 @@ window.ftd = (function () {
     exports.handle_event = function (evt, id, event, obj) {
         console_log(id, event);
         let actions = JSON.parse(event);
-        for (const action in actions) {
-            handle_event(evt, id, actions[action], obj);
-        }
+        batch(() => in_instances_of(id, obj, () => {
+            for (const action in actions) {
+                handle_event(evt, id, actions[action], obj);
+            }
+        }));
+        sync_loops(id);
     };
     exports.handle_function = function (evt, id, event, obj) {
         console_log(id, event);
         let actions = JSON.parse(event);
         let function_arguments = [];
-        return handle_function(evt, id, actions, obj, function_arguments);
+        let result = null;
+        in_instances_of(id, obj, () => {
+            result = handle_function(evt, id, actions, obj, function_arguments);
+        });
+        return result;
     };
     exports.get_value = function (id, variable) {
         let data = ftd_data[id];
@@ -224,6 +232,10 @@ window.ftd = (function () {
         window.ftd.set_value_by_id("main", variable, value);
     };
     exports.set_value_by_id = function (id, variable, value) {
+        batch(() => set_value(id, variable, value));
+        sync_loops(id);
+    };
+    function set_value(id, variable, value) {
         let data = ftd_data[id];
         let [var_name, remaining] = get_name_and_remaining(variable);
         if (data[var_name] === undefined && data[variable] === undefined) {
@@ -236,7 +248,7 @@ window.ftd = (function () {
         else {
             set_data_value(data, variable, value);
         }
-    };
+    }
     exports.is_empty = function (str) {
         return (!str || str.length === 0);
     };
@@ -244,6 +256,497 @@ window.ftd = (function () {
         array.push(value);
         return array;
     };
+    exports.length = function (array) {
+        return array.length;
+    };
+    // The list functions return a new list, as the functions of `ftd::interpreter2::default`
+    // do, and throw if the index is not in the list.
+    function list_index(index, length) {
+        if (!Number.isInteger(index) || index < 0 || index >= length) {
+            throw new Error("index `" + index + "` is out of bounds");
+        }
+        return index;
+    }
+    exports.insert_at = function (array, index, value) {
+        let result = array.slice();
+        result.splice(list_index(index, array.length + 1), 0, value);
+        return result;
+    };
+    exports.delete_at = function (array, index) {
+        let result = array.slice();
+        result.splice(list_index(index, array.length), 1);
+        return result;
+    };
+    exports.clear = function (_array) {
+        return [];
+    };
+    // `filter(array, value)` keeps the items equal to `value`, `filter(array, field, value)`
+    // keeps the records whose `field` is equal to `value`
+    exports.filter = function (array, ...args) {
+        if (args.length === 1) {
+            return array.filter(item => JSONstringify(item) === JSONstringify(args[0]));
+        }
+        return array.filter(item => JSONstringify(item[args[0]]) === JSONstringify(args[1]));
+    };
+    exports.contains = function (array, value) {
+        return array.some(item => JSONstringify(item) === JSONstringify(value));
+    };
+    exports.sort_by = function (array, field) {
+        return array.slice().sort((a, b) => a[field] < b[field] ? -1 : a[field] > b[field] ? 1 : 0);
+    };
+    // The properties of the nodes that depend on variables, see `ftd::html1::NodeDependency`.
+    // The node changes, which the set value functions of the variables call, only queue the
+    // properties, they are set once when the event is handled even if several of the variables
+    // changed.
+    let node_dependencies = {};
+    let queued_node_changes = {};
+    let batch_depth = 0;
+    exports.dependencies = function (id, dependencies) {
+        let functions = node_dependencies[id] = node_dependencies[id] || {};
+        let node_changes = window["node_change_" + id] = window["node_change_" + id] || {};
+        for (const dependency of dependencies) {
+            let f = (data) => set_node_property(id, dependency, data);
+            functions[dependency.key] = !!dependency.template ? loop_node_change(id, dependency.template, f) : f;
+            node_changes[dependency.key] = (_data) => queue_node_change(id, dependency.key);
+        }
+    };
+    function queue_node_change(id, key) {
+        let queue = queued_node_changes[id] = queued_node_changes[id] || [];
+        if (queue.indexOf(key) === -1) {
+            queue.push(key);
+        }
+        if (batch_depth === 0) {
+            flush_node_changes();
+        }
+    }
+    function batch(f) {
+        batch_depth += 1;
+        try {
+            f();
+        }
+        finally {
+            batch_depth -= 1;
+        }
+        if (batch_depth === 0) {
+            flush_node_changes();
+        }
+    }
+    function flush_node_changes() {
+        batch_depth += 1;
+        try {
+            // setting a property can change a variable again, e.g. the item of a loop
+            let ids;
+            while ((ids = Object.keys(queued_node_changes)).length > 0) {
+                for (const id of ids) {
+                    let queue = queued_node_changes[id];
+                    delete queued_node_changes[id];
+                    let node_changes = window["node_change_" + id] || {};
+                    for (const key of queue) {
+                        // the node changes of the instances rendered at build time are dropped
+                        if (!!node_changes[key]) {
+                            node_dependencies[id][key](ftd_data[id]);
+                        }
+                    }
+                }
+            }
+        }
+        finally {
+            batch_depth -= 1;
+        }
+    }
+    function set_node_property(id, dependency, data) {
+        let node = find_node(dependency.id);
+        if (!node) {
+            return;
+        }
+        let expression = dependency.default;
+        for (const [condition, value] of dependency.cases) {
+            if (evaluate(id, condition, data, {})) {
+                expression = value;
+                break;
+            }
+        }
+        let value = evaluate(id, expression, data, {});
+        let property = dependency.property;
+        if (property === "text") {
+            node.innerHTML = value;
+        }
+        else if ("style" in property) {
+            node.style[property.style] = value;
+        }
+        else if (value === null || value === undefined) {
+            node.removeAttribute(property.attribute);
+        }
+        else {
+            node.setAttribute(property.attribute, value);
+        }
+    }
+    function evaluate(id, expression, data, locals) {
+        if ("value" in expression) {
+            return expression.value;
+        }
+        else if ("reference" in expression) {
+            return resolve_reference(expression.reference, data, null);
+        }
+        else if ("variable" in expression) {
+            return locals[expression.variable];
+        }
+        else if ("format" in expression) {
+            let format = expression.format;
+            let value = format.pattern.format(JSONstringify(evaluate(id, format.value, data, locals)));
+            return format.eval ? eval(value) : value;
+        }
+        else if ("record" in expression) {
+            let record = {};
+            for (const name in expression.record) {
+                record[name] = evaluate(id, expression.record[name], data, locals);
+            }
+            return record;
+        }
+        else if ("function" in expression) {
+            return window.ftd.handle_function(null, id, JSON.stringify(expression.function), null);
+        }
+        else if ("call" in expression) {
+            let f = expression.call.name.split(".").reduce((object, name) => object[name], window);
+            return f(...expression.call.arguments.map(argument => evaluate(id, argument, data, locals)));
+        }
+        else if ("operation" in expression) {
+            return operation(id, expression.operation.operator, expression.operation.operands, data, locals);
+        }
+        else if ("theme" in expression) {
+            let theme = expression.theme;
+            return evaluate(id, !data["ftd#dark-mode"] ? theme.light : theme.dark, data, locals);
+        }
+        else {
+            let device = expression.device;
+            return evaluate(id, data["ftd#device"] == "desktop" ? device.desktop : device.mobile, data, locals);
+        }
+    }
+    // The operators of `ftd::evalexpr`, with the semantics of JS, same as the conditions used to
+    // be compiled to.
+    function operation(id, operator, operands, data, locals) {
+        let value = (i) => evaluate(id, operands[i], data, locals);
+        switch (operator) {
+            case ";": {
+                let result = null;
+                operands.forEach((_, i) => result = value(i));
+                return result;
+            }
+            case ",":
+                return operands.map((_, i) => value(i));
+            case "&&":
+                return value(0) && value(1);
+            case "||":
+                return value(0) || value(1);
+            case "!":
+                return !value(0);
+        }
+        if (operator === "-" && operands.length === 1) {
+            return -value(0);
+        }
+        if (/^(\+|-|\*|\/|%|\^|&&|\|\|)?=$/.test(operator)) {
+            // an assignment, e.g. `f = a + 1` or `f += 1`
+            let name = operands[0].variable;
+            return locals[name] = operator === "=" ? value(1) : operation(id, operator.slice(0, -1), operands, data, locals);
+        }
+        let [a, b] = [value(0), value(1)];
+        switch (operator) {
+            case "+": return a + b;
+            case "-": return a - b;
+            case "*": return a * b;
+            case "/": return a / b;
+            case "%": return a % b;
+            case "^": return Math.pow(a, b);
+            case "==": return a == b;
+            case "!=": return a != b;
+            case ">": return a > b;
+            case "<": return a < b;
+            case ">=": return a >= b;
+            case "<=": return a <= b;
+        }
+        console_log(operator, "is not supported, ignoring");
+        return null;
+    }
+    // Loops on mutable lists, see `ftd::executor::LoopTemplate`. The nodes of a loop template are
+    // cloned for every item of the list, an instance, when the list changes. The instances keep
+    // the data ids of the template, so its node changes are run for each instance with the
+    // nodes looked up in it, and with the variables of the template, its item, counter and
+    // locals, set to the ones of the instance.
+    let loop_templates = {};
+    let loop_states = {};
+    let active_instances = {};
+    let instance_of_element = new WeakMap();
+    exports.loop_template = function (id, template) {
+        template.node_changes = [];
+        template.initial_locals = {};
+        loop_templates[id] = loop_templates[id] || {};
+        loop_templates[id][template.id] = template;
+    };
+    function loop_node_change(id, template, f) {
+        let loop_template = loop_templates[id][template];
+        loop_template.node_changes.push(f);
+        return function (data) {
+            for_each_instance(id, loop_template, instance => in_scope(instance.root, () => f(data)));
+        };
+    }
+    function init_loops(id) {
+        let data = ftd_data[id];
+        let states = {};
+        let templates = loop_templates[id] || {};
+        for (const template_id in templates) {
+            let template = templates[template_id];
+            for (const name of template.locals) {
+                if (name in data) {
+                    template.initial_locals[name] = deepCopy(data[name]);
+                }
+            }
+            if (template.parent === null) {
+                // the instances rendered at build time are of the initial value of the list
+                states[template_id] = {
+                    template: template,
+                    rendered: JSON.stringify(get_data_value(data, template.on)),
+                    instances: null,
+                };
+            }
+        }
+        loop_states[id] = states;
+    }
+    // Runs `f` for the instance of `template` in the current context, or for all of them.
+    function for_each_instance(id, template, f) {
+        let active = (active_instances[id] || {})[template.id];
+        if (!!active) {
+            f(active);
+            return;
+        }
+        let each = state => {
+            for (const instance of (!!state && state.instances) || []) {
+                in_context(id, instance, () => f(instance));
+            }
+        };
+        if (template.parent === null) {
+            each((loop_states[id] || {})[template.id]);
+        }
+        else {
+            for_each_instance(id, loop_templates[id][template.parent], parent => each(parent.loops[template.id]));
+        }
+    }
+    let scope = null;
+    function in_scope(root, f) {
+        let previous = scope;
+        scope = root;
+        try {
+            f();
+        }
+        finally {
+            scope = previous;
+        }
+    }
+    // The node with `data_id`, in the instance of the loop template in scope if any. A node
+    // rendered for another value of an inner loop is not in the instance any more.
+    function find_node(data_id) {
+        let selectors = `[data-id="${data_id}"]`;
+        if (scope === null) {
+            return document.querySelector(selectors);
+        }
+        return scope.matches(selectors) ? scope : scope.querySelector(selectors);
+    }
+    function in_context(id, instance, f) {
+        let data = ftd_data[id];
+        let template = instance.template;
+        let active = active_instances[id] = active_instances[id] || {};
+        let previous = active[template.id];
+        active[template.id] = instance;
+        for (const name in instance.locals) {
+            data[name] = deepCopy(instance.locals[name]);
+        }
+        let item = get_data_value(data, template.on + "." + instance.index);
+        let item_json = JSON.stringify(item);
+        data[template.item] = item;
+        data[template.counter] = instance.index;
+        try {
+            f();
+        }
+        finally {
+            for (const name of template.locals) {
+                if (name in data) {
+                    instance.locals[name] = deepCopy(data[name]);
+                }
+            }
+            if (previous === undefined) {
+                delete active[template.id];
+            }
+            else {
+                active[template.id] = previous;
+            }
+            // the item was changed through the alias of the loop, e.g. `$obj.done`
+            if (JSON.stringify(data[template.item]) !== item_json) {
+                set_value(id, template.on + "." + instance.index, data[template.item]);
+            }
+        }
+    }
+    // Runs `f` in the context of the instances `element` is in, outermost first.
+    function in_instances_of(id, element, f) {
+        let instances = [];
+        for (let e = element; !!e; e = e.parentElement) {
+            let instance = instance_of_element.get(e);
+            if (!!instance) {
+                instances.unshift(instance);
+            }
+        }
+        let run = i => i < instances.length ? in_context(id, instances[i], () => run(i + 1)) : f();
+        run(0);
+    }
+    function sync_loops(id) {
+        let states = loop_states[id] || {};
+        for (const template_id in states) {
+            sync_loop(id, states[template_id], document);
+        }
+    }
+    function sync_loop(id, state, scope) {
+        let template = state.template;
+        let anchor = scope.querySelector(`template[data-loop="${template.id}"]`);
+        if (!anchor || !anchor.isConnected) {
+            return;
+        }
+        let list = get_data_value(ftd_data[id], template.on) || [];
+        let rendered = JSON.stringify(list);
+        if (rendered !== state.rendered) {
+            render_loop(id, state, anchor, list, scope);
+            state.rendered = rendered;
+            return;
+        }
+        for (const instance of state.instances || []) {
+            if (Object.keys(instance.loops).length === 0) {
+                continue;
+            }
+            in_context(id, instance, () => {
+                for (const template_id in instance.loops) {
+                    sync_loop(id, instance.loops[template_id], instance.root);
+                }
+            });
+        }
+    }
+    function render_loop(id, state, anchor, list, scope) {
+        let template = state.template;
+        let old_instances = state.instances !== null
+            ? state.instances
+            : build_time_instances(id, template, scope, state.rendered);
+        let keys = loop_keys(list);
+        // an instance is kept for the same item, then for the next changed one, e.g. an item
+        // with a field set, so the instance keeps its locals
+        let by_key = {};
+        for (const instance of old_instances) {
+            (by_key[instance.key] = by_key[instance.key] || []).push(instance);
+        }
+        let kept = keys.map(key => (by_key[key] || []).shift());
+        let unused = old_instances.filter(instance => kept.indexOf(instance) === -1);
+        kept = kept.map(instance => instance || unused.shift());
+        for (const instance of unused) {
+            instance.root.remove();
+        }
+        state.instances = kept.map((instance, index) => {
+            if (!instance || instance.build_time) {
+                if (!!instance) {
+                    instance.root.remove();
+                }
+                instance = new_instance(id, template, anchor, !!instance ? instance.locals : null);
+            }
+            instance.key = keys[index];
+            instance.index = index;
+            anchor.parentNode.insertBefore(instance.root, anchor);
+            return instance;
+        });
+        for (const instance of state.instances) {
+            refresh_instance(id, instance);
+        }
+    }
+    // The instances rendered at build time, with the values of their locals. Their node
+    // changes are dropped, the ones of the template are run for the new instances.
+    function build_time_instances(id, template, scope, rendered) {
+        let data = ftd_data[id];
+        let keys = rendered === null ? [] : loop_keys(JSON.parse(rendered) || []);
+        let template_container = split_once(template.id, ":")[0];
+        let node_changes = window["node_change_" + id] || {};
+        let instances = [];
+        template.instances.forEach((instance_id, index) => {
+            let container = split_once(instance_id, ":")[0];
+            for (const key in node_changes) {
+                let node_container = split_once(key, ":")[0];
+                if (node_container === container || node_container.startsWith(container + ",")) {
+                    delete node_changes[key];
+                }
+            }
+            let root = scope.querySelector(`[data-id="${instance_id}"]`);
+            if (!root) {
+                return;
+            }
+            let locals = {};
+            for (const name of template.locals) {
+                let i = name.lastIndexOf(":");
+                let build_time_name = name.slice(0, i + 1) + container + name.slice(i + 1 + template_container.length);
+                if (build_time_name in data) {
+                    locals[name] = deepCopy(data[build_time_name]);
+                }
+            }
+            instances.push({
+                template: template,
+                root: root,
+                index: index,
+                key: index < keys.length ? keys[index] : "",
+                locals: locals,
+                loops: {},
+                build_time: true,
+            });
+        });
+        return instances;
+    }
+    function new_instance(id, template, anchor, locals) {
+        let root = anchor.content.firstElementChild.cloneNode(true);
+        let instance = {
+            template: template,
+            root: root,
+            index: 0,
+            key: "",
+            locals: deepCopy(locals || template.initial_locals),
+            loops: {},
+            build_time: false,
+        };
+        let templates = loop_templates[id];
+        for (const template_id in templates) {
+            if (templates[template_id].parent === template.id) {
+                instance.loops[template_id] = { template: templates[template_id], rendered: null, instances: null };
+            }
+        }
+        instance_of_element.set(root, instance);
+        return instance;
+    }
+    function refresh_instance(id, instance) {
+        let data = ftd_data[id];
+        let template = instance.template;
+        in_context(id, instance, () => {
+            // the variables that depend on the item and the counter, e.g. the arguments of the
+            // components, are computed again
+            let set_functions = window["set_value_" + id] || {};
+            for (const name of [template.item, template.counter]) {
+                if (!!set_functions[name]) {
+                    set_functions[name](data, data[name], null);
+                }
+            }
+            in_scope(instance.root, () => template.node_changes.forEach(f => f(data)));
+            for (const template_id in instance.loops) {
+                sync_loop(id, instance.loops[template_id], instance.root);
+            }
+        });
+    }
+    // The key of an item is its value, and which occurrence of the value it is.
+    function loop_keys(list) {
+        let counts = {};
+        return list.map(item => {
+            let key = JSON.stringify(item);
+            counts[key] = (counts[key] || 0) + 1;
+            return key + "#" + counts[key];
+        });
+    }
     return exports;
 })();
 window.ftd.post_init = function () {
@@ -34,24 +34,26 @@ impl ComponentDefinition {
         doc_id: &str,
     ) -> ftd::ast::Result<ComponentDefinition> {
         if !Self::is_component_definition(section) {
-            return ftd::ast::parse_error(
+            return ftd::ast::parse_error_at(
                 format!(
-                    "Section is not component definition section, found `{:?}`",
-                    section
+                    "Section is not component definition section, found `{}`",
+                    section.name
                 ),
                 doc_id,
                 section.line_number,
+                section.span,
             );
         }
 
         if section.sub_sections.len() != 1 {
-            return ftd::ast::parse_error(
+            return ftd::ast::parse_error_at(
                 format!(
-                    "Component definition should be exactly one, found `{:?}`",
-                    section
+                    "Component definition should be exactly one, found `{}`",
+                    section.name
                 ),
                 doc_id,
                 section.line_number,
+                section.span,
             );
         }
 
@@ -81,9 +83,12 @@ pub struct Component {
     pub events: Vec<Event>,
     pub children: Vec<Component>,
     pub line_number: usize,
+    #[serde(default, skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
 }
 
 impl Component {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         name: &str,
         properties: Vec<Property>,
@@ -92,6 +97,7 @@ impl Component {
         events: Vec<Event>,
         children: Vec<Component>,
         line_number: usize,
+        span: ftd::p11::Span,
     ) -> Component {
         Component {
             name: name.to_string(),
@@ -101,6 +107,7 @@ impl Component {
             events,
             children,
             line_number,
+            span,
         }
     }
 
@@ -113,10 +120,14 @@ impl Component {
         doc_id: &str,
     ) -> ftd::ast::Result<Component> {
         if !Self::is_component(section) {
-            return ftd::ast::parse_error(
-                format!("Section is not ComponentDefinition, found `{:?}`", section),
+            return ftd::ast::parse_error_at(
+                format!(
+                    "Section is not ComponentDefinition, found `{}`",
+                    section.name
+                ),
                 doc_id,
                 section.line_number,
+                section.span,
             );
         }
 
@@ -152,12 +163,14 @@ impl Component {
             if let Some(ftd::p11::Body {
                 ref value,
                 line_number,
+                span,
             }) = section.body
             {
                 properties.push(Property::from_value(
                     Some(value.to_owned()),
                     PropertySource::Body,
                     line_number,
+                    span,
                 ));
             }
             properties
@@ -183,6 +196,7 @@ impl Component {
             events,
             children,
             section.line_number,
+            section.span,
         ))
     }
 
@@ -203,6 +217,7 @@ impl Component {
                 events: vec![],
                 children: vec![],
                 line_number,
+                span: Default::default(),
             }),
             ftd::ast::VariableValue::List { value, line_number } => {
                 let mut children = vec![];
@@ -217,6 +232,7 @@ impl Component {
                     events: vec![],
                     children,
                     line_number,
+                    span: Default::default(),
                 })
             }
             ftd::ast::VariableValue::Record {
@@ -234,6 +250,7 @@ impl Component {
                         source: ftd::ast::PropertySource::Caption,
                         condition: None,
                         line_number,
+                        span: Default::default(),
                     });
                 }
                 for header in headers.0.iter() {
@@ -251,6 +268,7 @@ impl Component {
                         },
                         condition: header.condition.to_owned(),
                         line_number,
+                        span: Default::default(),
                     });
                 }
                 if let Some(body) = body {
@@ -258,6 +276,7 @@ impl Component {
                         Some(body.value),
                         PropertySource::Body,
                         body.line_number,
+                        Default::default(),
                     ));
                 }
 
@@ -282,6 +301,7 @@ impl Component {
                     events,
                     children,
                     line_number,
+                    span: Default::default(),
                 })
             }
             ftd::ast::VariableValue::String { value, line_number } => Ok(ftd::ast::Component {
@@ -290,12 +310,14 @@ impl Component {
                     Some(value),
                     PropertySource::Caption,
                     line_number,
+                    Default::default(),
                 )],
                 iteration: None,
                 condition: None,
                 events: vec![],
                 children: vec![],
                 line_number,
+                span: Default::default(),
             }),
         }
     }
@@ -313,6 +335,8 @@ pub struct Property {
     pub source: PropertySource,
     pub condition: Option<String>,
     pub line_number: usize,
+    #[serde(default, skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
 }
 
 impl Property {
@@ -325,12 +349,14 @@ impl Property {
         source: PropertySource,
         condition: Option<String>,
         line_number: usize,
+        span: ftd::p11::Span,
     ) -> Property {
         Property {
             value,
             source,
             condition,
             line_number,
+            span,
         }
     }
 
@@ -343,10 +369,11 @@ impl Property {
             || header.get_key().eq(ftd::ast::utils::LOOP)
             || Event::get_event_name(header.get_key().as_str()).is_some()
         {
-            return ftd::ast::parse_error(
-                format!("Header is not property, found `{:?}`", header),
+            return ftd::ast::parse_error_at(
+                format!("Header is not property, found `{}`", header.get_key()),
                 doc_id,
                 header.get_line_number(),
+                header.get_span(),
             );
         }
 
@@ -357,12 +384,18 @@ impl Property {
             source,
             header.get_condition(),
             header.get_line_number(),
+            header.get_span(),
         ))
     }
 
-    fn from_value(value: Option<String>, source: PropertySource, line_number: usize) -> Property {
+    fn from_value(
+        value: Option<String>,
+        source: PropertySource,
+        line_number: usize,
+        span: ftd::p11::Span,
+    ) -> Property {
         let value = ftd::ast::VariableValue::from_value(&value, line_number);
-        Property::new(value, source, None, line_number)
+        Property::new(value, source, None, line_number, span)
     }
 }
 
@@ -395,14 +428,17 @@ pub struct Loop {
     pub on: String,
     pub alias: String,
     pub line_number: usize,
+    #[serde(default, skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
 }
 
 impl Loop {
-    fn new(on: &str, alias: &str, line_number: usize) -> Loop {
+    fn new(on: &str, alias: &str, line_number: usize, span: ftd::p11::Span) -> Loop {
         Loop {
             on: on.to_string(),
             alias: alias.to_string(),
             line_number,
+            span,
         }
     }
 
@@ -453,6 +489,7 @@ impl Loop {
             on.as_str(),
             alias.as_str(),
             loop_header.line_number,
+            Default::default(),
         )))
     }
 
@@ -473,31 +510,34 @@ impl Loop {
                 message: "Loop statement is blank".to_string(),
                 doc_id: doc_id.to_string(),
                 line_number: loop_header.get_line_number(),
+                span: Some(loop_header.get_span()),
             })?;
 
         let (on, alias) = ftd::ast::utils::split_at(loop_statement.as_str(), ftd::ast::utils::AS);
 
         if !on.starts_with(ftd::ast::utils::REFERENCE) {
-            return ftd::ast::parse_error(
+            return ftd::ast::parse_error_at(
                 format!(
                     "Loop should be on some reference, found: `{}`. Help: use `${}` instead",
                     on, on
                 ),
                 doc_id,
                 loop_header.get_line_number(),
+                loop_header.get_value_span(),
             );
         }
 
         let alias = {
             if let Some(alias) = alias {
                 if !alias.starts_with(ftd::ast::utils::REFERENCE) {
-                    return ftd::ast::parse_error(
+                    return ftd::ast::parse_error_at(
                         format!(
                             "Loop alias should start with reference, found: `{}`. Help: use `${}` instead",
                             alias, alias
                         ),
                         doc_id,
                         loop_header.get_line_number(),
+                        loop_header.get_value_span(),
                     );
                 }
                 alias
@@ -512,6 +552,7 @@ impl Loop {
             on.as_str(),
             alias.as_str(),
             loop_header.get_line_number(),
+            loop_header.get_span(),
         )))
     }
 }
@@ -521,14 +562,17 @@ pub struct Event {
     pub name: String,
     pub action: String,
     pub line_number: usize,
+    #[serde(default, skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
 }
 
 impl Event {
-    fn new(name: &str, action: &str, line_number: usize) -> Event {
+    fn new(name: &str, action: &str, line_number: usize, span: ftd::p11::Span) -> Event {
         Event {
             name: name.to_string(),
             action: action.to_string(),
             line_number,
+            span,
         }
     }
 
@@ -567,6 +611,7 @@ impl Event {
             event_name.as_str(),
             action.as_str(),
             header.line_number,
+            Default::default(),
         )))
     }
 
@@ -591,12 +636,14 @@ impl Event {
             message: "Event cannot be empty".to_string(),
             doc_id: doc_id.to_string(),
             line_number: header.get_line_number(),
+            span: Some(header.get_span()),
         })?;
 
         Ok(Some(Event::new(
             event_name.as_str(),
             action.as_str(),
             header.get_line_number(),
+            header.get_span(),
         )))
     }
 }
@@ -48,9 +48,10 @@ impl Function {
 
     pub(crate) fn from_p1(section: &ftd::p11::Section, doc_id: &str) -> ftd::ast::Result<Function> {
         let function_name = Self::function_name(section).ok_or(ftd::ast::Error::Parse {
-            message: format!("Section is not function section, found `{:?}`", section),
+            message: format!("Section is not function section, found `{}`", section.name),
             doc_id: doc_id.to_string(),
             line_number: section.line_number,
+            span: Some(section.span),
         })?;
         let kind = ftd::ast::VariableKind::get_kind(
             section.kind.as_ref().unwrap().as_str(),
@@ -65,6 +66,7 @@ impl Function {
             ),
             doc_id: doc_id.to_string(),
             line_number: section.line_number,
+            span: Some(section.span),
         })?;
         Ok(Function::new(
             function_name.as_str(),
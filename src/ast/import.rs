@@ -21,20 +21,22 @@ impl Import {
 
     pub(crate) fn from_p1(section: &ftd::p11::Section, doc_id: &str) -> ftd::ast::Result<Import> {
         if !Self::is_import(section) {
-            return ftd::ast::parse_error(
-                format!("Section is not import section, found `{:?}`", section),
+            return ftd::ast::parse_error_at(
+                format!("Section is not import section, found `{}`", section.name),
                 doc_id,
                 section.line_number,
+                section.span,
             );
         }
         if !section.sub_sections.is_empty() {
-            return ftd::ast::parse_error(
+            return ftd::ast::parse_error_at(
                 format!(
-                    "Subsection not expected for import statement `{:?}`",
-                    section
+                    "Subsection not expected for import statement `{}`",
+                    section.name
                 ),
                 doc_id,
                 section.line_number,
+                section.span,
             );
         }
         match &section.caption {
@@ -48,13 +50,11 @@ impl Import {
                     section.line_number,
                 ))
             }
-            t => ftd::ast::parse_error(
-                format!(
-                    "Expected value in caption for import statement, found: `{:?}`",
-                    t
-                ),
+            _ => ftd::ast::parse_error_at(
+                "Expected value in caption for import statement",
                 doc_id,
                 section.line_number,
+                section.span,
             ),
         }
     }
@@ -217,13 +217,26 @@ impl VariableValue {
         match self {
             VariableValue::String { value, .. } => Ok(value.to_string()),
             t => ftd::ast::parse_error(
-                format!("Expect Variable value string, found: `{:?}`", t),
+                format!("Expected string, found: {}", t.describe()),
                 doc_id,
                 t.line_number(),
             ),
         }
     }
 
+    /// Short description of the value for error messages, e.g. ``record `foo` ``.
+    pub fn describe(&self) -> String {
+        match self {
+            VariableValue::Optional { value, .. } => match value.as_ref() {
+                Some(value) => value.describe(),
+                None => "null".to_string(),
+            },
+            VariableValue::List { .. } => "list".to_string(),
+            VariableValue::Record { name, .. } => format!("record `{}`", name),
+            VariableValue::String { value, .. } => format!("`{}`", value),
+        }
+    }
+
     pub fn caption(&self) -> Option<String> {
         match self {
             VariableValue::String { value, .. } => Some(value.to_string()),
@@ -268,7 +281,7 @@ impl VariableValue {
         match self {
             VariableValue::List { value, .. } => Ok(value),
             t => ftd::ast::parse_error(
-                format!("Expected list, found: `{:?}`", t),
+                format!("Expected list, found: {}", t.describe()),
                 doc_name,
                 t.line_number(),
             ),
@@ -305,7 +318,7 @@ impl VariableValue {
                 line_number,
             } => Ok((name, caption, headers, body, values, *line_number)),
             t => ftd::ast::parse_error(
-                format!("Expected Record, found: `{:?}`", t),
+                format!("Expected record, found: {}", t.describe()),
                 doc_id,
                 self.line_number(),
             ),
@@ -361,14 +374,14 @@ impl VariableValue {
                         Ok(self)
                     } else {
                         ftd::ast::parse_error(
-                            format!("Expected List found: `{:?}`", self),
+                            format!("Expected list, found: {}", self.describe()),
                             doc_id,
                             line_number,
                         )
                     }
                 } else {
                     ftd::ast::parse_error(
-                        format!("Expected List found: `{:?}`", self),
+                        format!("Expected list, found: {}", self.describe()),
                         doc_id,
                         line_number,
                     )
@@ -385,7 +398,12 @@ impl VariableValue {
         let values = section
             .sub_sections
             .iter()
-            .map(|v| (v.name.to_string(), VariableValue::from_p1(v, doc_id)))
+            .map(|v| {
+                (
+                    VariableValue::list_item_key(v),
+                    VariableValue::from_p1(v, doc_id),
+                )
+            })
             .collect_vec();
 
         let caption = section
@@ -453,6 +471,17 @@ impl VariableValue {
         }
     }
 
+    /// The key of a list item section. An item of a list of list is written as
+    /// `-- integer list:`, which the parser reads as section `list` of kind `integer`.
+    fn list_item_key(section: &ftd::p11::Section) -> String {
+        match section.kind {
+            Some(ref kind) if VariableModifier::is_list_from_expr(section.name.as_str()) => {
+                format!("{} {}", kind, section.name)
+            }
+            _ => section.name.to_string(),
+        }
+    }
+
     pub(crate) fn from_p1_header(header: &ftd::p11::Header, doc_id: &str) -> VariableValue {
         use itertools::Itertools;
 
@@ -467,7 +496,12 @@ impl VariableValue {
             }) => VariableValue::List {
                 value: section
                     .iter()
-                    .map(|v| (v.name.to_string(), VariableValue::from_p1(v, doc_id)))
+                    .map(|v| {
+                        (
+                            VariableValue::list_item_key(v),
+                            VariableValue::from_p1(v, doc_id),
+                        )
+                    })
                     .collect_vec(),
                 line_number: *line_number,
             },
@@ -492,6 +526,8 @@ impl VariableValue {
 pub struct Condition {
     pub expression: String,
     pub line_number: usize,
+    #[serde(default, skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
 }
 
 impl Condition {
@@ -499,9 +535,15 @@ impl Condition {
         Condition {
             expression: expression.to_string(),
             line_number,
+            span: Default::default(),
         }
     }
 
+    pub(crate) fn with_span(mut self, span: ftd::p11::Span) -> Condition {
+        self.span = span;
+        self
+    }
+
     pub(crate) fn from_ast_headers(
         headers: &HeaderValues,
         doc_id: &str,
@@ -542,12 +584,13 @@ impl Condition {
             message: "`if` condition must contain expression".to_string(),
             doc_id: doc_id.to_string(),
             line_number: condition.get_line_number(),
+            span: Some(condition.get_span()),
         })?;
 
-        Ok(Some(Condition::new(
-            expression.as_str(),
-            condition.get_line_number(),
-        )))
+        Ok(Some(
+            Condition::new(expression.as_str(), condition.get_line_number())
+                .with_span(condition.get_span()),
+        ))
     }
 }
 
@@ -54,9 +54,10 @@ impl AST {
             AST::ComponentInvocation(ftd::ast::Component::from_p1(section, doc_id)?)
         } else {
             return Err(ftd::ast::Error::Parse {
-                message: format!("Invalid AST, found: `{:?}`", section),
+                message: format!("Invalid AST, found: `{}`", section.name),
                 doc_id: doc_id.to_string(),
                 line_number: section.line_number,
+                span: Some(section.span),
             });
         })
     }
@@ -74,12 +75,30 @@ impl AST {
         }
     }
 
+    /// What kind of item this is, for error messages.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            AST::Import(_) => "import",
+            AST::Record(_) => "record",
+            AST::OrType(_) => "or-type",
+            AST::VariableDefinition(_) => "variable definition",
+            AST::VariableInvocation(_) => "variable invocation",
+            AST::ComponentDefinition(_) => "component definition",
+            AST::ComponentInvocation(_) => "component invocation",
+            AST::FunctionDefinition(_) => "function",
+        }
+    }
+
     pub fn get_record(self, doc_id: &str) -> ftd::ast::Result<ftd::ast::Record> {
         if let ftd::ast::AST::Record(r) = self {
             return Ok(r);
         }
         ftd::ast::parse_error(
-            format!("`{:?}` is not a record", self),
+            format!(
+                "`{}` is not a record, found {}",
+                self.name(),
+                self.describe()
+            ),
             doc_id,
             self.line_number(),
         )
@@ -90,7 +109,11 @@ impl AST {
             return Ok(o);
         }
         ftd::ast::parse_error(
-            format!("`{:?}` is not a or-type", self),
+            format!(
+                "`{}` is not an or-type, found {}",
+                self.name(),
+                self.describe()
+            ),
             doc_id,
             self.line_number(),
         )
@@ -101,7 +124,11 @@ impl AST {
             return Ok(r);
         }
         ftd::ast::parse_error(
-            format!("`{:?}` is not a function", self),
+            format!(
+                "`{}` is not a function, found {}",
+                self.name(),
+                self.describe()
+            ),
             doc_id,
             self.line_number(),
         )
@@ -115,7 +142,11 @@ impl AST {
             return Ok(v);
         }
         ftd::ast::parse_error(
-            format!("`{:?}` is not a variable definition", self),
+            format!(
+                "`{}` is not a variable definition, found {}",
+                self.name(),
+                self.describe()
+            ),
             doc_id,
             self.line_number(),
         )
@@ -129,7 +160,11 @@ impl AST {
             return Ok(v);
         }
         ftd::ast::parse_error(
-            format!("`{:?}` is not a variable definition", self),
+            format!(
+                "`{}` is not a variable invocation, found {}",
+                self.name(),
+                self.describe()
+            ),
             doc_id,
             self.line_number(),
         )
@@ -143,7 +178,11 @@ impl AST {
             return Ok(v);
         }
         ftd::ast::parse_error(
-            format!("`{:?}` is not a component definition", self),
+            format!(
+                "`{}` is not a component definition, found {}",
+                self.name(),
+                self.describe()
+            ),
             doc_id,
             self.line_number(),
         )
@@ -154,7 +193,11 @@ impl AST {
             return Ok(v);
         }
         ftd::ast::parse_error(
-            format!("`{:?}` is not a component definition", self),
+            format!(
+                "`{}` is not a component invocation, found {}",
+                self.name(),
+                self.describe()
+            ),
             doc_id,
             self.line_number(),
         )
@@ -211,6 +254,8 @@ impl AST {
 /// Only '/' comments are ignored here.
 /// ';' comments are ignored inside the [`parser`] itself.
 ///
+/// Sections marked broken by [`ftd::p11::parse_with_recovery`] are ignored as well.
+///
 /// uses [`Section::remove_comments()`] and [`Subsection::remove_comments()`] to remove comments
 /// in sections and subsections accordingly.
 ///
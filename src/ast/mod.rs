@@ -34,6 +34,7 @@ pub enum Error {
         message: String,
         doc_id: String,
         line_number: usize,
+        span: Option<ftd::p11::Span>,
     },
 
     #[error("ParseBoolError: {}", _0)]
@@ -50,5 +51,24 @@ where
         message: m.into(),
         doc_id: doc_id.to_string(),
         line_number,
+        span: None,
+    })
+}
+
+/// Same as [`parse_error`], but points at the token `span` refers to.
+pub fn parse_error_at<T, S1>(
+    m: S1,
+    doc_id: &str,
+    line_number: usize,
+    span: ftd::p11::Span,
+) -> ftd::ast::Result<T>
+where
+    S1: Into<String>,
+{
+    Err(Error::Parse {
+        message: m.into(),
+        doc_id: doc_id.to_string(),
+        line_number,
+        span: Some(span),
     })
 }
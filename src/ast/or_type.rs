@@ -22,10 +22,11 @@ impl OrType {
 
     pub(crate) fn from_p1(section: &ftd::p11::Section, doc_id: &str) -> ftd::ast::Result<OrType> {
         if !Self::is_or_type(section) {
-            return ftd::ast::parse_error(
-                format!("Section is not or-type section, found `{:?}`", section),
+            return ftd::ast::parse_error_at(
+                format!("Section is not or-type section, found `{}`", section.name),
                 doc_id,
                 section.line_number,
+                section.span,
             );
         }
         let mut variants = vec![];
@@ -51,13 +52,14 @@ impl ftd::ast::Field {
         doc_id: &str,
     ) -> ftd::ast::Result<ftd::ast::Field> {
         if !ftd::ast::VariableDefinition::is_variable_definition(section) {
-            return ftd::ast::parse_error(
+            return ftd::ast::parse_error_at(
                 format!(
-                    "Section is not or-type variant section, found `{:?}`",
-                    section
+                    "Section is not or-type variant section, found `{}`",
+                    section.name
                 ),
                 doc_id,
                 section.line_number,
+                section.span,
             );
         }
 
@@ -23,10 +23,11 @@ impl Record {
 
     pub(crate) fn from_p1(section: &ftd::p11::Section, doc_id: &str) -> ftd::ast::Result<Record> {
         if !Self::is_record(section) {
-            return ftd::ast::parse_error(
-                format!("Section is not record section, found `{:?}`", section),
+            return ftd::ast::parse_error_at(
+                format!("Section is not record section, found `{}`", section.name),
                 doc_id,
                 section.line_number,
+                section.span,
             );
         }
 
@@ -59,10 +60,11 @@ impl Field {
 
     pub(crate) fn from_header(header: &ftd::p11::Header, doc_id: &str) -> ftd::ast::Result<Field> {
         if !Self::is_field(header) {
-            return ftd::ast::parse_error(
-                format!("Header is not argument, found `{:?}`", header),
+            return ftd::ast::parse_error_at(
+                format!("Header is not argument, found `{}`", header.get_key()),
                 doc_id,
                 header.get_line_number(),
+                header.get_span(),
             );
         }
 
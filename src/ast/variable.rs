@@ -44,13 +44,14 @@ impl VariableDefinition {
         doc_id: &str,
     ) -> ftd::ast::Result<VariableDefinition> {
         if !Self::is_variable_definition(section) {
-            return ftd::ast::parse_error(
+            return ftd::ast::parse_error_at(
                 format!(
-                    "Section is not variable definition section, found `{:?}`",
-                    section
+                    "Section is not variable definition section, found `{}`",
+                    section.name
                 ),
                 doc_id,
                 section.line_number,
+                section.span,
             );
         }
 
@@ -122,13 +123,14 @@ impl VariableInvocation {
         doc_id: &str,
     ) -> ftd::ast::Result<VariableInvocation> {
         if !Self::is_variable_invocation(section) {
-            return ftd::ast::parse_error(
+            return ftd::ast::parse_error_at(
                 format!(
-                    "Section is not variable invocation section, found `{:?}`",
-                    section
+                    "Section is not variable invocation section, found `{}`",
+                    section.name
                 ),
                 doc_id,
                 section.line_number,
+                section.span,
             );
         }
 
@@ -178,10 +180,14 @@ impl VariableFlags {
         let kv = match header {
             ftd::p11::Header::KV(kv) => kv,
             ftd::p11::Header::Section(s) => {
-                return ftd::ast::parse_error(
-                    format!("Expected the boolean value for flag, found: `{:?}`", s),
+                return ftd::ast::parse_error_at(
+                    format!(
+                        "Expected the boolean value for flag, found section: `{}`",
+                        s.key
+                    ),
                     doc_id,
                     header.get_line_number(),
+                    header.get_span(),
                 )
             }
         };
@@ -196,6 +202,7 @@ impl VariableFlags {
                             .to_string(),
                         doc_id: doc_id.to_string(),
                         line_number: kv.line_number,
+                        span: Some(kv.span),
                     })?
                     .parse::<bool>()?;
                 if value {
@@ -204,9 +211,12 @@ impl VariableFlags {
                     Ok(VariableFlags::new())
                 }
             }
-            t => {
-                ftd::ast::parse_error(format!("Unknown flag found`{}`", t), doc_id, kv.line_number)
-            }
+            t => ftd::ast::parse_error_at(
+                format!("Unknown flag found`{}`", t),
+                doc_id,
+                kv.line_number,
+                kv.span,
+            ),
         }
     }
 }
@@ -232,6 +242,7 @@ impl Processor {
                     message: "Processor statement is blank".to_string(),
                     doc_id: doc_id.to_string(),
                     line_number: processor_header.get_line_number(),
+                    span: Some(processor_header.get_span()),
                 })?;
 
         Ok(Some(processor_statement))
@@ -0,0 +1,34 @@
+//! Language server for `.ftd` files, over stdin and stdout.
+//!
+//! Usage: `ftd-lsp [ROOT]`. Imports are looked up in `ROOT`, the current directory by default,
+//! see `ftd::lsp::directory`.
+
+pub fn main() {
+    let root = match std::env::args().nth(1) {
+        Some(root) => std::path::PathBuf::from(root),
+        None => std::env::current_dir().expect("cannot read current directory"),
+    };
+    let mut server = ftd::lsp::Server::new(root.clone(), ftd::lsp::directory(root));
+
+    let stdin = std::io::stdin();
+    let mut stdin = stdin.lock();
+    let stdout = std::io::stdout();
+    let mut stdout = stdout.lock();
+    loop {
+        let messages = match ftd::lsp::read_message(&mut stdin) {
+            Ok(Some(message)) => server.handle(message),
+            Ok(None) => break,
+            // a malformed message has been consumed, so the next one can still be read
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                vec![ftd::lsp::parse_error(&e)]
+            }
+            Err(e) => panic!("failed to read message: {}", e),
+        };
+        for message in messages {
+            ftd::lsp::write_message(&mut stdout, &message).expect("failed to write message");
+        }
+        if let Some(code) = server.exit_code() {
+            std::process::exit(code);
+        }
+    }
+}
@@ -1,6 +1,12 @@
 static SYNTAX_DIR: include_dir::Dir<'_> = include_dir::include_dir!("syntax");
 pub const DEFAULT_THEME: &str = "base16-ocean.dark";
 
+/// Prefix of the css classes used by class based highlighting, see `Registry::theme_css`.
+pub const CLASS_PREFIX: &str = "ft_syntax_";
+pub const CLASS_STYLE: syntect::html::ClassStyle = syntect::html::ClassStyle::SpacedPrefixed {
+    prefix: CLASS_PREFIX,
+};
+
 pub static SS: once_cell::sync::Lazy<syntect::parsing::SyntaxSet> =
     once_cell::sync::Lazy::new(|| {
         let mut builder = syntect::parsing::SyntaxSet::load_defaults_newlines().into_builder();
@@ -26,6 +32,75 @@ pub static KNOWN_EXTENSIONS: once_cell::sync::Lazy<std::collections::HashSet<Str
 pub static TS: once_cell::sync::Lazy<syntect::highlighting::ThemeSet> =
     once_cell::sync::Lazy::new(syntect::highlighting::ThemeSet::load_defaults);
 
+/// Themes and grammars a host registers at runtime. They are looked up before the ones in `TS`
+/// and `SS`, so documents can use them as the `theme`, `dark-theme` and `lang` of `ftd.code`.
+#[derive(Debug, Default, Clone)]
+pub struct Registry {
+    themes: ftd::Map<syntect::highlighting::Theme>,
+    syntaxes: Option<syntect::parsing::SyntaxSet>,
+}
+
+impl Registry {
+    /// Registers every `.tmTheme` file in `dir`, by its file name without the extension.
+    pub fn add_themes(
+        &mut self,
+        dir: impl AsRef<std::path::Path>,
+    ) -> Result<(), syntect::LoadingError> {
+        let mut ts = syntect::highlighting::ThemeSet::new();
+        ts.add_from_folder(dir)?;
+        self.themes.extend(ts.themes);
+        Ok(())
+    }
+
+    pub fn add_theme(&mut self, name: &str, theme: syntect::highlighting::Theme) {
+        self.themes.insert(name.to_string(), theme);
+    }
+
+    /// Registers every `.sublime-syntax` file in `dir`, by its name and its file extensions.
+    pub fn add_syntaxes(
+        &mut self,
+        dir: impl AsRef<std::path::Path>,
+    ) -> Result<(), syntect::LoadingError> {
+        let mut builder = match self.syntaxes {
+            Some(ref ss) => ss.clone().into_builder(),
+            // the grammars build on the default ones, e.g. `scope:source.js`
+            None => SS.clone().into_builder(),
+        };
+        builder.add_from_folder(dir, true)?;
+        self.syntaxes = Some(builder.build());
+        Ok(())
+    }
+
+    pub fn theme(&self, name: &str) -> Option<&syntect::highlighting::Theme> {
+        self.themes.get(name).or_else(|| TS.themes.get(name))
+    }
+
+    /// The grammar for `lang`, found by file extension or else by name, and the set it belongs
+    /// to. Falls back to plain text.
+    pub fn syntax(
+        &self,
+        lang: &str,
+    ) -> (
+        &syntect::parsing::SyntaxSet,
+        &syntect::parsing::SyntaxReference,
+    ) {
+        for ss in self.syntaxes.iter().chain(std::iter::once(&*SS)) {
+            if let Some(syntax) = ss
+                .find_syntax_by_extension(lang)
+                .or_else(|| ss.find_syntax_by_token(lang))
+            {
+                return (ss, syntax);
+            }
+        }
+        (&SS, SS.find_syntax_plain_text())
+    }
+
+    /// The css for class based highlighting with `theme`, `None` if the theme is not known.
+    pub fn theme_css(&self, theme: &str) -> Option<String> {
+        syntect::html::css_for_theme_with_class_style(self.theme(theme)?, CLASS_STYLE).ok()
+    }
+}
+
 pub fn code(code: &str, ext: &str, theme: &str, doc_id: &str) -> ftd::p1::Result<String> {
     let syntax = SS
         .find_syntax_by_extension(ext)
@@ -55,3 +130,45 @@ pub fn code(code: &str, ext: &str, theme: &str, doc_id: &str) -> ftd::p1::Result
             .replacen('\n', "", 1),
     )
 }
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn syntax_including_default_syntax() {
+        let dir = std::env::temp_dir().join("ftd-code-registry-syntaxes");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("embedded-js.sublime-syntax"),
+            indoc::indoc!(
+                "
+                %YAML 1.2
+                ---
+                name: Embedded JS
+                file_extensions: [ejs]
+                scope: source.ejs
+                contexts:
+                  main:
+                    - include: scope:source.js
+                "
+            ),
+        )
+        .unwrap();
+
+        let mut registry = super::Registry::default();
+        registry.add_syntaxes(&dir).unwrap();
+        let (ss, syntax) = registry.syntax("ejs");
+        assert_eq!(syntax.name, "Embedded JS");
+
+        let mut parse_state = syntect::parsing::ParseState::new(syntax);
+        let ops = parse_state.parse_line("var a = 1;\n", ss).unwrap();
+        assert!(
+            ops.iter().any(|(_, op)| matches!(
+                op,
+                syntect::parsing::ScopeStackOp::Push(scope)
+                    if scope.build_string().starts_with("storage")
+            )),
+            "{:?}",
+            ops
+        );
+    }
+}
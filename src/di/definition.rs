@@ -20,7 +20,7 @@ impl Definition {
     ) -> ftd::di::Result<Definition> {
         if !Self::is_definition(section) {
             return ftd::di::parse_error(
-                format!("Section is not `definition`, found `{:?}`", section),
+                format!("Section is not `definition`, found `{}`", section.name),
                 doc_id,
                 section.line_number,
             );
@@ -34,8 +34,8 @@ impl Definition {
         } else {
             return ftd::di::parse_error(
                 format!(
-                    "Section is not `definition`, kind not found, found `{:?}`",
-                    section
+                    "Section is not `definition`, kind not found, found `{}`",
+                    section.name
                 ),
                 doc_id,
                 section.line_number,
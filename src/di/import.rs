@@ -15,7 +15,7 @@ impl Import {
     pub(crate) fn from_p1(section: &ftd::p11::Section, doc_id: &str) -> ftd::di::Result<Import> {
         if !Self::is_import(section) {
             return ftd::di::parse_error(
-                format!("Section is not import section, found `{:?}`", section),
+                format!("Section is not import section, found `{}`", section.name),
                 doc_id,
                 section.line_number,
             );
@@ -23,8 +23,8 @@ impl Import {
         if !section.sub_sections.is_empty() {
             return ftd::di::parse_error(
                 format!(
-                    "Subsection not expected for import statement `{:?}`",
-                    section
+                    "Subsection not expected for import statement `{}`",
+                    section.name
                 ),
                 doc_id,
                 section.line_number,
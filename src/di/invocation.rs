@@ -19,7 +19,7 @@ impl Invocation {
     ) -> ftd::di::Result<Invocation> {
         if !Self::is_invocation(section) {
             return ftd::di::parse_error(
-                format!("Section is not `invocation`, found `{:?}`", section),
+                format!("Section is not `invocation`, found `{}`", section.name),
                 doc_id,
                 section.line_number,
             );
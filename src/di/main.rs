@@ -28,7 +28,7 @@ impl DI {
             DI::Invocation(ftd::di::Invocation::from_p1(section, doc_id)?)
         } else {
             return Err(ftd::di::Error::ParseError {
-                message: format!("Invalid DI, found: `{:?}`", section),
+                message: format!("Invalid DI, found: `{}`", section.name),
                 doc_id: doc_id.to_string(),
                 line_number: section.line_number,
             });
@@ -14,7 +14,7 @@ impl Record {
     pub(crate) fn from_p1(section: &ftd::p11::Section, doc_id: &str) -> ftd::di::Result<Record> {
         if !Self::is_record(section) {
             return ftd::di::parse_error(
-                format!("Section is not record section, found `{:?}`", section),
+                format!("Section is not record section, found `{}`", section.name),
                 doc_id,
                 section.line_number,
             );
@@ -59,6 +59,7 @@ impl Field {
                 kind,
                 value,
                 condition,
+                ..
             }) => {
                 if condition.is_some() {
                     return ftd::di::parse_error(
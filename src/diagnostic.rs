@@ -0,0 +1,295 @@
+/// An error of the p11 → ast → interpreter2 → executor → html1 pipeline, reduced to what is
+/// needed to report it: the message, the document and the location in it.
+///
+/// Use [`Diagnostic::render`] to print it along with the offending line of the source, in the
+/// style of rustc:
+///
+/// ```text
+/// error: more than one header for key `padding`
+///   --> foo:3:1
+///    |
+///  3 | padding: 20
+///    | ^^^^^^^
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Diagnostic {
+    pub message: String,
+    pub doc_id: Option<String>,
+    pub line_number: Option<usize>,
+    pub span: Option<ftd::p11::Span>,
+    pub level: Level,
+}
+
+/// How bad a [`Diagnostic`] is: errors stop a document from being rendered, warnings (e.g. the
+/// lints of [`ftd::interpreter2::lint`]) do not.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Level {
+    #[default]
+    Error,
+    Warning,
+}
+
+impl Level {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        }
+    }
+}
+
+impl Diagnostic {
+    pub fn new(
+        message: &str,
+        doc_id: &str,
+        line_number: usize,
+        span: Option<ftd::p11::Span>,
+    ) -> Diagnostic {
+        Diagnostic {
+            message: message.to_string(),
+            doc_id: Some(doc_id.to_string()),
+            line_number: Some(line_number),
+            span,
+            level: Level::Error,
+        }
+    }
+
+    fn without_location(message: String) -> Diagnostic {
+        Diagnostic {
+            message,
+            ..Default::default()
+        }
+    }
+
+    /// `source` must be the content of the document `doc_id` refers to. If no span is known, the
+    /// whole line `line_number` is underlined.
+    pub fn render(&self, source: &str) -> String {
+        let mut out = format!("{}: {}", self.level.as_str(), self.message);
+        let span = match (self.span, self.line_number) {
+            (Some(span), _) if !span.is_empty() => span,
+            (_, Some(line_number)) if line_number > 0 => ftd::p11::Span::line(source, line_number),
+            _ => {
+                if let Some(ref doc_id) = self.doc_id {
+                    out.push_str(format!("\n  --> {}", doc_id).as_str());
+                }
+                return out;
+            }
+        };
+
+        let location = format!(
+            "{}:{}:{}",
+            self.doc_id.as_deref().unwrap_or_default(),
+            span.line,
+            span.column
+        );
+        let (line, offset) = match source.get(..span.offset) {
+            Some(before) if !span.is_empty() => {
+                let start = before.rfind('\n').map(|v| v + 1).unwrap_or_default();
+                let end = source[span.offset..]
+                    .find('\n')
+                    .map(|v| v + span.offset)
+                    .unwrap_or(source.len());
+                (&source[start..end], &source[start..span.offset])
+            }
+            _ => {
+                out.push_str(format!("\n  --> {}", location).as_str());
+                return out;
+            }
+        };
+
+        let gutter = " ".repeat(span.line.to_string().len());
+        let indent: String = offset
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        out.push_str(
+            format!(
+                "\n{gutter}--> {location}\n{gutter} |\n{} | {}\n{gutter} | {}{}",
+                span.line,
+                line.trim_end(),
+                indent,
+                "^".repeat(span.length),
+            )
+            .as_str(),
+        );
+        out
+    }
+}
+
+impl From<&ftd::p11::Error> for Diagnostic {
+    fn from(e: &ftd::p11::Error) -> Diagnostic {
+        use ftd::p11::Error;
+
+        match e {
+            Error::SectionNotFound {
+                doc_id,
+                line_number,
+            } => Diagnostic::new("section not found", doc_id, *line_number, None),
+            Error::MoreThanOneCaption {
+                doc_id,
+                line_number,
+                span,
+            } => Diagnostic::new("more than one caption", doc_id, *line_number, *span),
+            Error::ParseError {
+                message,
+                doc_id,
+                line_number,
+                span,
+            } => Diagnostic::new(message, doc_id, *line_number, *span),
+            Error::MoreThanOneHeader {
+                key,
+                doc_id,
+                line_number,
+                span,
+            } => Diagnostic::new(
+                format!("more than one header for key `{}`", key).as_str(),
+                doc_id,
+                *line_number,
+                *span,
+            ),
+            Error::HeaderNotFound {
+                key,
+                doc_id,
+                line_number,
+                span,
+            } => Diagnostic::new(
+                format!("header not found for key `{}`", key).as_str(),
+                doc_id,
+                *line_number,
+                *span,
+            ),
+            Error::ForbiddenUsage {
+                message,
+                doc_id,
+                line_number,
+            } => Diagnostic::new(
+                format!("forbidden usage: {}", message).as_str(),
+                doc_id,
+                *line_number,
+                None,
+            ),
+            Error::NotFound {
+                doc_id,
+                line_number,
+                key,
+            } => Diagnostic::new(
+                format!("key not found: {}", key).as_str(),
+                doc_id,
+                *line_number,
+                None,
+            ),
+            Error::MoreThanOneSubSections {
+                key,
+                doc_id,
+                line_number,
+            } => Diagnostic::new(
+                format!("got more than one sub-sections: {}", key).as_str(),
+                doc_id,
+                *line_number,
+                None,
+            ),
+            Error::Serde { .. } | Error::Syntect { .. } => {
+                Diagnostic::without_location(e.to_string())
+            }
+        }
+    }
+}
+
+impl From<&ftd::ast::Error> for Diagnostic {
+    fn from(e: &ftd::ast::Error) -> Diagnostic {
+        match e {
+            ftd::ast::Error::P1(e) => e.into(),
+            ftd::ast::Error::Parse {
+                message,
+                doc_id,
+                line_number,
+                span,
+            } => Diagnostic::new(message, doc_id, *line_number, *span),
+            ftd::ast::Error::ParseBool(_) => Diagnostic::without_location(e.to_string()),
+        }
+    }
+}
+
+impl From<&ftd::interpreter2::Error> for Diagnostic {
+    fn from(e: &ftd::interpreter2::Error) -> Diagnostic {
+        use ftd::interpreter2::Error;
+
+        match e {
+            Error::P1Error(e) => e.into(),
+            Error::ASTError(e) => e.into(),
+            Error::InvalidKind {
+                doc_id,
+                line_number,
+                message,
+                span,
+            } => Diagnostic::new(
+                format!("invalid kind: {}", message).as_str(),
+                doc_id,
+                *line_number,
+                *span,
+            ),
+            Error::ValueNotFound {
+                doc_id,
+                line_number,
+                message,
+                span,
+            } => Diagnostic::new(
+                format!("value not found: {}", message).as_str(),
+                doc_id,
+                *line_number,
+                *span,
+            ),
+            Error::ParseError {
+                message,
+                doc_id,
+                line_number,
+                span,
+            } => Diagnostic::new(message, doc_id, *line_number, *span),
+            _ => Diagnostic::without_location(e.to_string()),
+        }
+    }
+}
+
+impl From<&ftd::interpreter2::Lint> for Diagnostic {
+    fn from(lint: &ftd::interpreter2::Lint) -> Diagnostic {
+        Diagnostic {
+            level: lint.level,
+            ..Diagnostic::new(
+                format!("{} [{}]", lint.message, lint.id.as_str()).as_str(),
+                lint.doc_id.as_str(),
+                lint.line_number,
+                None,
+            )
+        }
+    }
+}
+
+impl From<&ftd::executor::Error> for Diagnostic {
+    fn from(e: &ftd::executor::Error) -> Diagnostic {
+        match e {
+            ftd::executor::Error::InterpreterError(e) => e.into(),
+            ftd::executor::Error::ParseError {
+                message,
+                doc_id,
+                line_number,
+                span,
+            } => Diagnostic::new(message, doc_id, *line_number, *span),
+            ftd::executor::Error::Syntect { .. } => Diagnostic::without_location(e.to_string()),
+        }
+    }
+}
+
+impl From<&ftd::html1::Error> for Diagnostic {
+    fn from(e: &ftd::html1::Error) -> Diagnostic {
+        match e {
+            ftd::html1::Error::InterpreterError(e) => e.into(),
+            ftd::html1::Error::ParseError {
+                message,
+                doc_id,
+                line_number,
+                span,
+            } => Diagnostic::new(message, doc_id, *line_number, *span),
+            ftd::html1::Error::EvalexprError(_) => Diagnostic::without_location(e.to_string()),
+        }
+    }
+}
@@ -1,46 +1,89 @@
-static SYNTAX_DIR: include_dir::Dir<'_> = include_dir::include_dir!("syntax");
-pub const DEFAULT_THEME: &str = "base16-ocean.dark";
-
-pub static SS: once_cell::sync::Lazy<syntect::parsing::SyntaxSet> =
-    once_cell::sync::Lazy::new(|| {
-        let mut builder = syntect::parsing::SyntaxSet::load_defaults_newlines().into_builder();
-        for f in SYNTAX_DIR.files() {
-            builder.add(
-                syntect::parsing::syntax_definition::SyntaxDefinition::load_from_str(
-                    f.contents_utf8().unwrap(),
-                    true,
-                    f.path().file_stem().and_then(|x| x.to_str()),
+pub const DEFAULT_THEME: &str = ftd::code::DEFAULT_THEME;
+
+/// How `ftd.code` is highlighted, set by the host.
+#[derive(Debug, Default, Clone)]
+pub struct CodeOptions {
+    /// Highlights using css classes instead of inline styles, so that the theme can be switched
+    /// using css. `ftd::code::Registry::theme_css` gives the css of a theme.
+    pub classes: bool,
+    pub registry: std::sync::Arc<ftd::code::Registry>,
+}
+
+impl PartialEq for CodeOptions {
+    fn eq(&self, other: &Self) -> bool {
+        self.classes == other.classes && std::sync::Arc::ptr_eq(&self.registry, &other.registry)
+    }
+}
+
+/// How the lines of an `ftd.code` are rendered. Each option wraps every line in a
+/// `ft_code_line` span, so that the lines can be styled using stable class names.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CodeLines {
+    /// Adds a `ft_code_line_number` span, counting from `start_line`, to each line.
+    pub show_line_numbers: bool,
+    /// Inclusive ranges of line numbers which get the `ft_code_highlight` class.
+    pub highlight_lines: Vec<(i64, i64)>,
+    pub start_line: Option<i64>,
+    /// Treats a leading `+` or `-` as a diff marker. The marker goes in a `ft_code_diff_marker`
+    /// span and the line gets the `ft_code_added` or `ft_code_removed` class.
+    pub diff: bool,
+}
+
+impl CodeLines {
+    fn is_plain(&self) -> bool {
+        self.eq(&Default::default())
+    }
+
+    fn is_highlighted(&self, line_number: i64) -> bool {
+        self.highlight_lines
+            .iter()
+            .any(|(start, end)| *start <= line_number && line_number <= *end)
+    }
+}
+
+/// Parses line ranges like `3-5,9`.
+pub fn parse_line_ranges(
+    ranges: &str,
+    doc_id: &str,
+    line_number: usize,
+) -> ftd::executor::Result<Vec<(i64, i64)>> {
+    let mut result = vec![];
+    for range in ranges.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+        let (start, end) = range.split_once('-').unwrap_or((range, range));
+        match (start.trim().parse::<i64>(), end.trim().parse::<i64>()) {
+            (Ok(start), Ok(end)) if start <= end => result.push((start, end)),
+            _ => {
+                return ftd::executor::utils::parse_error(
+                    format!("`{}` is not a valid line range", range),
+                    doc_id,
+                    line_number,
                 )
-                .unwrap(),
-            );
+            }
         }
-        builder.build()
-    });
-
-/*pub static KNOWN_EXTENSIONS: once_cell::sync::Lazy<std::collections::HashSet<String>> =
-once_cell::sync::Lazy::new(|| {
-    SS.syntaxes()
-        .iter()
-        .flat_map(|v| v.file_extensions.to_vec())
-        .collect()
-});*/
-
-pub static TS: once_cell::sync::Lazy<syntect::highlighting::ThemeSet> =
-    once_cell::sync::Lazy::new(syntect::highlighting::ThemeSet::load_defaults);
-
-pub fn code(code: &str, ext: &str, theme: &str, doc_id: &str) -> ftd::executor::Result<String> {
-    let syntax = SS
-        .find_syntax_by_extension(ext)
-        .unwrap_or_else(|| SS.find_syntax_plain_text());
-    if !TS.themes.contains_key(theme) {
-        return Err(ftd::executor::Error::ParseError {
-            message: format!("'{}' is not a valid theme", theme),
-            doc_id: doc_id.to_string(),
-            line_number: 0,
-        });
     }
+    Ok(result)
+}
 
-    let theme = &TS.themes[theme];
+pub fn code(
+    code: &str,
+    ext: &str,
+    theme: &str,
+    lines: &CodeLines,
+    options: &CodeOptions,
+    doc_id: &str,
+) -> ftd::executor::Result<String> {
+    let (ss, syntax) = options.registry.syntax(ext);
+    let theme = match options.registry.theme(theme) {
+        Some(theme) => theme,
+        None => {
+            return Err(ftd::executor::Error::ParseError {
+                message: format!("'{}' is not a valid theme", theme),
+                doc_id: doc_id.to_string(),
+                line_number: 0,
+                span: None,
+            })
+        }
+    };
 
     let code = code
         .lines()
@@ -51,9 +94,158 @@ pub fn code(code: &str, ext: &str, theme: &str, doc_id: &str) -> ftd::executor::
         .to_string()
         + "\n";
 
-    // TODO: handle various params
-    Ok(
-        syntect::html::highlighted_html_for_string(code.as_str(), &SS, syntax, theme)?
-            .replacen('\n', "", 1),
-    )
+    if lines.is_plain() && !options.classes {
+        // TODO: handle various params
+        return Ok(
+            syntect::html::highlighted_html_for_string(code.as_str(), ss, syntax, theme)?
+                .replacen('\n', "", 1),
+        );
+    }
+
+    let (mut output, mut highlighter) = if options.classes {
+        (
+            format!("<pre class=\"{}code\">", ftd::code::CLASS_PREFIX),
+            Highlighter::Classed {
+                parse_state: syntect::parsing::ParseState::new(syntax),
+                scope_stack: syntect::parsing::ScopeStack::new(),
+            },
+        )
+    } else {
+        let (output, background) = syntect::html::start_highlighted_html_snippet(theme);
+        (
+            output.replacen('\n', "", 1),
+            Highlighter::Inline {
+                highlighter: syntect::easy::HighlightLines::new(syntax, theme),
+                background,
+            },
+        )
+    };
+    let start_line = lines.start_line.unwrap_or(1);
+    for (idx, line) in syntect::util::LinesWithEndings::from(code.as_str()).enumerate() {
+        if lines.is_plain() {
+            output.push_str(highlighter.line(line, ss)?.as_str());
+            output.push('\n');
+            continue;
+        }
+
+        let line_number = start_line + idx as i64;
+        let mut classes = vec!["ft_code_line"];
+        let mut prefix = String::new();
+        if lines.show_line_numbers {
+            prefix.push_str(
+                format!("<span class=\"ft_code_line_number\">{}</span>", line_number).as_str(),
+            );
+        }
+
+        let mut line = line;
+        if lines.diff {
+            let marker = match line.chars().next() {
+                Some(c @ '+') => {
+                    classes.push("ft_code_added");
+                    Some(c)
+                }
+                Some(c @ '-') => {
+                    classes.push("ft_code_removed");
+                    Some(c)
+                }
+                _ => None,
+            };
+            if marker.is_some() {
+                line = &line[1..];
+            }
+            prefix.push_str(
+                format!(
+                    "<span class=\"ft_code_diff_marker\">{}</span>",
+                    marker.unwrap_or(' ')
+                )
+                .as_str(),
+            );
+        }
+        if lines.is_highlighted(line_number) {
+            classes.push("ft_code_highlight");
+        }
+
+        output.push_str(
+            format!(
+                "<span class=\"{}\">{}{}</span>\n",
+                classes.join(" "),
+                prefix,
+                highlighter.line(line, ss)?
+            )
+            .as_str(),
+        );
+    }
+    output.push_str("</pre>\n");
+    Ok(output)
+}
+
+enum Highlighter<'a> {
+    Inline {
+        highlighter: syntect::easy::HighlightLines<'a>,
+        background: syntect::highlighting::Color,
+    },
+    /// Uses css classes, prefixed with `ftd::code::CLASS_PREFIX`, instead of inline styles.
+    Classed {
+        parse_state: syntect::parsing::ParseState,
+        scope_stack: syntect::parsing::ScopeStack,
+    },
+}
+
+impl Highlighter<'_> {
+    /// Html of `line`, without the line ending, so that styling a line does not style the line
+    /// break. Spans that continue in the next line are closed at the end of the line, and
+    /// opened again at the start of the next one.
+    fn line(
+        &mut self,
+        line: &str,
+        ss: &syntect::parsing::SyntaxSet,
+    ) -> ftd::executor::Result<String> {
+        Ok(match self {
+            Highlighter::Inline {
+                highlighter,
+                background,
+            } => {
+                let regions = highlighter
+                    .highlight_line(line, ss)?
+                    .into_iter()
+                    .map(|(style, text)| (style, text.trim_end_matches('\n')))
+                    .collect::<Vec<_>>();
+                syntect::html::styled_line_to_highlighted_html(
+                    &regions[..],
+                    syntect::html::IncludeBackground::IfDifferent(*background),
+                )?
+            }
+            Highlighter::Classed {
+                parse_state,
+                scope_stack,
+            } => {
+                let open_scopes = scope_stack
+                    .as_slice()
+                    .iter()
+                    .map(|v| (0, syntect::parsing::ScopeStackOp::Push(*v)))
+                    .collect::<Vec<_>>();
+                let (open, _) = syntect::html::line_tokens_to_classed_spans(
+                    "",
+                    open_scopes.as_slice(),
+                    ftd::code::CLASS_STYLE,
+                    &mut syntect::parsing::ScopeStack::new(),
+                )?;
+                let ops = parse_state
+                    .parse_line(line, ss)
+                    .map_err(syntect::Error::from)?;
+                let (html, _) = syntect::html::line_tokens_to_classed_spans(
+                    line,
+                    ops.as_slice(),
+                    ftd::code::CLASS_STYLE,
+                    scope_stack,
+                )?;
+                format!(
+                    "{}{}{}",
+                    open,
+                    html.replace('\n', ""),
+                    "</span>".repeat(scope_stack.as_slice().len())
+                )
+            }
+        })
+    }
 }
@@ -2,6 +2,7 @@
 pub enum Element {
     Row(Row),
     Column(Column),
+    Grid(Box<Grid>),
     Text(Text),
     Integer(Text),
     Boolean(Text),
@@ -18,6 +19,7 @@ impl Element {
         match self {
             Element::Row(r) => Some(&r.common),
             Element::Column(c) => Some(&c.common),
+            Element::Grid(g) => Some(&g.common),
             Element::Text(t) => Some(&t.common),
             Element::Integer(i) => Some(&i.common),
             Element::Boolean(b) => Some(&b.common),
@@ -29,6 +31,23 @@ impl Element {
             Element::Null => None,
         }
     }
+
+    pub(crate) fn get_mut_common(&mut self) -> Option<&mut Common> {
+        match self {
+            Element::Row(r) => Some(&mut r.common),
+            Element::Column(c) => Some(&mut c.common),
+            Element::Grid(g) => Some(&mut g.common),
+            Element::Text(t) => Some(&mut t.common),
+            Element::Integer(i) => Some(&mut i.common),
+            Element::Boolean(b) => Some(&mut b.common),
+            Element::Decimal(d) => Some(&mut d.common),
+            Element::Image(i) => Some(&mut i.common),
+            Element::Code(c) => Some(&mut c.common),
+            Element::Iframe(i) => Some(&mut i.common),
+            Element::TextInput(i) => Some(&mut i.common),
+            Element::Null => None,
+        }
+    }
 }
 
 #[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
@@ -43,11 +62,23 @@ pub struct Column {
     pub common: Common,
 }
 
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct Grid {
+    pub template_columns: ftd::executor::Value<Vec<ftd::executor::GridTrack>>,
+    pub template_rows: ftd::executor::Value<Vec<ftd::executor::GridTrack>>,
+    pub template_areas: ftd::executor::Value<Vec<String>>,
+    pub column_gap: ftd::executor::Value<Option<ftd::executor::Length>>,
+    pub row_gap: ftd::executor::Value<Option<ftd::executor::Length>>,
+    pub container: Container,
+    pub common: Common,
+}
+
 #[derive(serde::Deserialize, Debug, PartialEq, Default, Clone, serde::Serialize)]
 pub struct Text {
     pub text: ftd::executor::Value<Rendered>,
     pub text_align: ftd::executor::Value<Option<ftd::executor::TextAlign>>,
     pub line_clamp: ftd::executor::Value<Option<i64>>,
+    pub markup: Vec<ftd::executor::Markup>,
     pub common: Common,
 }
 
@@ -70,9 +101,9 @@ pub struct ImageSrc {
 }
 
 impl ImageSrc {
-    fn from_values(
+    pub(crate) fn from_values(
         values: ftd::Map<ftd::interpreter2::PropertyValue>,
-        doc: &ftd::executor::TDoc,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::executor::Result<ImageSrc> {
         let light = {
@@ -82,11 +113,12 @@ impl ImageSrc {
                     message: "`light` field in ftd.image-src not found".to_string(),
                     doc_id: doc.name.to_string(),
                     line_number,
+                    span: None,
                 })?;
             ftd::executor::Value::new(
                 value
                     .clone()
-                    .resolve(&doc.itdoc(), line_number)?
+                    .resolve(doc, line_number)?
                     .string(doc.name, line_number)?,
                 Some(line_number),
                 vec![value.into_property(ftd::interpreter2::PropertySource::header("light"))],
@@ -98,7 +130,7 @@ impl ImageSrc {
                 ftd::executor::Value::new(
                     value
                         .clone()
-                        .resolve(&doc.itdoc(), line_number)?
+                        .resolve(doc, line_number)?
                         .string(doc.name, line_number)?,
                     Some(line_number),
                     vec![value.into_property(ftd::interpreter2::PropertySource::header("dark"))],
@@ -115,6 +147,9 @@ impl ImageSrc {
 #[derive(serde::Deserialize, Debug, PartialEq, Default, Clone, serde::Serialize)]
 pub struct Code {
     pub text: ftd::executor::Value<Rendered>,
+    /// `text` highlighted using `dark-theme`, shown in dark mode, like the `dark` of
+    /// `ftd::executor::Color`. Same as `text` if there is no `dark-theme`.
+    pub dark_text: ftd::executor::Value<Rendered>,
     pub text_align: ftd::executor::Value<Option<ftd::executor::TextAlign>>,
     pub line_clamp: ftd::executor::Value<Option<i64>>,
     pub common: Common,
@@ -160,17 +195,87 @@ pub fn code_from_properties(
         line_number,
     )?;
 
+    let lines = ftd::executor::code::CodeLines {
+        show_line_numbers: ftd::executor::value::bool_with_default(
+            "show-line-numbers",
+            properties,
+            arguments,
+            false,
+            doc,
+            line_number,
+        )?
+        .value,
+        highlight_lines: match ftd::executor::value::optional_string(
+            "highlight-lines",
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?
+        .value
+        {
+            Some(ranges) => {
+                ftd::executor::code::parse_line_ranges(ranges.as_str(), doc.name, line_number)?
+            }
+            None => vec![],
+        },
+        start_line: ftd::executor::value::optional_i64(
+            "start-line",
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?
+        .value,
+        diff: ftd::executor::value::bool_with_default(
+            "diff",
+            properties,
+            arguments,
+            false,
+            doc,
+            line_number,
+        )?
+        .value,
+    };
+
+    let dark_theme = ftd::executor::value::optional_string(
+        "dark-theme",
+        properties,
+        arguments,
+        doc,
+        line_number,
+    )?;
+
+    let code = text.value.unwrap_or_default();
     let text = ftd::executor::Value::new(
-        ftd::executor::element::code_with_theme(
-            text.value.unwrap_or_default().as_str(),
+        ftd::executor::element::code_with_lines(
+            code.as_str(),
             lang.value.as_str(),
             theme.value.as_str(),
+            &lines,
+            doc.code,
             doc.name,
         )?,
         text.line_number,
         text.properties,
     );
 
+    let dark_text = match dark_theme.value {
+        Some(dark_theme) => ftd::executor::Value::new(
+            ftd::executor::element::code_with_lines(
+                code.as_str(),
+                lang.value.as_str(),
+                dark_theme.as_str(),
+                &lines,
+                doc.code,
+                doc.name,
+            )?,
+            Some(line_number),
+            text.properties.clone(),
+        ),
+        None => text.clone(),
+    };
+
     let common = common_from_properties(
         properties,
         events,
@@ -183,6 +288,7 @@ pub fn code_from_properties(
 
     Ok(Code {
         text,
+        dark_text,
         text_align: ftd::executor::TextAlign::optional_text_align(
             properties,
             arguments,
@@ -303,10 +409,23 @@ pub fn markup_inline(s: &str) -> Rendered {
     }
 }
 
-pub fn code_with_theme(
+pub fn markup_inline_with_options(
+    s: &str,
+    options: &ftd::markup::MarkupOptions,
+    code: &ftd::executor::CodeOptions,
+) -> Rendered {
+    Rendered {
+        original: s.to_string(),
+        rendered: ftd::markup::markup_inline_with_options(s, options, code),
+    }
+}
+
+pub fn code_with_lines(
     code: &str,
     ext: &str,
     theme: &str,
+    lines: &ftd::executor::code::CodeLines,
+    options: &ftd::executor::CodeOptions,
     doc_id: &str,
 ) -> ftd::executor::Result<Rendered> {
     Ok(Rendered {
@@ -317,6 +436,8 @@ pub fn code_with_theme(
                 .as_str(),
             ext,
             theme,
+            lines,
+            options,
             doc_id,
         )?,
     })
@@ -392,6 +513,7 @@ pub struct Common {
     pub data_id: String,
     pub line_number: usize,
     pub condition: Option<ftd::interpreter2::Expression>,
+    pub loop_template: Option<LoopTemplate>,
     pub overflow: ftd::executor::Value<Option<ftd::executor::Overflow>>,
     pub overflow_x: ftd::executor::Value<Option<ftd::executor::Overflow>>,
     pub overflow_y: ftd::executor::Value<Option<ftd::executor::Overflow>>,
@@ -400,6 +522,26 @@ pub struct Common {
     pub text_transform: ftd::executor::Value<Option<ftd::executor::TextTransform>>,
     pub sticky: ftd::executor::Value<Option<bool>>,
     pub border_style: ftd::executor::Value<Option<ftd::executor::BorderStyle>>,
+    pub grid_area: ftd::executor::Value<Option<String>>,
+    pub grid_column: ftd::executor::Value<Option<String>>,
+    pub grid_row: ftd::executor::Value<Option<String>>,
+}
+
+/// An element rendered from a loop on a mutable list, which the runtime clones for every item
+/// of the list when it changes. The template is placed after the other children of its parent
+/// and is moved to `position` when converted to a node.
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct LoopTemplate {
+    /// The list the loop is on, e.g. `foo#names`
+    pub on: String,
+    /// The variable the item is set to for an instance, in place of the alias of the loop
+    pub item: String,
+    /// The variable the index of the item is set to, in place of `LOOP.COUNTER`
+    pub counter: String,
+    /// The data ids of the elements rendered for the items of the list at build time
+    pub instances: Vec<String>,
+    /// The index of the sibling the instances are rendered before
+    pub position: usize,
 }
 
 pub fn default_column() -> Column {
@@ -432,7 +574,13 @@ pub fn text_from_properties(
             line_number,
         );
     }
-    let text = text.map(|v| ftd::executor::element::markup_inline(v.unwrap_or_default().as_str()));
+    let text = text.map(|v| {
+        ftd::executor::element::markup_inline_with_options(
+            v.unwrap_or_default().as_str(),
+            doc.markup,
+            doc.code,
+        )
+    });
     let common = common_from_properties(
         properties,
         events,
@@ -458,6 +606,7 @@ pub fn text_from_properties(
             doc,
             line_number,
         )?,
+        markup: vec![],
         common,
     })
 }
@@ -513,6 +662,7 @@ pub fn integer_from_properties(
             doc,
             line_number,
         )?,
+        markup: vec![],
     })
 }
 
@@ -567,6 +717,7 @@ pub fn decimal_from_properties(
             doc,
             line_number,
         )?,
+        markup: vec![],
     })
 }
 
@@ -607,6 +758,7 @@ pub fn boolean_from_properties(
             doc,
             line_number,
         )?,
+        markup: vec![],
     })
 }
 
@@ -629,7 +781,7 @@ pub fn image_from_properties(
             ftd::interpreter2::FTD_IMAGE_SRC,
         )?;
         ftd::executor::Value::new(
-            ImageSrc::from_values(src.value, doc, line_number)?,
+            ImageSrc::from_values(src.value, &doc.itdoc(), line_number)?,
             Some(line_number),
             src.properties,
         )
@@ -695,6 +847,68 @@ pub fn column_from_properties(
     Ok(Column { container, common })
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn grid_from_properties(
+    properties: &[ftd::interpreter2::Property],
+    events: &[ftd::interpreter2::Event],
+    arguments: &[ftd::interpreter2::Argument],
+    condition: &Option<ftd::interpreter2::Expression>,
+    doc: &ftd::executor::TDoc,
+    local_container: &[usize],
+    line_number: usize,
+    children: Vec<Element>,
+) -> ftd::executor::Result<Grid> {
+    let common = common_from_properties(
+        properties,
+        events,
+        arguments,
+        condition,
+        doc,
+        local_container,
+        line_number,
+    )?;
+    let container = container_from_properties(properties, arguments, doc, line_number, children)?;
+    Ok(Grid {
+        template_columns: ftd::executor::GridTrack::grid_track_list(
+            properties,
+            arguments,
+            doc,
+            line_number,
+            "template-columns",
+        )?,
+        template_rows: ftd::executor::GridTrack::grid_track_list(
+            properties,
+            arguments,
+            doc,
+            line_number,
+            "template-rows",
+        )?,
+        template_areas: ftd::executor::value::string_list(
+            "template-areas",
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?,
+        column_gap: ftd::executor::Length::optional_length(
+            properties,
+            arguments,
+            doc,
+            line_number,
+            "column-gap",
+        )?,
+        row_gap: ftd::executor::Length::optional_length(
+            properties,
+            arguments,
+            doc,
+            line_number,
+            "row-gap",
+        )?,
+        container,
+        common,
+    })
+}
+
 pub fn common_from_properties(
     properties: &[ftd::interpreter2::Property],
     events: &[ftd::interpreter2::Event],
@@ -799,6 +1013,27 @@ pub fn common_from_properties(
             line_number,
             "border-style",
         )?,
+        grid_area: ftd::executor::value::optional_string(
+            "grid-area",
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?,
+        grid_column: ftd::executor::value::optional_string(
+            "grid-column",
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?,
+        grid_row: ftd::executor::value::optional_string(
+            "grid-row",
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?,
         classes: ftd::executor::value::string_list(
             "classes",
             properties,
@@ -1069,6 +1304,7 @@ pub fn common_from_properties(
             line_number,
         )?,
         condition: condition.to_owned(),
+        loop_template: None,
         data_id: ftd::executor::utils::get_string_container(local_container),
         line_number,
         background: ftd::executor::Background::optional_fill(
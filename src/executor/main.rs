@@ -6,6 +6,8 @@ pub struct ExecuteDoc<'a> {
     pub aliases: &'a ftd::Map<String>,
     pub bag: &'a mut ftd::Map<ftd::interpreter2::Thing>,
     pub instructions: &'a [ftd::interpreter2::Component],
+    pub markup: &'a ftd::markup::MarkupOptions,
+    pub code: &'a ftd::executor::CodeOptions,
 }
 
 #[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
@@ -19,12 +21,29 @@ pub struct RT {
 impl<'a> ExecuteDoc<'a> {
     #[tracing::instrument(skip_all)]
     pub fn from_interpreter(document: ftd::interpreter2::Document) -> ftd::executor::Result<RT> {
+        ExecuteDoc::from_interpreter_with_options(
+            document,
+            &Default::default(),
+            &Default::default(),
+        )
+    }
+
+    /// Same as `from_interpreter`, rendering the markup of the document using `markup` and its
+    /// `ftd.code` using `code`.
+    #[tracing::instrument(skip_all)]
+    pub fn from_interpreter_with_options(
+        document: ftd::interpreter2::Document,
+        markup: &ftd::markup::MarkupOptions,
+        code: &ftd::executor::CodeOptions,
+    ) -> ftd::executor::Result<RT> {
         let mut document = document;
         let execute_doc = ExecuteDoc {
             name: document.name.as_str(),
             aliases: &document.aliases,
             bag: &mut document.data,
             instructions: &document.tree,
+            markup,
+            code,
         }
         .execute()?;
         let mut main = ftd::executor::element::default_column();
@@ -43,6 +62,9 @@ impl<'a> ExecuteDoc<'a> {
             name: self.name,
             aliases: self.aliases,
             bag: self.bag,
+            markup: self.markup,
+            code: self.code,
+            loop_templates: Default::default(),
         };
 
         ExecuteDoc::execute_from_instructions_loop(self.instructions, &mut doc)
@@ -55,6 +77,7 @@ impl<'a> ExecuteDoc<'a> {
         inherited_variables: &mut ftd::VecMap<(String, Vec<usize>)>,
     ) -> ftd::executor::Result<Vec<(Vec<usize>, ftd::interpreter2::Component)>> {
         let mut elements = vec![];
+        let mut templates = vec![];
         let mut count = 0;
         for instruction in instructions.iter() {
             let instructions = ExecuteDoc::get_instructions_from_instruction(
@@ -64,12 +87,161 @@ impl<'a> ExecuteDoc<'a> {
                 count,
                 inherited_variables,
             )?;
+            if ExecuteDoc::is_dynamic_loop(instruction, doc) {
+                let instances = instructions
+                    .iter()
+                    .map(|(container, _)| ftd::executor::utils::get_string_container(container))
+                    .collect::<Vec<String>>();
+                templates.push((instruction, instances, count + instructions.len()));
+            }
             count += instructions.len();
             elements.extend(instructions)
         }
+        // the templates come after the other children so their data ids do not change
+        for (instruction, instances, position) in templates {
+            if let Some(template) = ExecuteDoc::get_loop_template_instruction(
+                instruction,
+                doc,
+                parent_container,
+                count,
+                instances,
+                position,
+                inherited_variables,
+            )? {
+                count += 1;
+                elements.push(template);
+            }
+        }
         Ok(elements)
     }
 
+    /// Whether `instruction` is a loop on a mutable list, which the runtime renders again when
+    /// the list changes.
+    fn is_dynamic_loop(
+        instruction: &ftd::interpreter2::Component,
+        doc: &ftd::executor::TDoc,
+    ) -> bool {
+        let reference = match instruction
+            .iteration
+            .as_ref()
+            .as_ref()
+            .and_then(|iteration| iteration.on.get_reference_or_clone())
+        {
+            Some(reference) => reference,
+            None => return false,
+        };
+        let variable = if doc.bag.contains_key(reference) {
+            reference.to_string()
+        } else {
+            ftd::interpreter2::utils::get_doc_name_and_remaining(
+                reference,
+                doc.name,
+                instruction.line_number,
+            )
+            .0
+        };
+        matches!(
+            doc.bag.get(variable.as_str()),
+            Some(ftd::interpreter2::Thing::Variable(v)) if v.mutable
+        )
+    }
+
+    /// The template of `instruction`, a loop on a mutable list, placed at `index` in
+    /// `parent_container`. The alias of the loop refers to a new mutable variable the runtime sets
+    /// to an item of the list, the first one at build time, and `LOOP.COUNTER` to one set to its
+    /// index. `None` if the list is empty and there is no value of the kind of its items to
+    /// render the template with.
+    fn get_loop_template_instruction(
+        instruction: &ftd::interpreter2::Component,
+        doc: &mut ftd::executor::TDoc,
+        parent_container: &[usize],
+        index: usize,
+        instances: Vec<String>,
+        position: usize,
+        inherited_variables: &mut ftd::VecMap<(String, Vec<usize>)>,
+    ) -> ftd::executor::Result<Option<(Vec<usize>, ftd::interpreter2::Component)>> {
+        let iteration = match instruction.iteration.as_ref() {
+            Some(iteration) => iteration,
+            None => return Ok(None),
+        };
+        let on = match iteration.on.get_reference_or_clone() {
+            Some(on) => on.to_string(),
+            None => return Ok(None),
+        };
+        let line_number = instruction.line_number;
+        let (data, kind) = iteration.children(&doc.itdoc())?;
+        let value = match data.into_iter().next() {
+            Some(value) => value,
+            None => {
+                match ftd::executor::utils::default_value(&kind.kind, &doc.itdoc(), line_number)? {
+                    Some(value) => value.into_property_value(true, line_number),
+                    None => return Ok(None),
+                }
+            }
+        };
+
+        let local_container = {
+            let mut local_container = parent_container.to_vec();
+            local_container.push(index);
+            local_container
+        };
+        let string_container = ftd::executor::utils::get_string_container(&local_container);
+        let alias = ftd::interpreter2::PropertyValueSource::Loop(iteration.alias.to_string())
+            .get_reference_name(iteration.alias.as_str(), &doc.itdoc());
+        let item = format!("{}:loop:{}", alias, string_container);
+        let counter = format!("{}:loop-counter:{}", alias, string_container);
+        let counter_kind = ftd::interpreter2::Kind::integer().into_kind_data();
+        for (name, kind, value) in [
+            (item.as_str(), kind, value),
+            (
+                counter.as_str(),
+                counter_kind.clone(),
+                ftd::interpreter2::Value::Integer { value: 0 }
+                    .into_property_value(true, line_number),
+            ),
+        ] {
+            doc.bag.insert(
+                name.to_string(),
+                ftd::interpreter2::Thing::Variable(ftd::interpreter2::Variable {
+                    name: name.to_string(),
+                    kind,
+                    mutable: true,
+                    value,
+                    conditional_value: vec![],
+                    line_number,
+                    is_static: false,
+                }),
+            );
+        }
+
+        let template = update_instruction_for_loop(
+            instruction,
+            doc,
+            alias.as_str(),
+            item.as_str(),
+            ftd::interpreter2::PropertyValue::Reference {
+                name: counter.to_string(),
+                kind: counter_kind,
+                source: ftd::interpreter2::PropertyValueSource::Global,
+                is_mutable: false,
+                line_number,
+            },
+            inherited_variables,
+            local_container.as_slice(),
+        );
+        doc.loop_templates.insert(
+            string_container,
+            ftd::executor::LoopTemplate {
+                on,
+                item,
+                counter,
+                instances,
+                position,
+            },
+        );
+        Ok(Some((local_container, template)))
+    }
+
     fn get_instructions_from_instruction(
         instruction: &ftd::interpreter2::Component,
         doc: &mut ftd::executor::TDoc,
@@ -85,6 +257,7 @@ impl<'a> ExecuteDoc<'a> {
                 start_index,
                 inherited_variables,
             )
+            .map_err(|e| e.with_instruction_span(instruction))
         } else {
             let mut local_container = parent_container.to_vec();
             local_container.push(start_index);
@@ -168,6 +341,7 @@ impl<'a> ExecuteDoc<'a> {
                     ),
                     doc_id: doc.name.to_string(),
                     line_number: iteration.line_number,
+                    span: Some(iteration.span),
                 })?;
         let mut elements = vec![];
         for index in 0..children_length {
@@ -195,14 +369,27 @@ impl<'a> ExecuteDoc<'a> {
         instructions: &[ftd::interpreter2::Component],
         doc: &mut ftd::executor::TDoc,
     ) -> ftd::executor::Result<Vec<ftd::executor::Element>> {
-        let mut elements = vec![];
         let mut inherited_variables: ftd::VecMap<(String, Vec<usize>)> = Default::default();
-        let mut instructions = ExecuteDoc::get_instructions_from_instructions(
+        let instructions = ExecuteDoc::get_instructions_from_instructions(
             instructions,
             doc,
             &[],
             &mut inherited_variables,
         )?;
+        ExecuteDoc::execute_instructions(instructions, vec![], doc, &[], &mut inherited_variables)
+    }
+
+    /// Executes `instructions`, each placed at its container, and inserts the resulting elements
+    /// into `elements`. The containers of the instructions start with `parent_container`.
+    pub(crate) fn execute_instructions(
+        instructions: Vec<(Vec<usize>, ftd::interpreter2::Component)>,
+        elements: Vec<ftd::executor::Element>,
+        doc: &mut ftd::executor::TDoc,
+        parent_container: &[usize],
+        inherited_variables: &mut ftd::VecMap<(String, Vec<usize>)>,
+    ) -> ftd::executor::Result<Vec<ftd::executor::Element>> {
+        let mut elements = elements;
+        let mut instructions = instructions;
         while !instructions.is_empty() {
             let (container, mut instruction) = instructions.remove(0);
             loop {
@@ -210,7 +397,7 @@ impl<'a> ExecuteDoc<'a> {
                     if condition.is_static(&doc.itdoc()) && !condition.eval(&doc.itdoc())? {
                         ExecuteDoc::insert_element(
                             &mut elements,
-                            container.as_slice(),
+                            &container[parent_container.len()..],
                             ftd::executor::Element::Null,
                         );
                         break;
@@ -227,25 +414,30 @@ impl<'a> ExecuteDoc<'a> {
                 if component_definition.definition.name.eq("ftd.kernel") {
                     update_inherited_reference_in_instruction(
                         &mut instruction,
-                        &mut inherited_variables,
+                        inherited_variables,
                         container.as_slice(),
                         doc,
                     );
+                    let mut element = ExecuteDoc::execute_kernel_components(
+                        &instruction,
+                        doc,
+                        container.as_slice(),
+                        &component_definition,
+                    )
+                    .map_err(|e| e.with_instruction_span(&instruction))?;
+                    if let Some(common) = element.get_mut_common() {
+                        common.loop_template = doc.loop_templates.remove(&common.data_id);
+                    }
                     ExecuteDoc::insert_element(
                         &mut elements,
-                        container.as_slice(),
-                        ExecuteDoc::execute_kernel_components(
-                            &instruction,
-                            doc,
-                            container.as_slice(),
-                            &component_definition,
-                        )?,
+                        &container[parent_container.len()..],
+                        element,
                     );
                     let children_instructions = ExecuteDoc::get_instructions_from_instructions(
                         instruction.get_children(&doc.itdoc())?.as_slice(),
                         doc,
                         container.as_slice(),
-                        &mut inherited_variables,
+                        inherited_variables,
                     )?;
                     instructions.extend(children_instructions);
 
@@ -256,8 +448,9 @@ impl<'a> ExecuteDoc<'a> {
                         doc,
                         container.as_slice(),
                         component_definition,
-                        &mut inherited_variables,
-                    )?;
+                        inherited_variables,
+                    )
+                    .map_err(|e| e.with_instruction_span(&instruction))?;
                 }
             }
         }
@@ -278,6 +471,7 @@ impl<'a> ExecuteDoc<'a> {
                 current = match &mut current[*i] {
                     ftd::executor::Element::Row(r) => &mut r.container.children,
                     ftd::executor::Element::Column(r) => &mut r.container.children,
+                    ftd::executor::Element::Grid(g) => &mut g.container.children,
                     t => unreachable!("{:?}", t),
                 };
             }
@@ -376,6 +570,7 @@ impl<'a> ExecuteDoc<'a> {
                     ),
                     doc_id: doc.name.to_string(),
                     line_number: iteration.line_number,
+                    span: Some(iteration.span),
                 })?;
         let mut elements = vec![];
         for index in 0..children_length {
@@ -438,7 +633,7 @@ impl<'a> ExecuteDoc<'a> {
     ) -> ftd::executor::Result<ftd::executor::Element> {
         Ok(match component_definition.name.as_str() {
             "ftd#text" => {
-                ftd::executor::Element::Text(ftd::executor::element::text_from_properties(
+                let mut text = ftd::executor::element::text_from_properties(
                     instruction.properties.as_slice(),
                     instruction.events.as_slice(),
                     component_definition.arguments.as_slice(),
@@ -446,7 +641,14 @@ impl<'a> ExecuteDoc<'a> {
                     doc,
                     local_container,
                     instruction.line_number,
-                )?)
+                )?;
+                text.markup = ftd::executor::markup::markup_from_text(
+                    text.text.value.original.as_str(),
+                    doc,
+                    local_container,
+                    instruction.line_number,
+                )?;
+                ftd::executor::Element::Text(text)
             }
             "ftd#integer" => {
                 ftd::executor::Element::Integer(ftd::executor::element::integer_from_properties(
@@ -503,6 +705,18 @@ impl<'a> ExecuteDoc<'a> {
                     vec![],
                 )?)
             }
+            "ftd#grid" => ftd::executor::Element::Grid(Box::new(
+                ftd::executor::element::grid_from_properties(
+                    instruction.properties.as_slice(),
+                    instruction.events.as_slice(),
+                    component_definition.arguments.as_slice(),
+                    instruction.condition.as_ref(),
+                    doc,
+                    local_container,
+                    instruction.line_number,
+                    vec![],
+                )?,
+            )),
             "ftd#image" => {
                 ftd::executor::Element::Image(ftd::executor::element::image_from_properties(
                     instruction.properties.as_slice(),
@@ -561,19 +775,44 @@ fn update_instruction_for_loop_element(
     inherited_variables: &mut ftd::VecMap<(String, Vec<usize>)>,
     local_container: &[usize],
 ) -> ftd::executor::Result<ftd::interpreter2::Component> {
-    let mut instruction = instruction.clone();
     let reference_replace_pattern = ftd::interpreter2::PropertyValueSource::Loop(alias.to_string())
         .get_reference_name(alias, &doc.itdoc());
     let replace_with = format!("{}.{}", reference_name, index_in_loop);
-    let map =
-        std::iter::IntoIterator::into_iter([(reference_replace_pattern, replace_with)]).collect();
-    let replace_property_value = std::iter::IntoIterator::into_iter([(
-        doc.itdoc()
-            .resolve_name(ftd::interpreter2::FTD_LOOP_COUNTER),
+    Ok(update_instruction_for_loop(
+        instruction,
+        doc,
+        reference_replace_pattern.as_str(),
+        replace_with.as_str(),
         ftd::interpreter2::Value::Integer {
             value: index_in_loop as i64,
         }
         .into_property_value(false, instruction.line_number),
+        inherited_variables,
+        local_container,
+    ))
+}
+
+/// `instruction` with the references to the alias of its loop, `reference_replace_pattern`,
+/// replaced with `replace_with` and `LOOP.COUNTER` with `counter`.
+fn update_instruction_for_loop(
+    instruction: &ftd::interpreter2::Component,
+    doc: &mut ftd::executor::TDoc,
+    reference_replace_pattern: &str,
+    replace_with: &str,
+    counter: ftd::interpreter2::PropertyValue,
+    inherited_variables: &mut ftd::VecMap<(String, Vec<usize>)>,
+    local_container: &[usize],
+) -> ftd::interpreter2::Component {
+    let mut instruction = instruction.clone();
+    let map = std::iter::IntoIterator::into_iter([(
+        reference_replace_pattern.to_string(),
+        replace_with.to_string(),
+    )])
+    .collect();
+    let replace_property_value = std::iter::IntoIterator::into_iter([(
+        doc.itdoc()
+            .resolve_name(ftd::interpreter2::FTD_LOOP_COUNTER),
+        counter,
     )])
     .collect();
 
@@ -585,7 +824,7 @@ fn update_instruction_for_loop_element(
         local_container,
         doc,
     );
-    Ok(instruction)
+    instruction
 }
 
 fn update_reference_value(
@@ -850,6 +1089,12 @@ fn update_local_variable_reference_in_property_value(
                         doc,
                     );
                 }
+                ftd::interpreter2::Value::String { text } if text.contains("{$") => {
+                    *text = ftd::executor::markup::update_markup_references(
+                        text.as_str(),
+                        local_variable,
+                    );
+                }
                 _ => {}
             }
         }
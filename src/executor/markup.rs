@@ -65,3 +65,301 @@ fn spaces(s: &str) -> (usize, usize) {
     }
     (space_before, space_after)
 }
+
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub enum Markup {
+    Text(MarkupText),
+    Element(Box<ftd::executor::Element>),
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Default, Clone, serde::Serialize)]
+pub struct MarkupText {
+    pub text: ftd::executor::Value<ftd::executor::element::Rendered>,
+    pub data_id: String,
+    pub line_number: usize,
+}
+
+#[derive(Debug, PartialEq)]
+enum MarkupPart {
+    Text(String),
+    Reference(String),
+    Component {
+        name: String,
+        caption: Option<String>,
+    },
+}
+
+/// Splits `text` into runs of plain text, `{$<variable>}` references and
+/// `{<component>: <caption>}` inline components. Returns an empty vector if the text has no
+/// references or components, so plain text keeps rendering as a single string.
+pub(crate) fn markup_from_text(
+    text: &str,
+    doc: &mut ftd::executor::TDoc,
+    local_container: &[usize],
+    line_number: usize,
+) -> ftd::executor::Result<Vec<Markup>> {
+    let parts = parse_markup(text, doc, line_number)?;
+    if parts.iter().all(|v| matches!(v, MarkupPart::Text(_))) {
+        return Ok(vec![]);
+    }
+
+    let mut markups = vec![];
+    let mut instructions = vec![];
+    for (idx, part) in parts.into_iter().enumerate() {
+        let mut container = local_container.to_vec();
+        container.push(idx);
+        let data_id = ftd::executor::utils::get_string_container(container.as_slice());
+        match part {
+            MarkupPart::Text(text) => markups.push(Some(Markup::Text(MarkupText {
+                text: ftd::executor::Value::new(
+                    ftd::executor::element::markup_inline_with_options(
+                        text.as_str(),
+                        doc.markup,
+                        doc.code,
+                    ),
+                    Some(line_number),
+                    vec![],
+                ),
+                data_id,
+                line_number,
+            }))),
+            MarkupPart::Reference(name) => markups.push(Some(Markup::Text(
+                markup_text_from_reference(name.as_str(), data_id, doc, line_number)?,
+            ))),
+            MarkupPart::Component { name, caption } => {
+                instructions.push((
+                    container,
+                    component_from_markup(name.as_str(), caption, doc, line_number)?,
+                ));
+                markups.push(None);
+            }
+        }
+    }
+
+    // Text runs get a placeholder so that each component lands at its own index.
+    let placeholders = markups
+        .iter()
+        .filter(|v| v.is_some())
+        .map(|_| ftd::executor::Element::Null)
+        .collect();
+    let elements = ftd::executor::ExecuteDoc::execute_instructions(
+        instructions,
+        placeholders,
+        doc,
+        local_container,
+        &mut Default::default(),
+    )?;
+
+    Ok(markups
+        .into_iter()
+        .zip(elements)
+        .map(|(markup, element)| markup.unwrap_or_else(|| Markup::Element(Box::new(element))))
+        .collect())
+}
+
+fn markup_text_from_reference(
+    name: &str,
+    data_id: String,
+    doc: &mut ftd::executor::TDoc,
+    line_number: usize,
+) -> ftd::executor::Result<MarkupText> {
+    let name = doc.itdoc().resolve_name(name);
+    let kind = match doc.itdoc().get_kind(name.as_str(), line_number)? {
+        ftd::interpreter2::StateWithThing::Thing(kind) => kind,
+        _ => {
+            return ftd::executor::utils::parse_error(
+                format!("Cannot find variable `{}` in markup", name),
+                doc.name,
+                line_number,
+            )
+        }
+    };
+    let property_value = ftd::interpreter2::PropertyValue::Reference {
+        name,
+        kind,
+        source: ftd::interpreter2::PropertyValueSource::Global,
+        is_mutable: false,
+        line_number,
+    };
+    let value = property_value
+        .clone()
+        .resolve(&doc.itdoc(), line_number)?
+        .to_string()
+        .unwrap_or_default();
+    Ok(MarkupText {
+        text: ftd::executor::Value::new(
+            ftd::executor::element::markup_inline_with_options(
+                value.as_str(),
+                doc.markup,
+                doc.code,
+            ),
+            Some(line_number),
+            vec![ftd::interpreter2::Property {
+                value: property_value,
+                source: ftd::interpreter2::PropertySource::Caption,
+                condition: None,
+                line_number,
+                span: Default::default(),
+            }],
+        ),
+        data_id,
+        line_number,
+    })
+}
+
+/// Rewrites `{$<argument>}` references in markup to the local variables created for the
+/// component instance, the same way references in property values are updated.
+pub(crate) fn update_markup_references(text: &str, local_variable: &ftd::Map<String>) -> String {
+    let mut text = text.to_string();
+    for (k, v) in local_variable {
+        let name = match k.split_once('#') {
+            Some((_, name)) => name,
+            None => k.as_str(),
+        };
+        for end in ["}", ".", " "] {
+            text = text.replace(
+                format!("{{${}{}", name, end).as_str(),
+                format!("{{${}{}", v, end).as_str(),
+            );
+        }
+    }
+    text
+}
+
+fn component_from_markup(
+    name: &str,
+    caption: Option<String>,
+    doc: &mut ftd::executor::TDoc,
+    line_number: usize,
+) -> ftd::executor::Result<ftd::interpreter2::Component> {
+    let name = doc.itdoc().resolve_name(name);
+    let component_definition = doc.itdoc().get_component(name.as_str(), line_number)?;
+    let mut properties = vec![];
+    if let Some(caption) = caption {
+        let argument = match component_definition
+            .arguments
+            .iter()
+            .find(|v| v.kind.caption)
+        {
+            Some(argument) => argument,
+            None => {
+                return ftd::executor::utils::parse_error(
+                    format!("`{}` does not take a caption", name),
+                    doc.name,
+                    line_number,
+                )
+            }
+        };
+        let value = match ftd::interpreter2::PropertyValue::from_ast_value(
+            ftd::ast::VariableValue::String {
+                value: caption,
+                line_number,
+            },
+            &mut doc.itdoc(),
+            false,
+            Some(&argument.kind),
+        )? {
+            ftd::interpreter2::StateWithThing::Thing(value) => value,
+            _ => {
+                return ftd::executor::utils::parse_error(
+                    format!("Cannot resolve caption of `{}` in markup", name),
+                    doc.name,
+                    line_number,
+                )
+            }
+        };
+        properties.push(ftd::interpreter2::Property {
+            value,
+            source: ftd::interpreter2::PropertySource::Caption,
+            condition: None,
+            line_number,
+            span: Default::default(),
+        });
+    }
+
+    let mut component = ftd::interpreter2::Component::from_name(name.as_str());
+    component.properties = properties;
+    component.line_number = line_number;
+    Ok(component)
+}
+
+fn parse_markup(
+    text: &str,
+    doc: &ftd::executor::TDoc,
+    line_number: usize,
+) -> ftd::executor::Result<Vec<MarkupPart>> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // markdown takes care of un-escaping `\{` and `\}` in text runs
+            '\\' if matches!(chars.peek(), Some('{') | Some('}')) => {
+                current.push(c);
+                current.extend(chars.next());
+            }
+            '{' => {
+                let mut content = String::new();
+                let mut depth = 1;
+                loop {
+                    match chars.next() {
+                        Some('}') if depth == 1 => break,
+                        Some(c) => {
+                            match c {
+                                '{' => depth += 1,
+                                '}' => depth -= 1,
+                                _ => {}
+                            }
+                            content.push(c);
+                        }
+                        None => {
+                            return ftd::executor::utils::parse_error(
+                                "cannot find closing-parenthesis before the string ends",
+                                doc.name,
+                                line_number,
+                            )
+                        }
+                    }
+                }
+
+                let trimmed = content.trim();
+                let part = if let Some(reference) = trimmed.strip_prefix('$') {
+                    MarkupPart::Reference(reference.trim().to_string())
+                } else if let Some((name, caption)) = trimmed
+                    .split_once(':')
+                    .filter(|(name, _)| is_component(name.trim(), doc, line_number))
+                {
+                    let caption = caption.trim();
+                    MarkupPart::Component {
+                        name: name.trim().to_string(),
+                        caption: (!caption.is_empty()).then(|| caption.to_string()),
+                    }
+                } else {
+                    // `{text}` without a reference or a component, e.g. `{note: ...}` when
+                    // there is no component `note`, is kept as it is
+                    current.push('{');
+                    current.push_str(content.as_str());
+                    current.push('}');
+                    continue;
+                };
+                if !current.is_empty() {
+                    parts.push(MarkupPart::Text(std::mem::take(&mut current)));
+                }
+                parts.push(part);
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        parts.push(MarkupPart::Text(current));
+    }
+    Ok(parts)
+}
+
+/// If `name` is a component `{name: caption}` can render inline.
+fn is_component(name: &str, doc: &ftd::executor::TDoc, line_number: usize) -> bool {
+    let name = doc.itdoc().resolve_name(name);
+    doc.itdoc()
+        .get_component(name.as_str(), line_number)
+        .is_ok()
+}
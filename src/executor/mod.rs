@@ -2,7 +2,7 @@
 #[macro_use]
 mod test;
 
-mod code;
+pub(crate) mod code;
 mod element;
 mod main;
 mod markup;
@@ -12,14 +12,19 @@ mod utils;
 mod value;
 mod youtube_id;
 
+pub use code::{CodeLines, CodeOptions};
 pub use element::{
-    Code, Column, Common, Container, Element, Event, Iframe, Image, Row, Text, TextInput,
+    Code, Column, Common, Container, Element, Event, Grid, Iframe, Image, ImageSrc, LoopTemplate,
+    Row, Text, TextInput,
 };
 pub use main::{ExecuteDoc, RT};
+pub use markup::{Markup, MarkupText};
 pub use styles::{
-    AlignSelf, Alignment, Anchor, Background, BorderStyle, Color, ColorValue, Cursor, FontSize,
-    Length, LineClamp, Loading, Overflow, Region, Resize, Resizing, ResponsiveType, SpacingMode,
-    TextAlign, TextInputType, TextTransform, WhiteSpace,
+    AlignSelf, Alignment, Anchor, Background, BackgroundPosition, BackgroundRepeat, BackgroundSize,
+    BorderStyle, Color, ColorStop, ColorValue, Cursor, FontSize, GridTrack, ImageBackground,
+    Length, LineClamp, LinearGradient, LinearGradientDirection, Loading, Overflow, RadialGradient,
+    RadialGradientShape, Region, Resize, Resizing, ResponsiveType, SpacingMode, TextAlign,
+    TextInputType, TextTransform, WhiteSpace,
 };
 pub(crate) use tdoc::TDoc;
 pub(crate) use value::Value;
@@ -34,6 +39,7 @@ pub enum Error {
         message: String,
         doc_id: String,
         line_number: usize,
+        span: Option<ftd::p11::Span>,
     },
 
     #[error("syntect error: {source}")]
@@ -43,4 +49,38 @@ pub enum Error {
     },
 }
 
+impl Error {
+    /// Points the error at `span` if it is reported for the line `span` is on and does not
+    /// already point somewhere more precise.
+    pub(crate) fn with_span(self, span: ftd::p11::Span) -> Error {
+        match self {
+            Error::InterpreterError(e) => Error::InterpreterError(e.with_span(span)),
+            Error::ParseError {
+                message,
+                doc_id,
+                line_number,
+                span: None,
+            } if !span.is_empty() && line_number == span.line => Error::ParseError {
+                message,
+                doc_id,
+                line_number,
+                span: Some(span),
+            },
+            e => e,
+        }
+    }
+
+    /// Points the error at the property or loop of `instruction` it is reported for, or else
+    /// at the section of `instruction`.
+    pub(crate) fn with_instruction_span(self, instruction: &ftd::interpreter2::Component) -> Error {
+        instruction
+            .properties
+            .iter()
+            .map(|v| v.span)
+            .chain(instruction.iteration.iter().map(|v| v.span))
+            .chain(std::iter::once(instruction.span))
+            .fold(self, |e, span| e.with_span(span))
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
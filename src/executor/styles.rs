@@ -19,7 +19,7 @@ impl Default for Length {
 impl Length {
     fn from_optional_values(
         or_type_value: Option<(String, ftd::interpreter2::PropertyValue)>,
-        doc: &ftd::executor::TDoc,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::executor::Result<Option<Length>> {
         if let Some(value) = or_type_value {
@@ -31,10 +31,10 @@ impl Length {
 
     fn from_value(
         value: ftd::interpreter2::PropertyValue,
-        doc: &ftd::executor::TDoc,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::executor::Result<Length> {
-        let binding = value.resolve(&doc.itdoc(), line_number)?;
+        let binding = value.resolve(doc, line_number)?;
         let value = binding.get_or_type(doc.name, line_number)?;
         let value = (value.1.to_owned(), value.2.to_owned());
         Length::from_values(value, doc, line_number)
@@ -42,7 +42,7 @@ impl Length {
 
     fn from_values(
         or_type_value: (String, ftd::interpreter2::PropertyValue),
-        doc: &ftd::executor::TDoc,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::executor::Result<Length> {
         match or_type_value.0.as_str() {
@@ -50,49 +50,49 @@ impl Length {
                 or_type_value
                     .1
                     .clone()
-                    .resolve(&doc.itdoc(), line_number)?
+                    .resolve(doc, line_number)?
                     .decimal(doc.name, line_number)?,
             )),
             ftd::interpreter2::FTD_LENGTH_PX => Ok(Length::Px(
                 or_type_value
                     .1
                     .clone()
-                    .resolve(&doc.itdoc(), line_number)?
+                    .resolve(doc, line_number)?
                     .integer(doc.name, line_number)?,
             )),
             ftd::interpreter2::FTD_LENGTH_CALC => Ok(Length::Calc(
                 or_type_value
                     .1
                     .clone()
-                    .resolve(&doc.itdoc(), line_number)?
+                    .resolve(doc, line_number)?
                     .string(doc.name, line_number)?,
             )),
             ftd::interpreter2::FTD_LENGTH_VH => Ok(Length::Vh(
                 or_type_value
                     .1
                     .clone()
-                    .resolve(&doc.itdoc(), line_number)?
+                    .resolve(doc, line_number)?
                     .decimal(doc.name, line_number)?,
             )),
             ftd::interpreter2::FTD_LENGTH_VW => Ok(Length::Vw(
                 or_type_value
                     .1
                     .clone()
-                    .resolve(&doc.itdoc(), line_number)?
+                    .resolve(doc, line_number)?
                     .decimal(doc.name, line_number)?,
             )),
             ftd::interpreter2::FTD_LENGTH_EM => Ok(Length::Em(
                 or_type_value
                     .1
                     .clone()
-                    .resolve(&doc.itdoc(), line_number)?
+                    .resolve(doc, line_number)?
                     .decimal(doc.name, line_number)?,
             )),
             ftd::interpreter2::FTD_LENGTH_REM => Ok(Length::Rem(
                 or_type_value
                     .1
                     .clone()
-                    .resolve(&doc.itdoc(), line_number)?
+                    .resolve(doc, line_number)?
                     .decimal(doc.name, line_number)?,
             )),
             ftd::interpreter2::FTD_LENGTH_RESPONSIVE => Ok(Length::Responsive(Box::new(
@@ -123,7 +123,7 @@ impl Length {
         )?;
 
         Ok(ftd::executor::Value::new(
-            Length::from_optional_values(or_type_value.value, doc, line_number)?,
+            Length::from_optional_values(or_type_value.value, &doc.itdoc(), line_number)?,
             or_type_value.line_number,
             or_type_value.properties,
         ))
@@ -147,7 +147,8 @@ impl Length {
         )?;
 
         Ok(ftd::executor::Value::new(
-            Length::from_optional_values(or_type_value.value, doc, line_number)?.unwrap_or(default),
+            Length::from_optional_values(or_type_value.value, &doc.itdoc(), line_number)?
+                .unwrap_or(default),
             or_type_value.line_number,
             or_type_value.properties,
         ))
@@ -240,10 +241,10 @@ pub struct ResponsiveLength {
 impl ResponsiveLength {
     fn from_value(
         value: ftd::interpreter2::PropertyValue,
-        doc: &ftd::executor::TDoc,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::executor::Result<ResponsiveLength> {
-        let value = value.resolve(&doc.itdoc(), line_number)?;
+        let value = value.resolve(doc, line_number)?;
         let fields = match value.inner() {
             Some(ftd::interpreter2::Value::Record { name, fields })
                 if name.eq(ftd::interpreter2::FTD_RESPONSIVE_LENGTH) =>
@@ -267,7 +268,7 @@ impl ResponsiveLength {
 
     fn from_values(
         values: ftd::Map<ftd::interpreter2::PropertyValue>,
-        doc: &ftd::executor::TDoc,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::executor::Result<ResponsiveLength> {
         let desktop = {
@@ -277,6 +278,7 @@ impl ResponsiveLength {
                     message: "`desktop` field in ftd.responsive-type not found".to_string(),
                     doc_id: doc.name.to_string(),
                     line_number,
+                    span: None,
                 })?;
             Length::from_value(value.to_owned(), doc, line_number)?
         };
@@ -558,6 +560,102 @@ impl Alignment {
     }
 }
 
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub enum GridTrack {
+    Fixed(ftd::executor::Length),
+    Fraction(f64),
+    Auto,
+    MinContent,
+    MaxContent,
+}
+
+impl GridTrack {
+    fn from_values(
+        or_type_value: (String, ftd::interpreter2::PropertyValue),
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Self> {
+        match or_type_value.0.as_str() {
+            t if t.starts_with(ftd::interpreter2::FTD_GRID_TRACK_FIXED) => {
+                let value = or_type_value.1.clone().resolve(&doc.itdoc(), line_number)?;
+                let (_, variant, value) = value.get_or_type(doc.name, line_number)?;
+                Ok(GridTrack::Fixed(Length::from_values(
+                    (variant.to_owned(), value.to_owned()),
+                    &doc.itdoc(),
+                    line_number,
+                )?))
+            }
+            ftd::interpreter2::FTD_GRID_TRACK_FRACTION => Ok(GridTrack::Fraction(
+                or_type_value
+                    .1
+                    .clone()
+                    .resolve(&doc.itdoc(), line_number)?
+                    .decimal(doc.name, line_number)?,
+            )),
+            ftd::interpreter2::FTD_GRID_TRACK_AUTO => Ok(GridTrack::Auto),
+            ftd::interpreter2::FTD_GRID_TRACK_MIN_CONTENT => Ok(GridTrack::MinContent),
+            ftd::interpreter2::FTD_GRID_TRACK_MAX_CONTENT => Ok(GridTrack::MaxContent),
+            t => ftd::executor::utils::parse_error(
+                format!("Unknown variant `{}` for or-type `ftd.grid-track`", t),
+                doc.name,
+                line_number,
+            ),
+        }
+    }
+
+    pub(crate) fn grid_track_list(
+        properties: &[ftd::interpreter2::Property],
+        arguments: &[ftd::interpreter2::Argument],
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+        key: &str,
+    ) -> ftd::executor::Result<ftd::executor::Value<Vec<GridTrack>>> {
+        let or_type_values = ftd::executor::value::or_type_list(
+            key,
+            properties,
+            arguments,
+            doc,
+            line_number,
+            ftd::interpreter2::FTD_GRID_TRACK,
+        )?;
+
+        let mut tracks = vec![];
+        for value in or_type_values.value {
+            tracks.push(GridTrack::from_values(value, doc, line_number)?);
+        }
+
+        Ok(ftd::executor::Value::new(
+            tracks,
+            or_type_values.line_number,
+            or_type_values.properties,
+        ))
+    }
+
+    pub fn to_css_string(&self) -> String {
+        match self {
+            GridTrack::Fixed(l) => l.to_css_string(),
+            GridTrack::Fraction(f) => format!("{}fr", f),
+            GridTrack::Auto => "auto".to_string(),
+            GridTrack::MinContent => "min-content".to_string(),
+            GridTrack::MaxContent => "max-content".to_string(),
+        }
+    }
+
+    /// The `grid-template-columns` or `grid-template-rows` value of `tracks`, if any.
+    pub fn list_to_css_string(tracks: &[GridTrack]) -> Option<String> {
+        if tracks.is_empty() {
+            return None;
+        }
+        Some(
+            tracks
+                .iter()
+                .map(|v| v.to_css_string())
+                .collect::<Vec<String>>()
+                .join(" "),
+        )
+    }
+}
+
 #[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
 pub enum Resizing {
     HugContent,
@@ -596,7 +694,7 @@ impl Resizing {
                 let (_, variant, value) = value.get_or_type(doc.name, line_number)?;
                 Ok(Resizing::Fixed(Length::from_values(
                     (variant.to_owned(), value.to_owned()),
-                    doc,
+                    &doc.itdoc(),
                     line_number,
                 )?))
             }
@@ -751,12 +849,16 @@ impl Resizing {
 #[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
 pub enum Background {
     Solid(Color),
+    LinearGradient(LinearGradient),
+    RadialGradient(RadialGradient),
+    Image(ImageBackground),
+    Layers(Vec<Background>),
 }
 
 impl Background {
     fn from_optional_values(
         or_type_value: Option<(String, ftd::interpreter2::PropertyValue)>,
-        doc: &ftd::executor::TDoc,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::executor::Result<Option<Self>> {
         if let Some(value) = or_type_value {
@@ -766,9 +868,20 @@ impl Background {
         }
     }
 
+    fn from_value(
+        value: ftd::interpreter2::PropertyValue,
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Self> {
+        let binding = value.resolve(doc, line_number)?;
+        let value = binding.get_or_type(doc.name, line_number)?;
+        let value = (value.1.to_owned(), value.2.to_owned());
+        Background::from_values(value, doc, line_number)
+    }
+
     fn from_values(
         or_type_value: (String, ftd::interpreter2::PropertyValue),
-        doc: &ftd::executor::TDoc,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::executor::Result<Self> {
         match or_type_value.0.as_str() {
@@ -777,8 +890,24 @@ impl Background {
                 doc,
                 line_number,
             )?)),
+            ftd::interpreter2::FTD_BACKGROUND_LINEAR_GRADIENT => Ok(Background::LinearGradient(
+                LinearGradient::from_value(or_type_value.1, doc, line_number)?,
+            )),
+            ftd::interpreter2::FTD_BACKGROUND_RADIAL_GRADIENT => Ok(Background::RadialGradient(
+                RadialGradient::from_value(or_type_value.1, doc, line_number)?,
+            )),
+            ftd::interpreter2::FTD_BACKGROUND_IMAGE => Ok(Background::Image(
+                ImageBackground::from_value(or_type_value.1, doc, line_number)?,
+            )),
+            ftd::interpreter2::FTD_BACKGROUND_LAYERS => {
+                let mut layers = vec![];
+                for value in list_values(or_type_value.1, doc, line_number)? {
+                    layers.push(Background::from_value(value, doc, line_number)?);
+                }
+                Ok(Background::Layers(layers))
+            }
             t => ftd::executor::utils::parse_error(
-                format!("Unknown variant `{}` for or-type `ftd.length`", t),
+                format!("Unknown variant `{}` for or-type `ftd.background`", t),
                 doc.name,
                 line_number,
             ),
@@ -802,19 +931,679 @@ impl Background {
         )?;
 
         Ok(ftd::executor::Value::new(
-            Background::from_optional_values(or_type_value.value, doc, line_number)?,
+            Background::from_optional_values(or_type_value.value, &doc.itdoc(), line_number)?,
             or_type_value.line_number,
             or_type_value.properties,
         ))
     }
 
+    /// Light and dark `background` css of an `ftd.background` value, as the html1 data and
+    /// dependency generators need it for gradients, images and layers.
+    pub(crate) fn css_strings_from_value(
+        value: &ftd::interpreter2::Value,
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<(String, String)> {
+        let (_, variant, value) = value.get_or_type(doc.name, line_number)?;
+        let background =
+            Background::from_values((variant.to_owned(), value.to_owned()), doc, line_number)?;
+        Ok((background.to_css_string(), background.to_dark_css_string()))
+    }
+
+    /// `true` if `value` is an `ftd.background` that is rendered using the `background`
+    /// shorthand, i.e. anything but a solid color.
+    pub fn is_shorthand_value(value: &ftd::interpreter2::Value) -> bool {
+        matches!(
+            value,
+            ftd::interpreter2::Value::OrType { name, variant, .. }
+                if name.eq(ftd::interpreter2::FTD_BACKGROUND)
+                    && variant.ne(ftd::interpreter2::FTD_BACKGROUND_SOLID)
+        )
+    }
+
+    /// `true` unless `kind` is known to be a solid `ftd.background`, whose values go to
+    /// `background-color` instead.
+    pub fn is_shorthand_kind(kind: &ftd::interpreter2::Kind) -> bool {
+        !matches!(
+            kind.ref_inner(),
+            ftd::interpreter2::Kind::OrType { name, variant: Some(variant), .. }
+                if name.eq(ftd::interpreter2::FTD_BACKGROUND)
+                    && variant.eq(ftd::interpreter2::FTD_BACKGROUND_SOLID)
+        )
+    }
+
+    /// The `background-color` of a solid background.
+    pub fn color_css_string(&self) -> Option<String> {
+        match self {
+            Background::Solid(c) => Some(c.light.value.to_css_string()),
+            _ => None,
+        }
+    }
+
     pub fn to_css_string(&self) -> String {
+        self.css_string(false)
+    }
+
+    pub fn to_dark_css_string(&self) -> String {
+        self.css_string(true)
+    }
+
+    fn css_string(&self, dark: bool) -> String {
         match self {
-            Background::Solid(c) => c.light.value.to_css_string(),
+            Background::Solid(c) => c.css_string(dark),
+            Background::LinearGradient(g) => g.css_string(dark),
+            Background::RadialGradient(g) => g.css_string(dark),
+            Background::Image(i) => i.css_string(dark),
+            Background::Layers(layers) => layers
+                .iter()
+                .map(|v| match v {
+                    // a color is only allowed in the bottom layer, so it is drawn as a
+                    // gradient between the same color
+                    Background::Solid(c) => {
+                        format!("linear-gradient({0}, {0})", c.css_string(dark))
+                    }
+                    v => v.css_string(dark),
+                })
+                .collect::<Vec<String>>()
+                .join(", "),
         }
     }
 }
 
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub struct ColorStop {
+    pub color: Color,
+    pub stop: Option<Length>,
+}
+
+impl ColorStop {
+    fn from_value(
+        value: ftd::interpreter2::PropertyValue,
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<ColorStop> {
+        let values = record_fields(value, ftd::interpreter2::FTD_COLOR_STOP, doc, line_number)?;
+        let color = Color::from_value(
+            get_field(
+                &values,
+                "color",
+                ftd::interpreter2::FTD_COLOR_STOP,
+                doc,
+                line_number,
+            )?,
+            doc,
+            line_number,
+        )?;
+        let stop = Length::from_optional_values(
+            optional_or_type_field(&values, "stop", doc, line_number)?,
+            doc,
+            line_number,
+        )?;
+        Ok(ColorStop { color, stop })
+    }
+
+    fn list_from_value(
+        value: ftd::interpreter2::PropertyValue,
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Vec<ColorStop>> {
+        let mut colors = vec![];
+        for value in list_values(value, doc, line_number)? {
+            colors.push(ColorStop::from_value(value, doc, line_number)?);
+        }
+        Ok(colors)
+    }
+
+    fn css_string(&self, dark: bool) -> String {
+        match self.stop {
+            Some(ref stop) => format!("{} {}", self.color.css_string(dark), stop.to_css_string()),
+            None => self.color.css_string(dark),
+        }
+    }
+
+    fn list_css_string(colors: &[ColorStop], dark: bool) -> String {
+        colors
+            .iter()
+            .map(|v| v.css_string(dark))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub enum LinearGradientDirection {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Angle(f64),
+}
+
+impl Default for LinearGradientDirection {
+    fn default() -> LinearGradientDirection {
+        LinearGradientDirection::Bottom
+    }
+}
+
+impl LinearGradientDirection {
+    fn from_values(
+        or_type_value: (String, ftd::interpreter2::PropertyValue),
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Self> {
+        match or_type_value.0.as_str() {
+            ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_TOP => {
+                Ok(LinearGradientDirection::Top)
+            }
+            ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM => {
+                Ok(LinearGradientDirection::Bottom)
+            }
+            ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_LEFT => {
+                Ok(LinearGradientDirection::Left)
+            }
+            ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_RIGHT => {
+                Ok(LinearGradientDirection::Right)
+            }
+            ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_TOP_LEFT => {
+                Ok(LinearGradientDirection::TopLeft)
+            }
+            ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_TOP_RIGHT => {
+                Ok(LinearGradientDirection::TopRight)
+            }
+            ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM_LEFT => {
+                Ok(LinearGradientDirection::BottomLeft)
+            }
+            ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM_RIGHT => {
+                Ok(LinearGradientDirection::BottomRight)
+            }
+            ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_ANGLE => {
+                Ok(LinearGradientDirection::Angle(
+                    or_type_value
+                        .1
+                        .clone()
+                        .resolve(doc, line_number)?
+                        .decimal(doc.name, line_number)?,
+                ))
+            }
+            t => ftd::executor::utils::parse_error(
+                format!(
+                    "Unknown variant `{}` for or-type `ftd.linear-gradient-directions`",
+                    t
+                ),
+                doc.name,
+                line_number,
+            ),
+        }
+    }
+
+    pub fn to_css_string(&self) -> String {
+        match self {
+            LinearGradientDirection::Top => "to top".to_string(),
+            LinearGradientDirection::Bottom => "to bottom".to_string(),
+            LinearGradientDirection::Left => "to left".to_string(),
+            LinearGradientDirection::Right => "to right".to_string(),
+            LinearGradientDirection::TopLeft => "to top left".to_string(),
+            LinearGradientDirection::TopRight => "to top right".to_string(),
+            LinearGradientDirection::BottomLeft => "to bottom left".to_string(),
+            LinearGradientDirection::BottomRight => "to bottom right".to_string(),
+            LinearGradientDirection::Angle(a) => format!("{}deg", a),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub struct LinearGradient {
+    pub direction: LinearGradientDirection,
+    pub colors: Vec<ColorStop>,
+}
+
+impl LinearGradient {
+    fn from_value(
+        value: ftd::interpreter2::PropertyValue,
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<LinearGradient> {
+        let values = record_fields(
+            value,
+            ftd::interpreter2::FTD_LINEAR_GRADIENT,
+            doc,
+            line_number,
+        )?;
+        let direction = match optional_or_type_field(&values, "direction", doc, line_number)? {
+            Some(value) => LinearGradientDirection::from_values(value, doc, line_number)?,
+            None => LinearGradientDirection::default(),
+        };
+        let colors = ColorStop::list_from_value(
+            get_field(
+                &values,
+                "colors",
+                ftd::interpreter2::FTD_LINEAR_GRADIENT,
+                doc,
+                line_number,
+            )?,
+            doc,
+            line_number,
+        )?;
+        Ok(LinearGradient { direction, colors })
+    }
+
+    fn css_string(&self, dark: bool) -> String {
+        format!(
+            "linear-gradient({}, {})",
+            self.direction.to_css_string(),
+            ColorStop::list_css_string(self.colors.as_slice(), dark)
+        )
+    }
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub enum RadialGradientShape {
+    Circle,
+    Ellipse,
+}
+
+impl Default for RadialGradientShape {
+    fn default() -> RadialGradientShape {
+        RadialGradientShape::Ellipse
+    }
+}
+
+impl RadialGradientShape {
+    fn from_values(
+        or_type_value: (String, ftd::interpreter2::PropertyValue),
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Self> {
+        match or_type_value.0.as_str() {
+            ftd::interpreter2::FTD_RADIAL_GRADIENT_SHAPE_CIRCLE => Ok(RadialGradientShape::Circle),
+            ftd::interpreter2::FTD_RADIAL_GRADIENT_SHAPE_ELLIPSE => {
+                Ok(RadialGradientShape::Ellipse)
+            }
+            t => ftd::executor::utils::parse_error(
+                format!(
+                    "Unknown variant `{}` for or-type `ftd.radial-gradient-shape`",
+                    t
+                ),
+                doc.name,
+                line_number,
+            ),
+        }
+    }
+
+    pub fn to_css_string(&self) -> String {
+        match self {
+            RadialGradientShape::Circle => "circle".to_string(),
+            RadialGradientShape::Ellipse => "ellipse".to_string(),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub struct RadialGradient {
+    pub shape: RadialGradientShape,
+    pub position: Option<BackgroundPosition>,
+    pub colors: Vec<ColorStop>,
+}
+
+impl RadialGradient {
+    fn from_value(
+        value: ftd::interpreter2::PropertyValue,
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<RadialGradient> {
+        let values = record_fields(
+            value,
+            ftd::interpreter2::FTD_RADIAL_GRADIENT,
+            doc,
+            line_number,
+        )?;
+        let shape = match optional_or_type_field(&values, "shape", doc, line_number)? {
+            Some(value) => RadialGradientShape::from_values(value, doc, line_number)?,
+            None => RadialGradientShape::default(),
+        };
+        let position = match optional_or_type_field(&values, "position", doc, line_number)? {
+            Some(value) => Some(BackgroundPosition::from_values(value, doc, line_number)?),
+            None => None,
+        };
+        let colors = ColorStop::list_from_value(
+            get_field(
+                &values,
+                "colors",
+                ftd::interpreter2::FTD_RADIAL_GRADIENT,
+                doc,
+                line_number,
+            )?,
+            doc,
+            line_number,
+        )?;
+        Ok(RadialGradient {
+            shape,
+            position,
+            colors,
+        })
+    }
+
+    fn css_string(&self, dark: bool) -> String {
+        let shape = match self.position {
+            Some(ref position) => format!(
+                "{} at {}",
+                self.shape.to_css_string(),
+                position.to_css_string()
+            ),
+            None => self.shape.to_css_string(),
+        };
+        format!(
+            "radial-gradient({}, {})",
+            shape,
+            ColorStop::list_css_string(self.colors.as_slice(), dark)
+        )
+    }
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub enum BackgroundRepeat {
+    Repeat,
+    RepeatX,
+    RepeatY,
+    NoRepeat,
+    Space,
+    Round,
+}
+
+impl BackgroundRepeat {
+    fn from_values(
+        or_type_value: (String, ftd::interpreter2::PropertyValue),
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Self> {
+        match or_type_value.0.as_str() {
+            ftd::interpreter2::FTD_BACKGROUND_REPEAT_REPEAT => Ok(BackgroundRepeat::Repeat),
+            ftd::interpreter2::FTD_BACKGROUND_REPEAT_REPEAT_X => Ok(BackgroundRepeat::RepeatX),
+            ftd::interpreter2::FTD_BACKGROUND_REPEAT_REPEAT_Y => Ok(BackgroundRepeat::RepeatY),
+            ftd::interpreter2::FTD_BACKGROUND_REPEAT_NO_REPEAT => Ok(BackgroundRepeat::NoRepeat),
+            ftd::interpreter2::FTD_BACKGROUND_REPEAT_SPACE => Ok(BackgroundRepeat::Space),
+            ftd::interpreter2::FTD_BACKGROUND_REPEAT_ROUND => Ok(BackgroundRepeat::Round),
+            t => ftd::executor::utils::parse_error(
+                format!(
+                    "Unknown variant `{}` for or-type `ftd.background-repeat`",
+                    t
+                ),
+                doc.name,
+                line_number,
+            ),
+        }
+    }
+
+    pub fn to_css_string(&self) -> String {
+        match self {
+            BackgroundRepeat::Repeat => "repeat".to_string(),
+            BackgroundRepeat::RepeatX => "repeat-x".to_string(),
+            BackgroundRepeat::RepeatY => "repeat-y".to_string(),
+            BackgroundRepeat::NoRepeat => "no-repeat".to_string(),
+            BackgroundRepeat::Space => "space".to_string(),
+            BackgroundRepeat::Round => "round".to_string(),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub enum BackgroundSize {
+    Auto,
+    Cover,
+    Contain,
+    Fixed(Length),
+}
+
+impl BackgroundSize {
+    fn from_values(
+        or_type_value: (String, ftd::interpreter2::PropertyValue),
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Self> {
+        match or_type_value.0.as_str() {
+            ftd::interpreter2::FTD_BACKGROUND_SIZE_AUTO => Ok(BackgroundSize::Auto),
+            ftd::interpreter2::FTD_BACKGROUND_SIZE_COVER => Ok(BackgroundSize::Cover),
+            ftd::interpreter2::FTD_BACKGROUND_SIZE_CONTAIN => Ok(BackgroundSize::Contain),
+            t if t.starts_with(ftd::interpreter2::FTD_BACKGROUND_SIZE_FIXED) => Ok(
+                BackgroundSize::Fixed(Length::from_value(or_type_value.1, doc, line_number)?),
+            ),
+            t => ftd::executor::utils::parse_error(
+                format!("Unknown variant `{}` for or-type `ftd.background-size`", t),
+                doc.name,
+                line_number,
+            ),
+        }
+    }
+
+    pub fn to_css_string(&self) -> String {
+        match self {
+            BackgroundSize::Auto => "auto".to_string(),
+            BackgroundSize::Cover => "cover".to_string(),
+            BackgroundSize::Contain => "contain".to_string(),
+            BackgroundSize::Fixed(l) => l.to_css_string(),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub enum BackgroundPosition {
+    Left,
+    Center,
+    Right,
+    Top,
+    Bottom,
+    LeftTop,
+    LeftBottom,
+    RightTop,
+    RightBottom,
+}
+
+impl BackgroundPosition {
+    fn from_values(
+        or_type_value: (String, ftd::interpreter2::PropertyValue),
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Self> {
+        match or_type_value.0.as_str() {
+            ftd::interpreter2::FTD_BACKGROUND_POSITION_LEFT => Ok(BackgroundPosition::Left),
+            ftd::interpreter2::FTD_BACKGROUND_POSITION_CENTER => Ok(BackgroundPosition::Center),
+            ftd::interpreter2::FTD_BACKGROUND_POSITION_RIGHT => Ok(BackgroundPosition::Right),
+            ftd::interpreter2::FTD_BACKGROUND_POSITION_TOP => Ok(BackgroundPosition::Top),
+            ftd::interpreter2::FTD_BACKGROUND_POSITION_BOTTOM => Ok(BackgroundPosition::Bottom),
+            ftd::interpreter2::FTD_BACKGROUND_POSITION_LEFT_TOP => Ok(BackgroundPosition::LeftTop),
+            ftd::interpreter2::FTD_BACKGROUND_POSITION_LEFT_BOTTOM => {
+                Ok(BackgroundPosition::LeftBottom)
+            }
+            ftd::interpreter2::FTD_BACKGROUND_POSITION_RIGHT_TOP => {
+                Ok(BackgroundPosition::RightTop)
+            }
+            ftd::interpreter2::FTD_BACKGROUND_POSITION_RIGHT_BOTTOM => {
+                Ok(BackgroundPosition::RightBottom)
+            }
+            t => ftd::executor::utils::parse_error(
+                format!(
+                    "Unknown variant `{}` for or-type `ftd.background-position`",
+                    t
+                ),
+                doc.name,
+                line_number,
+            ),
+        }
+    }
+
+    pub fn to_css_string(&self) -> String {
+        match self {
+            BackgroundPosition::Left => "left".to_string(),
+            BackgroundPosition::Center => "center".to_string(),
+            BackgroundPosition::Right => "right".to_string(),
+            BackgroundPosition::Top => "top".to_string(),
+            BackgroundPosition::Bottom => "bottom".to_string(),
+            BackgroundPosition::LeftTop => "left top".to_string(),
+            BackgroundPosition::LeftBottom => "left bottom".to_string(),
+            BackgroundPosition::RightTop => "right top".to_string(),
+            BackgroundPosition::RightBottom => "right bottom".to_string(),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub struct ImageBackground {
+    pub src: ftd::executor::ImageSrc,
+    pub repeat: Option<BackgroundRepeat>,
+    pub size: Option<BackgroundSize>,
+    pub position: Option<BackgroundPosition>,
+}
+
+impl ImageBackground {
+    fn from_value(
+        value: ftd::interpreter2::PropertyValue,
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<ImageBackground> {
+        let values = record_fields(
+            value,
+            ftd::interpreter2::FTD_IMAGE_BACKGROUND,
+            doc,
+            line_number,
+        )?;
+        let src = ftd::executor::ImageSrc::from_values(
+            record_fields(
+                get_field(
+                    &values,
+                    "src",
+                    ftd::interpreter2::FTD_IMAGE_BACKGROUND,
+                    doc,
+                    line_number,
+                )?,
+                ftd::interpreter2::FTD_IMAGE_SRC,
+                doc,
+                line_number,
+            )?,
+            doc,
+            line_number,
+        )?;
+        let repeat = match optional_or_type_field(&values, "repeat", doc, line_number)? {
+            Some(value) => Some(BackgroundRepeat::from_values(value, doc, line_number)?),
+            None => None,
+        };
+        let size = match optional_or_type_field(&values, "size", doc, line_number)? {
+            Some(value) => Some(BackgroundSize::from_values(value, doc, line_number)?),
+            None => None,
+        };
+        let position = match optional_or_type_field(&values, "position", doc, line_number)? {
+            Some(value) => Some(BackgroundPosition::from_values(value, doc, line_number)?),
+            None => None,
+        };
+        Ok(ImageBackground {
+            src,
+            repeat,
+            size,
+            position,
+        })
+    }
+
+    fn css_string(&self, dark: bool) -> String {
+        let src = if dark {
+            &self.src.dark.value
+        } else {
+            &self.src.light.value
+        };
+        let mut css = vec![format!("url('{}')", src)];
+        match (&self.position, &self.size) {
+            (Some(position), Some(size)) => css.push(format!(
+                "{} / {}",
+                position.to_css_string(),
+                size.to_css_string()
+            )),
+            // the size can only follow a position in the `background` shorthand
+            (None, Some(size)) => css.push(format!("left top / {}", size.to_css_string())),
+            (Some(position), None) => css.push(position.to_css_string()),
+            (None, None) => {}
+        }
+        if let Some(ref repeat) = self.repeat {
+            css.push(repeat.to_css_string());
+        }
+        css.join(" ")
+    }
+}
+
+fn record_fields(
+    value: ftd::interpreter2::PropertyValue,
+    rec_name: &str,
+    doc: &ftd::interpreter2::TDoc,
+    line_number: usize,
+) -> ftd::executor::Result<ftd::Map<ftd::interpreter2::PropertyValue>> {
+    let value = value.resolve(doc, line_number)?;
+    match value.inner() {
+        Some(ftd::interpreter2::Value::Record { name, fields }) if name.eq(rec_name) => Ok(fields),
+        t => ftd::executor::utils::parse_error(
+            format!(
+                "Expected value of type record `{}`, found: {:?}",
+                rec_name, t
+            ),
+            doc.name,
+            line_number,
+        ),
+    }
+}
+
+fn get_field(
+    values: &ftd::Map<ftd::interpreter2::PropertyValue>,
+    field: &str,
+    rec_name: &str,
+    doc: &ftd::interpreter2::TDoc,
+    line_number: usize,
+) -> ftd::executor::Result<ftd::interpreter2::PropertyValue> {
+    values
+        .get(field)
+        .cloned()
+        .ok_or(ftd::executor::Error::ParseError {
+            message: format!("`{}` field in {} not found", field, rec_name),
+            doc_id: doc.name.to_string(),
+            line_number,
+            span: None,
+        })
+}
+
+/// The variant and value of the or-type in the optional `field`, if any.
+fn optional_or_type_field(
+    values: &ftd::Map<ftd::interpreter2::PropertyValue>,
+    field: &str,
+    doc: &ftd::interpreter2::TDoc,
+    line_number: usize,
+) -> ftd::executor::Result<Option<(String, ftd::interpreter2::PropertyValue)>> {
+    let value = match values.get(field) {
+        Some(value) => value.clone().resolve(doc, line_number)?,
+        None => return Ok(None),
+    };
+    match value.inner() {
+        Some(value) => {
+            let (_, variant, value) = value.get_or_type(doc.name, line_number)?;
+            Ok(Some((variant.to_owned(), value.to_owned())))
+        }
+        None => Ok(None),
+    }
+}
+
+fn list_values(
+    value: ftd::interpreter2::PropertyValue,
+    doc: &ftd::interpreter2::TDoc,
+    line_number: usize,
+) -> ftd::executor::Result<Vec<ftd::interpreter2::PropertyValue>> {
+    match value.resolve(doc, line_number)?.inner() {
+        Some(ftd::interpreter2::Value::List { data, .. }) => Ok(data),
+        t => ftd::executor::utils::parse_error(
+            format!("Expected list, found: {:?}", t),
+            doc.name,
+            line_number,
+        ),
+    }
+}
+
 #[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
 pub struct Color {
     pub light: ftd::executor::Value<ColorValue>,
@@ -824,10 +1613,10 @@ pub struct Color {
 impl Color {
     fn from_value(
         value: ftd::interpreter2::PropertyValue,
-        doc: &ftd::executor::TDoc,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::executor::Result<Color> {
-        let value = value.resolve(&doc.itdoc(), line_number)?;
+        let value = value.resolve(doc, line_number)?;
         let fields = match value.inner() {
             Some(ftd::interpreter2::Value::Record { name, fields })
                 if name.eq(ftd::interpreter2::FTD_COLOR) =>
@@ -851,7 +1640,7 @@ impl Color {
 
     fn from_values(
         values: ftd::Map<ftd::interpreter2::PropertyValue>,
-        doc: &ftd::executor::TDoc,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::executor::Result<Color> {
         let light = {
@@ -861,12 +1650,13 @@ impl Color {
                     message: "`light` field in ftd.color not found".to_string(),
                     doc_id: doc.name.to_string(),
                     line_number,
+                    span: None,
                 })?;
             ftd::executor::Value::new(
                 ColorValue::color_from(
                     value
                         .clone()
-                        .resolve(&doc.itdoc(), line_number)?
+                        .resolve(doc, line_number)?
                         .string(doc.name, line_number)?,
                     doc.name,
                     line_number,
@@ -882,7 +1672,7 @@ impl Color {
                     ColorValue::color_from(
                         value
                             .clone()
-                            .resolve(&doc.itdoc(), line_number)?
+                            .resolve(doc, line_number)?
                             .string(doc.name, line_number)?,
                         doc.name,
                         line_number,
@@ -900,7 +1690,7 @@ impl Color {
 
     fn from_optional_values(
         or_type_value: Option<ftd::Map<ftd::interpreter2::PropertyValue>>,
-        doc: &ftd::executor::TDoc,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::executor::Result<Option<Self>> {
         if let Some(value) = or_type_value {
@@ -927,7 +1717,7 @@ impl Color {
         )?;
 
         Ok(ftd::executor::Value::new(
-            Color::from_optional_values(record_values.value, doc, line_number)?,
+            Color::from_optional_values(record_values.value, &doc.itdoc(), line_number)?,
             record_values.line_number,
             record_values.properties,
         ))
@@ -936,6 +1726,14 @@ impl Color {
     pub fn to_css_string(&self) -> String {
         self.light.value.to_css_string()
     }
+
+    fn css_string(&self, dark: bool) -> String {
+        if dark {
+            self.dark.value.to_css_string()
+        } else {
+            self.light.value.to_css_string()
+        }
+    }
 }
 
 #[derive(serde::Deserialize, Debug, PartialEq, Default, Clone, serde::Serialize)]
@@ -967,6 +1765,7 @@ impl ColorValue {
                     message: e.to_string(),
                     doc_id: doc_id.to_string(),
                     line_number: 0,
+                    span: None,
                 }
             })?;
 
@@ -1806,6 +2605,7 @@ impl ResponsiveType {
                     message: "`desktop` field in ftd.responsive-type not found".to_string(),
                     doc_id: doc.name.to_string(),
                     line_number,
+                    span: None,
                 })?;
             Type::from_value(value.to_owned(), doc, line_number)?
         };
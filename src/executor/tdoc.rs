@@ -3,6 +3,10 @@ pub struct TDoc<'a> {
     pub name: &'a str,
     pub aliases: &'a ftd::Map<String>,
     pub bag: &'a mut ftd::Map<ftd::interpreter2::Thing>,
+    pub markup: &'a ftd::markup::MarkupOptions,
+    pub code: &'a ftd::executor::CodeOptions,
+    /// The templates of the loops on mutable lists, by the data id of the template element
+    pub loop_templates: ftd::Map<ftd::executor::LoopTemplate>,
 }
 
 impl<'a> TDoc<'a> {
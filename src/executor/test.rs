@@ -4,72 +4,7 @@ pub fn interpret_helper(
     name: &str,
     source: &str,
 ) -> ftd::interpreter2::Result<ftd::interpreter2::Document> {
-    let mut s = ftd::interpreter2::interpret(name, source)?;
-    let document;
-    loop {
-        match s {
-            ftd::interpreter2::Interpreter::Done { document: doc } => {
-                document = doc;
-                break;
-            }
-            ftd::interpreter2::Interpreter::StuckOnImport {
-                module, state: st, ..
-            } => {
-                let source = "";
-                let mut foreign_variable = vec![];
-                let mut foreign_function = vec![];
-                if module.eq("test") {
-                    foreign_variable.push("var".to_string());
-                    foreign_function.push("fn".to_string());
-                }
-
-                let document = ftd::interpreter2::ParsedDocument::parse(module.as_str(), source)?;
-
-                s = st.continue_after_import(
-                    module.as_str(),
-                    document,
-                    foreign_variable,
-                    foreign_function,
-                    0,
-                )?;
-            }
-            ftd::interpreter2::Interpreter::StuckOnProcessor {
-                state, ast, module, ..
-            } => {
-                let variable_definition = ast.get_variable_definition(module.as_str())?;
-                let processor = variable_definition.processor.unwrap();
-                let value = ftd::interpreter2::Value::String {
-                    text: variable_definition
-                        .value
-                        .caption()
-                        .unwrap_or(processor)
-                        .to_uppercase()
-                        .to_string(),
-                };
-                s = state.continue_after_processor(value)?;
-            }
-            ftd::interpreter2::Interpreter::StuckOnForeignVariable {
-                state,
-                module,
-                variable,
-                ..
-            } => {
-                if module.eq("test") {
-                    let value = ftd::interpreter2::Value::String {
-                        text: variable.to_uppercase().to_string(),
-                    };
-                    s = state.continue_after_variable(module.as_str(), variable.as_str(), value)?;
-                } else {
-                    return ftd::interpreter2::utils::e2(
-                        format!("Unknown module {}", module),
-                        module.as_str(),
-                        0,
-                    );
-                }
-            }
-        }
-    }
-    Ok(document)
+    ftd::interpreter2::interpret_with_host(name, source, &mut ftd::interpreter2::test::TestHost)
 }
 
 #[track_caller]
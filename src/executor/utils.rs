@@ -6,6 +6,7 @@ where
         message: m.into(),
         doc_id: doc_id.to_string(),
         line_number,
+        span: None,
     })
 }
 
@@ -51,6 +52,7 @@ pub(crate) fn validate_properties_and_set_default(
                 source: Default::default(),
                 condition: None,
                 line_number: argument.line_number,
+                span: Default::default(),
             });
         } else if !expected_kind.is_optional() && !expected_kind.is_list() {
             return ftd::executor::utils::parse_error(
@@ -73,3 +75,48 @@ pub(crate) fn get_string_container(local_container: &[usize]) -> String {
         .collect::<Vec<String>>()
         .join(",")
 }
+
+/// A value of `kind` to render the template of a loop on an empty list with, `None` if there is
+/// no such value, e.g. for an or-type.
+pub(crate) fn default_value(
+    kind: &ftd::interpreter2::Kind,
+    doc: &ftd::interpreter2::TDoc,
+    line_number: usize,
+) -> ftd::executor::Result<Option<ftd::interpreter2::Value>> {
+    Ok(Some(match kind {
+        ftd::interpreter2::Kind::String => ftd::interpreter2::Value::String {
+            text: "".to_string(),
+        },
+        ftd::interpreter2::Kind::Integer => ftd::interpreter2::Value::Integer { value: 0 },
+        ftd::interpreter2::Kind::Decimal => ftd::interpreter2::Value::Decimal { value: 0.0 },
+        ftd::interpreter2::Kind::Boolean => ftd::interpreter2::Value::Boolean { value: false },
+        ftd::interpreter2::Kind::Optional { kind } => ftd::interpreter2::Value::Optional {
+            data: Box::new(None),
+            kind: kind.as_ref().to_owned().into_kind_data(),
+        },
+        ftd::interpreter2::Kind::List { kind } => ftd::interpreter2::Value::List {
+            data: vec![],
+            kind: kind.as_ref().to_owned().into_kind_data(),
+        },
+        ftd::interpreter2::Kind::Record { name } => {
+            let record = doc.get_record(name, line_number)?;
+            let mut fields: ftd::Map<ftd::interpreter2::PropertyValue> = Default::default();
+            for field in record.fields {
+                let value = match field.value {
+                    Some(value) => value,
+                    None => match default_value(&field.kind.kind, doc, line_number)? {
+                        Some(value) => value.into_property_value(false, line_number),
+                        None => return Ok(None),
+                    },
+                };
+                fields.insert(field.name, value);
+            }
+            ftd::interpreter2::Value::Record {
+                name: name.to_string(),
+                fields,
+            }
+        }
+        ftd::interpreter2::Kind::Constant { kind } => return default_value(kind, doc, line_number),
+        _ => return Ok(None),
+    }))
+}
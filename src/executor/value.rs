@@ -42,6 +42,7 @@ pub(crate) fn get_value_from_properties_using_key_and_arguments(
                 message: format!("Cannot find `{}` argument", key),
                 doc_id: doc.name.to_string(),
                 line_number,
+                span: None,
             })?;
 
     let sources = argument.to_sources();
@@ -602,3 +603,59 @@ pub fn optional_or_type(
         ),
     }
 }
+
+pub fn or_type_list(
+    key: &str,
+    properties: &[ftd::interpreter2::Property],
+    arguments: &[ftd::interpreter2::Argument],
+    doc: &ftd::executor::TDoc,
+    line_number: usize,
+    rec_name: &str,
+) -> ftd::executor::Result<ftd::executor::Value<Vec<(String, ftd::interpreter2::PropertyValue)>>> {
+    let value = get_value_from_properties_using_key_and_arguments(
+        key,
+        properties,
+        arguments,
+        doc,
+        line_number,
+    )?;
+
+    match value.value.and_then(|v| v.inner()) {
+        Some(ftd::interpreter2::Value::List { data, kind }) if kind.kind.is_or_type() => {
+            let mut values = vec![];
+            for d in data {
+                let d = d.resolve(&doc.itdoc(), line_number)?;
+                let (name, variant, property_value) = d.get_or_type(doc.name, line_number)?;
+                if name.ne(rec_name) {
+                    return ftd::executor::utils::parse_error(
+                        format!(
+                            "Expected value of type or-type `{}`, found: `{}`",
+                            rec_name, name
+                        ),
+                        doc.name,
+                        line_number,
+                    );
+                }
+                values.push((variant.to_owned(), property_value.to_owned()));
+            }
+            Ok(ftd::executor::Value::new(
+                values,
+                value.line_number,
+                value.properties,
+            ))
+        }
+        None => Ok(ftd::executor::Value::new(
+            vec![],
+            value.line_number,
+            value.properties,
+        )),
+        t => ftd::executor::utils::parse_error(
+            format!(
+                "Expected value of type or-type `{}` list, found: {:?}",
+                rec_name, t
+            ),
+            doc.name,
+            line_number,
+        ),
+    }
+}
@@ -8,18 +8,247 @@ impl<'a> DataGenerator<'a> {
     }
 
     pub(crate) fn get_data(&self) -> ftd::html1::Result<ftd::Map<serde_json::Value>> {
+        Ok(self
+            .variables()?
+            .into_iter()
+            .map(|(k, v)| (ftd::html1::utils::js_reference_name(k.as_str()), v))
+            .collect())
+    }
+
+    /// Same as `get_data`, but only with the variables the runtime can read or mutate, see
+    /// `reachable_variables`, and the size of the kept and the dropped variables of each module.
+    pub(crate) fn get_reachable_data(
+        &self,
+        node: &ftd::node::Node,
+        var_dependencies: &ftd::VecMap<String>,
+    ) -> ftd::html1::Result<(ftd::Map<serde_json::Value>, DataReport)> {
+        let reachable = self.reachable_variables(node, var_dependencies);
         let mut d: ftd::Map<serde_json::Value> = Default::default();
+        let mut report = DataReport::default();
+        for (k, v) in self.variables()? {
+            let kept = reachable.contains(&k);
+            report.insert(k.as_str(), &v, kept);
+            if kept {
+                d.insert(ftd::html1::utils::js_reference_name(k.as_str()), v);
+            }
+        }
+        Ok((d, report))
+    }
+
+    fn variables(&self) -> ftd::html1::Result<Vec<(String, serde_json::Value)>> {
+        let mut d = vec![];
         for (k, v) in self.doc.bag().iter() {
             if let ftd::interpreter2::Thing::Variable(ftd::interpreter2::Variable {
-                value, ..
+                value,
+                conditional_value,
+                ..
             }) = v
             {
+                // same as `ftd::interpreter2::TDoc::resolve`, the value of the first condition
+                // that is true, e.g. of an argument of a component instance
+                let mut value = value;
+                for conditional in conditional_value.iter() {
+                    if conditional.condition.eval(self.doc)? {
+                        value = &conditional.value;
+                        break;
+                    }
+                }
                 let value = value.clone().resolve(self.doc, value.line_number())?;
                 if let Some(value) = ftd::interpreter2::utils::get_value(self.doc, &value)? {
-                    d.insert(ftd::html1::utils::js_reference_name(k), value);
+                    d.push((k.to_string(), value));
                 }
             }
         }
         Ok(d)
     }
+
+    /// The variables the runtime can read or mutate:
+    ///
+    /// - the variables of the `ftd` module, `ftd.js` reads and sets them by name, e.g.
+    ///   `ftd#dark-mode`
+    /// - the variables the events of `node` pass to their functions, and the lists of its loop
+    ///   templates, see `ftd::executor::LoopTemplate`
+    /// - the variables a property of `node` reads, if one of them can change, as the property
+    ///   is computed again from all of them, see `DependencyGenerator`
+    /// - the variables a reachable variable that can change depends on, or that depend on it,
+    ///   as they are read and set when it changes, see `VariableDependencyGenerator`
+    ///
+    /// A variable can change if it is mutable, is of the `ftd` module or depends on a variable
+    /// that can change.
+    fn reachable_variables(
+        &self,
+        node: &ftd::node::Node,
+        var_dependencies: &ftd::VecMap<String>,
+    ) -> std::collections::BTreeSet<String> {
+        let mut dependents: ftd::Map<Vec<String>> = Default::default();
+        let mut dependencies: ftd::Map<Vec<String>> = Default::default();
+        let graph = ftd::html1::VariableDependencyGenerator::new("", self.doc).get_dependencies();
+        for (dependency, dependent) in graph.value.iter() {
+            let dependency = self.variable_name(dependency);
+            for dependent in dependent {
+                let dependent = self.variable_name(dependent);
+                dependents
+                    .entry(dependency.to_string())
+                    .or_default()
+                    .push(dependent.to_string());
+                dependencies
+                    .entry(dependent)
+                    .or_default()
+                    .push(dependency.to_string());
+            }
+        }
+
+        let mut changing: std::collections::BTreeSet<String> = self
+            .doc
+            .bag()
+            .iter()
+            .filter_map(|(k, v)| match v {
+                ftd::interpreter2::Thing::Variable(v) if v.mutable || is_ftd_variable(k) => {
+                    Some(k.to_string())
+                }
+                _ => None,
+            })
+            .collect();
+        let mut stack = changing.iter().cloned().collect::<Vec<_>>();
+        while let Some(variable) = stack.pop() {
+            for dependent in dependents.get(&variable).into_iter().flatten() {
+                if changing.insert(dependent.to_string()) {
+                    stack.push(dependent.to_string());
+                }
+            }
+        }
+
+        let mut reachable: std::collections::BTreeSet<String> = self
+            .doc
+            .bag()
+            .keys()
+            .filter(|k| is_ftd_variable(k))
+            .cloned()
+            .collect();
+        self.event_references(node, &mut reachable);
+
+        let mut node_changes: ftd::Map<Vec<String>> = Default::default();
+        for (variable, node_change_ids) in var_dependencies.value.iter() {
+            let variable = self.variable_name(variable);
+            for node_change_id in node_change_ids {
+                node_changes
+                    .entry(node_change_id.to_string())
+                    .or_default()
+                    .push(variable.to_string());
+            }
+        }
+        for variables in node_changes.into_values() {
+            if variables.iter().any(|v| changing.contains(v)) {
+                reachable.extend(variables);
+            }
+        }
+
+        let mut stack = reachable.iter().cloned().collect::<Vec<_>>();
+        while let Some(variable) = stack.pop() {
+            if !changing.contains(&variable) {
+                continue;
+            }
+            for other in dependents
+                .get(&variable)
+                .into_iter()
+                .chain(dependencies.get(&variable))
+                .flatten()
+            {
+                if reachable.insert(other.to_string()) {
+                    stack.push(other.to_string());
+                }
+            }
+        }
+
+        reachable
+    }
+
+    fn event_references(
+        &self,
+        node: &ftd::node::Node,
+        references: &mut std::collections::BTreeSet<String>,
+    ) {
+        for event in node.events.iter() {
+            for value in event.action.values.values() {
+                for reference in
+                    ftd::html1::utils::dependencies_from_property_value(value, self.doc)
+                {
+                    references.insert(self.variable_name(reference.as_str()));
+                }
+            }
+        }
+        if let Some(loop_template) = node.loop_template.as_ref() {
+            references.insert(self.variable_name(loop_template.on.as_str()));
+        }
+        for child in node.children.iter() {
+            self.event_references(child, references);
+        }
+    }
+
+    /// The variable `reference` is in, e.g. `foo#person` for `foo#person.name`.
+    fn variable_name(&self, reference: &str) -> String {
+        if self.doc.bag().contains_key(reference) {
+            return reference.to_string();
+        }
+        ftd::interpreter2::utils::get_doc_name_and_remaining(reference, self.doc.name, 0).0
+    }
+}
+
+fn is_ftd_variable(name: &str) -> bool {
+    name.starts_with("ftd#")
+}
+
+/// The size of the page data of each module, see `HtmlUI::from_node_data_pruned`.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
+pub struct DataReport {
+    pub modules: ftd::Map<ModuleData>,
+}
+
+/// The variables of a module in the page data, and the ones left out of it. Sizes are of the
+/// compact json of the values.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
+pub struct ModuleData {
+    pub kept: usize,
+    pub kept_bytes: usize,
+    pub dropped: usize,
+    pub dropped_bytes: usize,
+}
+
+impl DataReport {
+    /// Every variable of `data` counted as kept.
+    pub(crate) fn from_data(data: &ftd::Map<serde_json::Value>) -> DataReport {
+        let mut report = DataReport::default();
+        for (k, v) in data.iter() {
+            report.insert(k.as_str(), v, true);
+        }
+        report
+    }
+
+    fn insert(&mut self, variable: &str, value: &serde_json::Value, kept: bool) {
+        let bytes = serde_json::to_string(value)
+            .map(|v| v.len())
+            .unwrap_or_default();
+        let module = variable.split('#').next().unwrap_or(variable);
+        let data = self.modules.entry(module.to_string()).or_default();
+        if kept {
+            data.kept += 1;
+            data.kept_bytes += bytes;
+        } else {
+            data.dropped += 1;
+            data.dropped_bytes += bytes;
+        }
+    }
+}
+
+impl std::fmt::Display for DataReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (module, data) in self.modules.iter() {
+            writeln!(
+                f,
+                "{}: kept {} variables ({} bytes), dropped {} variables ({} bytes)",
+                module, data.kept, data.kept_bytes, data.dropped, data.dropped_bytes
+            )?;
+        }
+        Ok(())
+    }
 }
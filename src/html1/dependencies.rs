@@ -2,6 +2,9 @@ pub struct DependencyGenerator<'a> {
     pub id: &'a str,
     pub node: &'a ftd::node::Node,
     pub doc: &'a ftd::interpreter2::TDoc<'a>,
+    /// The full data id of the loop template `node` is in, or is, see
+    /// `ftd::executor::LoopTemplate`
+    pub template: Option<String>,
 }
 
 impl<'a> DependencyGenerator<'a> {
@@ -10,31 +13,54 @@ impl<'a> DependencyGenerator<'a> {
         node: &'a ftd::node::Node,
         doc: &'a ftd::interpreter2::TDoc,
     ) -> DependencyGenerator<'a> {
-        DependencyGenerator { id, node, doc }
+        DependencyGenerator {
+            id,
+            node,
+            doc,
+            template: None,
+        }
     }
 
-    pub(crate) fn get_dependencies(&self) -> ftd::html1::Result<(String, ftd::VecMap<String>)> {
+    pub(crate) fn get_dependencies(
+        &self,
+    ) -> ftd::html1::Result<(String, Vec<ftd::html1::NodeDependency>, ftd::VecMap<String>)> {
+        use itertools::Itertools;
+
+        let mut node_dependencies = vec![];
+        let mut loop_templates = vec![];
         let mut var_dependencies: ftd::VecMap<String> = Default::default();
-        let dependencies = self.get_dependencies_(&mut var_dependencies)?;
-        if dependencies.trim().is_empty() {
-            return Ok(("".to_string(), Default::default()));
+        self.get_dependencies_(
+            &mut node_dependencies,
+            &mut loop_templates,
+            &mut var_dependencies,
+        )?;
+        if node_dependencies.is_empty() && loop_templates.is_empty() {
+            return Ok(("".to_string(), vec![], Default::default()));
         }
-        Ok((
-            format!("window.node_change_{} = {{}};\n{}", self.id, dependencies),
-            var_dependencies,
-        ))
+
+        let mut result = loop_templates;
+        if !node_dependencies.is_empty() {
+            let node_dependencies_json = node_dependencies
+                .iter()
+                .map(|v| serde_json::to_string(v).expect("failed to convert dependency to json"))
+                .join(",\n");
+            result.push(format!(
+                "window.ftd.dependencies(\"{}\", [\n{}\n]);",
+                self.id, node_dependencies_json
+            ));
+        }
+        Ok((result.join("\n"), node_dependencies, var_dependencies))
     }
 
     fn get_dependencies_(
         &self,
+        node_dependencies: &mut Vec<ftd::html1::NodeDependency>,
+        loop_templates: &mut Vec<String>,
         var_dependencies: &mut ftd::VecMap<String>,
-    ) -> ftd::html1::Result<String> {
+    ) -> ftd::html1::Result<()> {
         let node_data_id = ftd::html1::utils::full_data_id(self.id, self.node.data_id.as_str());
-        let mut result = vec![];
-
-        {
-            let mut expressions = vec![];
 
+        if let Some(condition) = self.node.condition.as_ref() {
             let node_change_id =
                 ftd::html1::utils::node_change_id(node_data_id.as_str(), "display");
             dependency_map_from_condition(
@@ -43,73 +69,42 @@ impl<'a> DependencyGenerator<'a> {
                 node_change_id.as_str(),
                 self.doc,
             );
-
-            let condition = self
-                .node
-                .condition
-                .as_ref()
-                .map(ftd::html1::utils::get_condition_string);
-
-            let key = format!(
-                "document.querySelector(`[data-id=\"{}\"]`).style[\"display\"]",
-                node_data_id
-            );
-
-            if let Some(condition) = condition {
-                let pos_value = format!("{} = \"{}\";", key, self.node.display);
-                let neg_value = format!("{} = \"none\";", key);
-                expressions.push((Some(condition), pos_value));
-                expressions.push((None, neg_value));
-            }
-
-            let value = ftd::html1::utils::js_expression_from_list(
-                expressions,
-                Some(key.as_str()),
-                format!("{} = null;", key).as_str(),
-            );
-            if !value.trim().is_empty() {
-                result.push(format!(
-                    indoc::indoc! {"
-                         window.node_change_{id}[\"{key}\"] = function(data) {{
-                                {value}
-                         }}
-                    "},
-                    id = self.id,
-                    key = node_change_id,
-                    value = value.trim(),
-                ));
-            }
+            node_dependencies.extend(self.node_dependency(
+                node_change_id,
+                node_data_id.as_str(),
+                ftd::html1::Property::Style("display".to_string()),
+                vec![
+                    (
+                        Some(ftd::html1::Expression::from_condition(condition)),
+                        ftd::html1::Expression::Value(serde_json::json!(self.node.display)),
+                    ),
+                    (
+                        None,
+                        ftd::html1::Expression::Value(serde_json::json!("none")),
+                    ),
+                ],
+                ftd::html1::Expression::Value(serde_json::Value::Null),
+            ));
         }
 
         {
             let node_change_id = ftd::html1::utils::node_change_id(node_data_id.as_str(), "text");
             let mut expressions = vec![];
             let mut is_static = true;
-            let key = format!(
-                "document.querySelector(`[data-id=\"{}\"]`).innerHTML",
-                node_data_id,
-            );
             for property_with_pattern in self.node.text.properties.iter() {
                 let property = &property_with_pattern.property;
-                let condition = property
-                    .condition
-                    .as_ref()
-                    .map(ftd::html1::utils::get_condition_string);
-
-                if !is_static_expression(&property.value, &condition, self.doc) {
+                if !is_static_expression(&property.value, &property.condition, self.doc) {
                     is_static = false;
                 }
 
-                if let Some(value_string) =
-                    ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                        self.id,
-                        self.doc,
-                        &property.value,
-                        &property_with_pattern.pattern_with_eval,
-                        None,
-                        false,
-                    )?
-                {
+                if let Some(value) = ftd::html1::Expression::from_property_value_with_pattern(
+                    self.id,
+                    self.doc,
+                    &property.value,
+                    &property_with_pattern.pattern_with_eval,
+                    None,
+                    false,
+                )? {
                     dependency_map_from_condition(
                         var_dependencies,
                         &property.condition,
@@ -122,530 +117,138 @@ impl<'a> DependencyGenerator<'a> {
                         node_change_id.as_str(),
                         self.doc,
                     );
-
-                    let value = format!("{} = {};", key, value_string);
-                    expressions.push((condition, value));
+                    expressions.push((
+                        property
+                            .condition
+                            .as_ref()
+                            .map(ftd::html1::Expression::from_condition),
+                        value,
+                    ));
                 }
             }
-            let value = ftd::html1::utils::js_expression_from_list(
-                expressions,
-                Some(key.as_str()),
-                format!(
-                    "{} = {}",
-                    key,
-                    self.node
-                        .text
-                        .default
-                        .clone()
-                        .unwrap_or_else(|| "null".to_string())
-                )
-                .as_str(),
-            );
-            if !value.trim().is_empty() && !is_static {
-                result.push(format!(
-                    indoc::indoc! {"
-                         window.node_change_{id}[\"{key}\"] = function(data) {{
-                                {value}
-                         }}
-                    "},
-                    id = self.id,
-                    key = node_change_id,
-                    value = value.trim(),
+            if let (true, Some(light), Some(dark)) = (
+                is_static,
+                self.node.text.value.as_ref(),
+                self.node.dark_text.as_ref().and_then(|v| v.value.as_ref()),
+            ) {
+                // same as the dark mode dependent properties, e.g. `ftd.color`, the text is
+                // switched when `ftd#dark-mode` changes
+                is_static = false;
+                expressions.push((
+                    None,
+                    ftd::html1::Expression::Theme {
+                        light: Box::new(ftd::html1::Expression::Value(serde_json::json!(light))),
+                        dark: Box::new(ftd::html1::Expression::Value(serde_json::json!(dark))),
+                    },
+                ));
+                var_dependencies.insert("ftd#dark-mode".to_string(), node_change_id.to_string());
+            }
+            if !is_static {
+                node_dependencies.extend(self.node_dependency(
+                    node_change_id,
+                    node_data_id.as_str(),
+                    ftd::html1::Property::Text,
+                    expressions,
+                    default_expression(self.node.text.default.as_ref()),
                 ));
             }
         }
 
         for (key, attribute) in self.node.attrs.iter() {
-            let mut expressions = vec![];
-            let mut is_static = true;
-            let node_change_id = ftd::html1::utils::node_change_id(node_data_id.as_str(), key);
-            for property_with_pattern in attribute.properties.iter() {
-                let property = &property_with_pattern.property;
-                let condition = property
-                    .condition
-                    .as_ref()
-                    .map(ftd::html1::utils::get_condition_string);
-
-                if !is_static_expression(&property.value, &condition, self.doc) {
-                    is_static = false;
-                }
-
-                if ftd::html1::utils::is_device_dependent(&property.value, self.doc)? {
-                    let mut desktop_value_string = "".to_string();
-                    if let Some(value_string) =
-                        ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                            self.id,
-                            self.doc,
-                            &property.value,
-                            &property_with_pattern.pattern_with_eval,
-                            Some("desktop".to_string()),
-                            false,
-                        )?
-                    {
-                        desktop_value_string = value_string;
-                    }
-
-                    let mut mobile_value_string = "".to_string();
-                    if let Some(value_string) =
-                        ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                            self.id,
-                            self.doc,
-                            &property.value,
-                            &property_with_pattern.pattern_with_eval,
-                            Some("mobile".to_string()),
-                            false,
-                        )?
-                    {
-                        mobile_value_string = value_string;
-                    }
-                    if desktop_value_string.ne(&mobile_value_string) {
-                        is_static = false;
-                        let value = ftd::html1::utils::js_expression_from_list(
-                            std::iter::IntoIterator::into_iter([
-                                (
-                                    Some("data[\"ftd#device\"] == \"desktop\"".to_string()),
-                                    format!(
-                                        "document.querySelector(`[data-id=\"{}\"]`).setAttribute(\"{}\", {});",
-                                        node_data_id, key, desktop_value_string
-                                    )
-                                ),
-                                (None, format!(
-                                    "document.querySelector(`[data-id=\"{}\"]`).setAttribute(\"{}\", {});",
-                                    node_data_id, key, mobile_value_string
-                                )),
-                            ])
-                                .collect(),
-                            Some(key.as_str()),attribute
-                                .default
-                                .as_ref()
-                                .map(|v| {
-                                    format!(
-                                        "document.querySelector(`[data-id=\"{}\"]`).setAttribute(\"{}\", {});",
-                                        node_data_id, key, v
-                                    )
-                                })
-                                .unwrap_or_else(|| {
-                                    format!(
-                                        "document.querySelector(`[data-id=\"{}\"]`).removeAttribute(\"{}\");",
-                                        node_data_id, key,
-                                    )
-                                })
-                                .as_str(),
-                        );
-                        expressions.push((condition, value));
-                    } else {
-                        expressions
-                            .push((condition, format!("{} = {};", key, desktop_value_string)));
-                    }
-
-                    if !desktop_value_string.is_empty() {
-                        dependency_map_from_condition(
-                            var_dependencies,
-                            &property.condition,
-                            node_change_id.as_str(),
-                            self.doc,
-                        );
-                        dependency_map_from_property_value(
-                            var_dependencies,
-                            &property.value,
-                            node_change_id.as_str(),
-                            self.doc,
-                        );
-                        var_dependencies
-                            .insert("ftd#device".to_string(), node_change_id.to_string());
-                    }
-                    continue;
-                }
-
-                if ftd::html1::utils::is_dark_mode_dependent(&property.value, self.doc)? {
-                    // Todo: If the property.value is static then resolve it and use
-                    let mut light_value_string = "".to_string();
-                    if let Some(value_string) =
-                        ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                            self.id,
-                            self.doc,
-                            &property.value,
-                            &property_with_pattern.pattern_with_eval,
-                            Some("light".to_string()),
-                            false,
-                        )?
-                    {
-                        light_value_string = value_string;
-                    }
-                    let mut dark_value_string = "".to_string();
-                    if let Some(value_string) =
-                        ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                            self.id,
-                            self.doc,
-                            &property.value,
-                            &property_with_pattern.pattern_with_eval,
-                            Some("dark".to_string()),
-                            false,
-                        )?
-                    {
-                        dark_value_string = value_string;
-                    }
-
-                    if light_value_string.ne(&dark_value_string) {
-                        is_static = false;
-                        let value = ftd::html1::utils::js_expression_from_list(
-                            std::iter::IntoIterator::into_iter([
-                                (
-                                    Some("!data[\"ftd#dark-mode\"]".to_string()),
-                                    format!(
-                                        "document.querySelector(`[data-id=\"{}\"]`).setAttribute(\"{}\", {});",
-                                        node_data_id, key, light_value_string
-                                    )
-                                ),
-                                (None, format!(
-                                    "document.querySelector(`[data-id=\"{}\"]`).setAttribute(\"{}\", {});",
-                                    node_data_id, key, dark_value_string
-                                )),
-                            ])
-                            .collect(),
-                            Some(key.as_str()),attribute
-                                .default
-                                .as_ref()
-                                .map(|v| {
-                                    format!(
-                                        "document.querySelector(`[data-id=\"{}\"]`).setAttribute(\"{}\", {});",
-                                        node_data_id, key, v
-                                    )
-                                })
-                                .unwrap_or_else(|| {
-                                    format!(
-                                        "document.querySelector(`[data-id=\"{}\"]`).removeAttribute(\"{}\");",
-                                        node_data_id, key,
-                                    )
-                                })
-                                .as_str(),
-                        );
-                        expressions.push((condition, value));
-                    } else {
-                        expressions.push((condition, format!("{} = {};", key, light_value_string)));
-                    }
+            node_dependencies.extend(self.value_dependency(
+                node_data_id.as_str(),
+                ftd::html1::Property::Attribute(key.to_string()),
+                key,
+                attribute,
+                key.eq("class"),
+                var_dependencies,
+            )?);
+        }
 
-                    if !light_value_string.is_empty() {
-                        dependency_map_from_condition(
-                            var_dependencies,
-                            &property.condition,
-                            node_change_id.as_str(),
-                            self.doc,
-                        );
-                        dependency_map_from_property_value(
-                            var_dependencies,
-                            &property.value,
-                            node_change_id.as_str(),
-                            self.doc,
-                        );
-                        var_dependencies
-                            .insert("ftd#dark-mode".to_string(), node_change_id.to_string());
-                    }
-                    continue;
-                }
+        for (key, style) in self.node.style.iter() {
+            node_dependencies.extend(self.value_dependency(
+                node_data_id.as_str(),
+                ftd::html1::Property::Style(key.to_string()),
+                key,
+                style,
+                false,
+                var_dependencies,
+            )?);
+        }
 
-                if let Some(value_string) =
-                    ftd::html1::utils::get_formatted_dep_string_from_property_value(
+        for children in self.node.children.iter() {
+            let template = match children.loop_template.as_ref() {
+                Some(loop_template) => {
+                    loop_templates.push(self.loop_template(children, loop_template));
+                    Some(ftd::html1::utils::full_data_id(
                         self.id,
-                        self.doc,
-                        &property.value,
-                        &property_with_pattern.pattern_with_eval,
-                        None,
-                        key.eq("class"),
-                    )?
-                {
-                    dependency_map_from_condition(
-                        var_dependencies,
-                        &property.condition,
-                        node_change_id.as_str(),
-                        self.doc,
-                    );
-                    dependency_map_from_property_value(
-                        var_dependencies,
-                        &property.value,
-                        node_change_id.as_str(),
-                        self.doc,
-                    );
-                    let value = format!(
-                        "document.querySelector(`[data-id=\"{}\"]`).setAttribute(\"{}\", {});",
-                        node_data_id, key, value_string
-                    );
-                    expressions.push((condition, value));
+                        children.data_id.as_str(),
+                    ))
                 }
+                None => self.template.clone(),
+            };
+            DependencyGenerator {
+                id: self.id,
+                node: children,
+                doc: self.doc,
+                template,
             }
-            let value = ftd::html1::utils::js_expression_from_list(
-                expressions,
-                Some(key),
-                attribute
-                    .default
-                    .as_ref()
-                    .map(|v| {
-                        format!(
-                            "document.querySelector(`[data-id=\"{}\"]`).setAttribute(\"{}\", {});",
-                            node_data_id, key, v
-                        )
-                    })
-                    .unwrap_or_else(|| {
-                        format!(
-                            "document.querySelector(`[data-id=\"{}\"]`).removeAttribute(\"{}\");",
-                            node_data_id, key,
-                        )
-                    })
-                    .as_str(),
-            );
-            if !value.trim().is_empty() && !is_static {
-                result.push(format!(
-                    indoc::indoc! {"
-                         window.node_change_{id}[\"{key}\"] = function(data) {{
-                                {value}
-                         }}
-                    "},
-                    id = self.id,
-                    key = node_change_id,
-                    value = value.trim(),
-                ));
-            }
+            .get_dependencies_(node_dependencies, loop_templates, var_dependencies)?;
         }
+        Ok(())
+    }
 
-        for (key, attribute) in self.node.style.iter() {
-            let mut expressions = vec![];
-            let mut is_static = true;
-            let node_change_id = ftd::html1::utils::node_change_id(node_data_id.as_str(), key);
-            let key = format!(
-                "document.querySelector(`[data-id=\"{}\"]`).style[\"{}\"]",
-                node_data_id, key
-            );
-            for property_with_pattern in attribute.properties.iter() {
-                let property = &property_with_pattern.property;
-                let condition = property
-                    .condition
-                    .as_ref()
-                    .map(ftd::html1::utils::get_condition_string);
-
-                if !is_static_expression(&property.value, &condition, self.doc) {
-                    is_static = false;
-                }
-
-                if ftd::html1::utils::is_device_dependent(&property.value, self.doc)? {
-                    let mut desktop_value_string = "".to_string();
-                    if let Some(value_string) =
-                        ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                            self.id,
-                            self.doc,
-                            &property.value,
-                            &property_with_pattern.pattern_with_eval,
-                            Some("desktop".to_string()),
-                            false,
-                        )?
-                    {
-                        desktop_value_string = value_string;
-                    }
-
-                    let mut mobile_value_string = "".to_string();
-                    if let Some(value_string) =
-                        ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                            self.id,
-                            self.doc,
-                            &property.value,
-                            &property_with_pattern.pattern_with_eval,
-                            Some("mobile".to_string()),
-                            false,
-                        )?
-                    {
-                        mobile_value_string = value_string;
-                    }
-                    if desktop_value_string.ne(&mobile_value_string) {
-                        is_static = false;
-                        let value = ftd::html1::utils::js_expression_from_list(
-                            std::iter::IntoIterator::into_iter([
-                                (
-                                    Some("data[\"ftd#device\"] == \"desktop\"".to_string()),
-                                    format!("{} = {};", key, desktop_value_string),
-                                ),
-                                (None, format!("{} = {};", key, mobile_value_string)),
-                            ])
-                            .collect(),
-                            Some(key.as_str()),
-                            format!(
-                                "{} = {}",
-                                key,
-                                attribute
-                                    .default
-                                    .clone()
-                                    .unwrap_or_else(|| "null".to_string())
-                            )
-                            .as_str(),
-                        );
-                        expressions.push((condition, value));
-                    } else {
-                        expressions
-                            .push((condition, format!("{} = {};", key, desktop_value_string)));
-                    }
-
-                    if !desktop_value_string.is_empty() {
-                        dependency_map_from_condition(
-                            var_dependencies,
-                            &property.condition,
-                            node_change_id.as_str(),
-                            self.doc,
-                        );
-                        dependency_map_from_property_value(
-                            var_dependencies,
-                            &property.value,
-                            node_change_id.as_str(),
-                            self.doc,
-                        );
-                        var_dependencies
-                            .insert("ftd#device".to_string(), node_change_id.to_string());
-                    }
-                    continue;
-                }
-
-                if ftd::html1::utils::is_dark_mode_dependent(&property.value, self.doc)? {
-                    // Todo: If the property.value is static then resolve it and use
-                    let mut light_value_string = "".to_string();
-                    if let Some(value_string) =
-                        ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                            self.id,
-                            self.doc,
-                            &property.value,
-                            &property_with_pattern.pattern_with_eval,
-                            Some("light".to_string()),
-                            false,
-                        )?
-                    {
-                        light_value_string = value_string;
-                    }
-                    let mut dark_value_string = "".to_string();
-                    if let Some(value_string) =
-                        ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                            self.id,
-                            self.doc,
-                            &property.value,
-                            &property_with_pattern.pattern_with_eval,
-                            Some("dark".to_string()),
-                            false,
-                        )?
-                    {
-                        dark_value_string = value_string;
-                    }
-
-                    if light_value_string.ne(&dark_value_string) {
-                        is_static = false;
-                        let value = ftd::html1::utils::js_expression_from_list(
-                            std::iter::IntoIterator::into_iter([
-                                (
-                                    Some("!data[\"ftd#dark-mode\"]".to_string()),
-                                    format!("{} = {};", key, light_value_string),
-                                ),
-                                (None, format!("{} = {};", key, dark_value_string)),
-                            ])
-                            .collect(),
-                            Some(key.as_str()),
-                            format!(
-                                "{} = {}",
-                                key,
-                                attribute
-                                    .default
-                                    .clone()
-                                    .unwrap_or_else(|| "null".to_string())
-                            )
-                            .as_str(),
-                        );
-                        expressions.push((condition, value));
-                    } else {
-                        expressions.push((condition, format!("{} = {};", key, light_value_string)));
-                    }
-
-                    if !light_value_string.is_empty() {
-                        dependency_map_from_condition(
-                            var_dependencies,
-                            &property.condition,
-                            node_change_id.as_str(),
-                            self.doc,
-                        );
-                        dependency_map_from_property_value(
-                            var_dependencies,
-                            &property.value,
-                            node_change_id.as_str(),
-                            self.doc,
-                        );
-                        var_dependencies
-                            .insert("ftd#dark-mode".to_string(), node_change_id.to_string());
-                    }
-
-                    /*let mut light_value_string = "".to_string();
-                    if let Some(value_string) =
-                        ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                            self.id,
-                            self.doc,
-                            &property.value,
-                            &property_with_pattern.pattern_with_eval,
-                            Some("light".to_string()),
-                            false,
-                        )?
-                    {
-                        let value = format!("{} = {};", key, value_string);
-                        let condition = Some(match condition {
-                            Some(ref c) => format!("{} && !data[\"ftd#dark-mode\"]", c),
-                            None => "!data[\"ftd#dark-mode\"]".to_string(),
-                        });
-                        expressions.push((condition, value));
-                        light_value_string = value_string;
-                    }
-
-                    let mut dark_value_string = "".to_string();
-                    if let Some(value_string) =
-                        ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                            self.id,
-                            self.doc,
-                            &property.value,
-                            &property_with_pattern.pattern_with_eval,
-                            Some("dark".to_string()),
-                            false,
-                        )?
-                    {
-                        let value = format!("{} = {};", key, value_string);
-                        let condition = Some(match condition {
-                            Some(ref c) => format!("{} && data[\"ftd#dark-mode\"]", c),
-                            None => "data[\"ftd#dark-mode\"]".to_string(),
-                        });
-                        expressions.push((condition, value));
-                        dark_value_string = value_string;
-                    }
+    /// The dependency of an attribute or a style of the node, if it is not static.
+    fn value_dependency(
+        &self,
+        node_data_id: &str,
+        property: ftd::html1::Property,
+        key: &str,
+        node_value: &ftd::node::Value,
+        string_needs_no_quotes: bool,
+        var_dependencies: &mut ftd::VecMap<String>,
+    ) -> ftd::html1::Result<Option<ftd::html1::NodeDependency>> {
+        let node_change_id = ftd::html1::utils::node_change_id(node_data_id, key);
+        let mut expressions = vec![];
+        let mut is_static = true;
+        for property_with_pattern in node_value.properties.iter() {
+            let property = &property_with_pattern.property;
+            let condition = property
+                .condition
+                .as_ref()
+                .map(ftd::html1::Expression::from_condition);
 
-                    if !light_value_string.eq(&dark_value_string)
-                        || condition.is_some()
-                        || length > 0
-                    {
-                        dependency_map_from_condition(
-                            var_dependencies,
-                            &property.condition,
-                            node_change_id.as_str(),
-                            self.doc,
-                        );
-                        dependency_map_from_property_value(
-                            var_dependencies,
-                            &property.value,
-                            node_change_id.as_str(),
-                            self.doc,
-                        );
-                        var_dependencies
-                            .insert("ftd#dark-mode".to_string(), node_change_id.to_string());
-                    }*/
-                    continue;
-                }
+            if !is_static_expression(&property.value, &property.condition, self.doc) {
+                is_static = false;
+            }
 
-                if let Some(value_string) =
-                    ftd::html1::utils::get_formatted_dep_string_from_property_value(
-                        self.id,
-                        self.doc,
-                        &property.value,
-                        &property_with_pattern.pattern_with_eval,
-                        None,
-                        false,
-                    )?
-                {
+            // the value is a record with a field for each device, or for each mode
+            let variants = if ftd::html1::utils::is_device_dependent(&property.value, self.doc)? {
+                Some(("desktop", "mobile", "ftd#device"))
+            } else if ftd::html1::utils::is_dark_mode_dependent(&property.value, self.doc)? {
+                Some(("light", "dark", "ftd#dark-mode"))
+            } else {
+                None
+            };
+
+            if let Some((first, second, variable)) = variants {
+                let first = ftd::html1::Expression::from_property_value_with_pattern(
+                    self.id,
+                    self.doc,
+                    &property.value,
+                    &property_with_pattern.pattern_with_eval,
+                    Some(first.to_string()),
+                    false,
+                )?;
+                let second = ftd::html1::Expression::from_property_value_with_pattern(
+                    self.id,
+                    self.doc,
+                    &property.value,
+                    &property_with_pattern.pattern_with_eval,
+                    Some(second.to_string()),
+                    false,
+                )?;
+                if let Some(first) = first {
                     dependency_map_from_condition(
                         var_dependencies,
                         &property.condition,
@@ -658,49 +261,151 @@ impl<'a> DependencyGenerator<'a> {
                         node_change_id.as_str(),
                         self.doc,
                     );
-                    let value = format!("{} = {};", key, value_string);
+                    var_dependencies.insert(variable.to_string(), node_change_id.to_string());
+                    let value = match second {
+                        Some(second) if second.ne(&first) && variable.eq("ftd#device") => {
+                            is_static = false;
+                            ftd::html1::Expression::Device {
+                                desktop: Box::new(first),
+                                mobile: Box::new(second),
+                            }
+                        }
+                        Some(second) if second.ne(&first) => {
+                            is_static = false;
+                            ftd::html1::Expression::Theme {
+                                light: Box::new(first),
+                                dark: Box::new(second),
+                            }
+                        }
+                        _ => first,
+                    };
                     expressions.push((condition, value));
                 }
+                continue;
             }
 
-            let value = ftd::html1::utils::js_expression_from_list(
-                expressions,
-                Some(key.as_str()),
-                format!(
-                    "{} = {}",
-                    key,
-                    attribute
-                        .default
-                        .clone()
-                        .unwrap_or_else(|| "null".to_string())
-                )
-                .as_str(),
-            );
-            if !value.trim().is_empty() && !is_static {
-                result.push(format!(
-                    indoc::indoc! {"
-                         window.node_change_{id}[\"{key}\"] = function(data) {{
-                                {value}
-                         }}
-                    "},
-                    id = self.id,
-                    key = node_change_id,
-                    value = value.trim(),
-                ));
+            if let Some(value) = ftd::html1::Expression::from_property_value_with_pattern(
+                self.id,
+                self.doc,
+                &property.value,
+                &property_with_pattern.pattern_with_eval,
+                None,
+                string_needs_no_quotes,
+            )? {
+                dependency_map_from_condition(
+                    var_dependencies,
+                    &property.condition,
+                    node_change_id.as_str(),
+                    self.doc,
+                );
+                dependency_map_from_property_value(
+                    var_dependencies,
+                    &property.value,
+                    node_change_id.as_str(),
+                    self.doc,
+                );
+                expressions.push((condition, value));
             }
         }
 
-        for children in self.node.children.iter() {
-            let value = DependencyGenerator::new(self.id, children, self.doc)
-                .get_dependencies_(var_dependencies)?;
-            if !value.trim().is_empty() {
-                result.push(value.trim().to_string());
+        if is_static {
+            return Ok(None);
+        }
+        Ok(self.node_dependency(
+            node_change_id,
+            node_data_id,
+            property,
+            expressions,
+            default_expression(node_value.default.as_ref()),
+        ))
+    }
+
+    /// The values with a condition are the cases of the dependency, the last one without a
+    /// condition is its default, same as `ftd::html1::utils::js_expression_from_list`.
+    fn node_dependency(
+        &self,
+        key: String,
+        id: &str,
+        property: ftd::html1::Property,
+        expressions: Vec<(Option<ftd::html1::Expression>, ftd::html1::Expression)>,
+        default: ftd::html1::Expression,
+    ) -> Option<ftd::html1::NodeDependency> {
+        if expressions.is_empty() {
+            return None;
+        }
+        let mut cases = vec![];
+        let mut unconditional = None;
+        for (condition, value) in expressions {
+            match condition {
+                Some(condition) => cases.push((condition, value)),
+                None => unconditional = Some(value),
             }
         }
-        Ok(result.join("\n"))
+        Some(ftd::html1::NodeDependency {
+            key,
+            id: id.to_string(),
+            property,
+            cases,
+            default: unconditional.unwrap_or(default),
+            template: self.template.clone(),
+        })
+    }
+
+    /// Registers `node`, the template of a loop on a mutable list, with the runtime, which
+    /// renders it again for every item when the list changes. `locals` are the variables of the
+    /// components in the template, each instance keeps its own values of them.
+    fn loop_template(
+        &self,
+        node: &ftd::node::Node,
+        loop_template: &ftd::executor::LoopTemplate,
+    ) -> String {
+        let locals = self
+            .doc
+            .bag()
+            .keys()
+            .filter(|k| {
+                k.as_str() != loop_template.item.as_str()
+                    && k.as_str() != loop_template.counter.as_str()
+                    && is_in_container(k, node.data_id.as_str())
+            })
+            .map(|k| ftd::html1::utils::js_reference_name(k.as_str()))
+            .collect::<Vec<String>>();
+        let template = serde_json::json!({
+            "id": ftd::html1::utils::full_data_id(self.id, node.data_id.as_str()),
+            "on": ftd::html1::utils::js_reference_name(loop_template.on.as_str()),
+            "item": ftd::html1::utils::js_reference_name(loop_template.item.as_str()),
+            "counter": ftd::html1::utils::js_reference_name(loop_template.counter.as_str()),
+            "locals": locals,
+            "instances": loop_template
+                .instances
+                .iter()
+                .map(|v| ftd::html1::utils::full_data_id(self.id, v))
+                .collect::<Vec<String>>(),
+            "parent": self.template,
+        });
+        format!("window.ftd.loop_template(\"{}\", {});", self.id, template)
+    }
+}
+
+/// Whether `variable` is a variable of a component placed at `container` or inside it, e.g.
+/// `foo#foo:value:1,0` is in `1`.
+fn is_in_container(variable: &str, container: &str) -> bool {
+    match variable.rsplit_once(':') {
+        Some((_, c)) => c == container || c.starts_with(format!("{},", container).as_str()),
+        None => false,
     }
 }
 
+/// The defaults of `ftd::node::Value` are JS values, e.g. `"\"block\""`, or CSS values.
+fn default_expression(default: Option<&String>) -> ftd::html1::Expression {
+    ftd::html1::Expression::Value(match default {
+        Some(default) => {
+            serde_json::from_str(default).unwrap_or_else(|_| serde_json::json!(default))
+        }
+        None => serde_json::Value::Null,
+    })
+}
+
 fn dependency_map_from_condition(
     var_dependencies: &mut ftd::VecMap<String>,
     condition: &Option<ftd::interpreter2::Expression>,
@@ -728,7 +433,7 @@ fn dependency_map_from_property_value(
 
 fn is_static_expression(
     property_value: &ftd::interpreter2::PropertyValue,
-    condition: &Option<String>,
+    condition: &Option<ftd::interpreter2::Expression>,
     doc: &ftd::interpreter2::TDoc,
 ) -> bool {
     if property_value.kind().is_ftd_length() {
@@ -0,0 +1,289 @@
+/// The initial values of `ftd::html1::NodeDependency`, evaluated with the same semantics as
+/// `evaluate` of the runtime so the html rendered at build time is the one the runtime would
+/// set. `None` is a value only the runtime can compute, e.g. a call of a function of the
+/// document, or a value JS would coerce in a way not mirrored here, the value the executor
+/// resolved is kept for it.
+impl ftd::html1::NodeDependency {
+    pub fn evaluate(&self, data: &ftd::Map<serde_json::Value>) -> Option<serde_json::Value> {
+        let mut expression = &self.default;
+        for (condition, value) in self.cases.iter() {
+            if truthy(&condition.evaluate(data, &mut Default::default())?) {
+                expression = value;
+                break;
+            }
+        }
+        expression.evaluate(data, &mut Default::default())
+    }
+
+    /// The value as the runtime sets it, `Some(None)` removes the style or the attribute.
+    pub fn evaluate_to_string(&self, data: &ftd::Map<serde_json::Value>) -> Option<Option<String>> {
+        match self.evaluate(data)? {
+            serde_json::Value::Null => Some(None),
+            value => to_string(&value).map(Some),
+        }
+    }
+}
+
+impl ftd::html1::Expression {
+    pub fn evaluate(
+        &self,
+        data: &ftd::Map<serde_json::Value>,
+        locals: &mut ftd::Map<serde_json::Value>,
+    ) -> Option<serde_json::Value> {
+        match self {
+            ftd::html1::Expression::Value(value) => Some(value.clone()),
+            ftd::html1::Expression::Reference(name) => resolve_reference(name, data),
+            ftd::html1::Expression::Variable(name) => locals.get(name).cloned(),
+            ftd::html1::Expression::Format {
+                pattern,
+                eval: false,
+                value,
+            } => {
+                let value = match value.evaluate(data, locals)? {
+                    serde_json::Value::String(v) => v,
+                    serde_json::Value::Number(v) => number_to_string(v.as_f64()?),
+                    serde_json::Value::Bool(v) => v.to_string(),
+                    serde_json::Value::Null => "null".to_string(),
+                    // the numbers of `JSON.stringify` are not the ones of serde
+                    serde_json::Value::Array(_) | serde_json::Value::Object(_) => return None,
+                };
+                // `$` is special in the replacement of `String.replace`
+                if value.contains('$') {
+                    return None;
+                }
+                Some(serde_json::Value::String(pattern.replace("{0}", &value)))
+            }
+            ftd::html1::Expression::Record(fields) => {
+                let mut record = serde_json::Map::new();
+                for (name, field) in fields {
+                    record.insert(name.to_string(), field.evaluate(data, locals)?);
+                }
+                Some(serde_json::Value::Object(record))
+            }
+            ftd::html1::Expression::Call { name, arguments } => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| argument.evaluate(data, locals))
+                    .collect::<Option<Vec<serde_json::Value>>>()?;
+                call(name, arguments.as_slice())
+            }
+            ftd::html1::Expression::Operation { operator, operands } => {
+                operation(operator, operands, data, locals)
+            }
+            ftd::html1::Expression::Theme { light, dark } => {
+                match data.get("ftd#dark-mode").map(truthy).unwrap_or(false) {
+                    true => dark.evaluate(data, locals),
+                    false => light.evaluate(data, locals),
+                }
+            }
+            ftd::html1::Expression::Device { desktop, mobile } => {
+                let is_desktop = data
+                    .get("ftd#device")
+                    .and_then(|v| loose_equals(v, &serde_json::json!("desktop")))?;
+                match is_desktop {
+                    true => desktop.evaluate(data, locals),
+                    false => mobile.evaluate(data, locals),
+                }
+            }
+            ftd::html1::Expression::Format { eval: true, .. }
+            | ftd::html1::Expression::Function(_) => None,
+        }
+    }
+}
+
+fn operation(
+    operator: &str,
+    operands: &[ftd::html1::Expression],
+    data: &ftd::Map<serde_json::Value>,
+    locals: &mut ftd::Map<serde_json::Value>,
+) -> Option<serde_json::Value> {
+    let value = |i: usize, locals: &mut ftd::Map<serde_json::Value>| {
+        operands.get(i)?.evaluate(data, locals)
+    };
+    match operator {
+        ";" => {
+            let mut result = serde_json::Value::Null;
+            for i in 0..operands.len() {
+                result = value(i, locals)?;
+            }
+            return Some(result);
+        }
+        "," => {
+            return (0..operands.len())
+                .map(|i| value(i, locals))
+                .collect::<Option<Vec<serde_json::Value>>>()
+                .map(serde_json::Value::Array);
+        }
+        "&&" | "||" => {
+            let a = value(0, locals)?;
+            return match (operator, truthy(&a)) {
+                ("&&", true) | ("||", false) => value(1, locals),
+                _ => Some(a),
+            };
+        }
+        "!" => return Some(serde_json::Value::Bool(!truthy(&value(0, locals)?))),
+        "-" if operands.len() == 1 => return number(-to_number(&value(0, locals)?)?),
+        _ => {}
+    }
+    if let Some(operator) = operator
+        .strip_suffix('=')
+        .filter(|v| ["", "+", "-", "*", "/", "%", "^", "&&", "||"].contains(v))
+    {
+        // an assignment, e.g. `f = a + 1` or `f += 1`
+        let name = match operands.first() {
+            Some(ftd::html1::Expression::Variable(name)) => name,
+            _ => return None,
+        };
+        let result = if operator.is_empty() {
+            value(1, locals)?
+        } else {
+            self::operation(operator, operands, data, locals)?
+        };
+        locals.insert(name.to_string(), result.clone());
+        return Some(result);
+    }
+
+    let (a, b) = (value(0, locals)?, value(1, locals)?);
+    match operator {
+        "+" => match (&a, &b) {
+            (serde_json::Value::String(_), _) | (_, serde_json::Value::String(_)) => Some(
+                serde_json::Value::String(format!("{}{}", to_string(&a)?, to_string(&b)?)),
+            ),
+            _ => number(to_number(&a)? + to_number(&b)?),
+        },
+        "-" => number(to_number(&a)? - to_number(&b)?),
+        "*" => number(to_number(&a)? * to_number(&b)?),
+        "/" => number(to_number(&a)? / to_number(&b)?),
+        "%" => number(to_number(&a)? % to_number(&b)?),
+        "^" => number(to_number(&a)?.powf(to_number(&b)?)),
+        "==" => loose_equals(&a, &b).map(serde_json::Value::Bool),
+        "!=" => loose_equals(&a, &b).map(|v| serde_json::Value::Bool(!v)),
+        ">" | "<" | ">=" | "<=" => {
+            let ordering = match (&a, &b) {
+                (serde_json::Value::String(a), serde_json::Value::String(b)) => {
+                    // JS compares the UTF-16 code units
+                    Some(a.encode_utf16().cmp(b.encode_utf16()))
+                }
+                _ => to_number(&a)?.partial_cmp(&to_number(&b)?),
+            };
+            let result = match ordering {
+                Some(ordering) => match operator {
+                    ">" => ordering.is_gt(),
+                    "<" => ordering.is_lt(),
+                    ">=" => ordering.is_ge(),
+                    _ => ordering.is_le(),
+                },
+                // NaN
+                None => false,
+            };
+            Some(serde_json::Value::Bool(result))
+        }
+        _ => None,
+    }
+}
+
+/// The functions of the runtime, `window.ftd`, a condition can call
+fn call(name: &str, arguments: &[serde_json::Value]) -> Option<serde_json::Value> {
+    match (name, arguments) {
+        ("ftd.is_empty", [value]) => Some(serde_json::Value::Bool(match value {
+            serde_json::Value::String(v) => v.is_empty(),
+            serde_json::Value::Array(v) => v.is_empty(),
+            v => !truthy(v),
+        })),
+        ("ftd.length", [serde_json::Value::Array(v)]) => Some(serde_json::json!(v.len())),
+        _ => None,
+    }
+}
+
+/// Same as `resolve_reference` of the runtime, a missing variable is `undefined` for it, which
+/// is not a value of JSON.
+fn resolve_reference(name: &str, data: &ftd::Map<serde_json::Value>) -> Option<serde_json::Value> {
+    if let Some(value) = data.get(name).filter(|v| truthy(v)) {
+        return Some(value.clone());
+    }
+    let (name, remaining) = match name.split_once('#') {
+        Some((part1, part2)) => match part2.split_once('.') {
+            Some((var, remaining)) => (format!("{}#{}", part1, var), Some(remaining)),
+            None => (name.to_string(), None),
+        },
+        None => match name.split_once('.') {
+            Some((var, remaining)) => (var.to_string(), Some(remaining)),
+            None => (name.to_string(), None),
+        },
+    };
+    let mut value = data.get(&name)?;
+    if let Some(remaining) = remaining {
+        for part in remaining.split('.') {
+            value = match value {
+                serde_json::Value::Object(v) => v.get(part)?,
+                serde_json::Value::Array(v) => v.get(part.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+    }
+    Some(value.clone())
+}
+
+fn truthy(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => false,
+        serde_json::Value::Bool(v) => *v,
+        serde_json::Value::Number(v) => v.as_f64().map(|v| v != 0.0).unwrap_or(true),
+        serde_json::Value::String(v) => !v.is_empty(),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => true,
+    }
+}
+
+/// `Number(value)`, `None` for an array or an object
+fn to_number(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Null => Some(0.0),
+        serde_json::Value::Bool(v) => Some(if *v { 1.0 } else { 0.0 }),
+        serde_json::Value::Number(v) => v.as_f64(),
+        serde_json::Value::String(v) if v.trim().is_empty() => Some(0.0),
+        serde_json::Value::String(v) => Some(v.trim().parse::<f64>().unwrap_or(f64::NAN)),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => None,
+    }
+}
+
+/// `String(value)`, `None` for an array or an object
+fn to_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => Some("null".to_string()),
+        serde_json::Value::Bool(v) => Some(v.to_string()),
+        serde_json::Value::Number(v) => Some(number_to_string(v.as_f64()?)),
+        serde_json::Value::String(v) => Some(v.to_string()),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => None,
+    }
+}
+
+/// A number of JS as JSON, `None` for `NaN` and the infinities, which JSON does not have
+fn number(value: f64) -> Option<serde_json::Value> {
+    if !value.is_finite() {
+        return None;
+    }
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return Some(serde_json::json!(value as i64));
+    }
+    serde_json::Number::from_f64(value).map(serde_json::Value::Number)
+}
+
+fn number_to_string(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        // no `.0`, and `-0` is `0`
+        return (value as i64).to_string();
+    }
+    value.to_string()
+}
+
+/// `a == b`, `None` for an array or an object, which are compared by reference
+fn loose_equals(a: &serde_json::Value, b: &serde_json::Value) -> Option<bool> {
+    Some(match (a, b) {
+        (serde_json::Value::Array(_) | serde_json::Value::Object(_), _)
+        | (_, serde_json::Value::Array(_) | serde_json::Value::Object(_)) => return None,
+        (serde_json::Value::Null, serde_json::Value::Null) => true,
+        (serde_json::Value::Null, _) | (_, serde_json::Value::Null) => false,
+        (serde_json::Value::String(a), serde_json::Value::String(b)) => a == b,
+        (a, b) => to_number(a)? == to_number(b)?,
+    })
+}
@@ -124,7 +124,7 @@ impl<'a> ftd::html1::main::HtmlGenerator<'a> {
     }
 }
 
-fn to_event_name(event_name: &ftd::interpreter2::EventName) -> String {
+pub(crate) fn to_event_name(event_name: &ftd::interpreter2::EventName) -> String {
     match event_name {
         ftd::interpreter2::EventName::Click => "onclick".to_string(),
         ftd::interpreter2::EventName::MouseLeave => "onmouseleave".to_string(),
@@ -0,0 +1,355 @@
+/// A property of a node that depends on variables. The runtime, `window.ftd.dependencies`,
+/// sets it again when one of the variables changes, the set value functions of the variables,
+/// see `ftd::html1::VariableDependencyGenerator`, call the node change `key`.
+#[derive(serde::Serialize, Debug, PartialEq, Clone)]
+pub struct NodeDependency {
+    /// `{full data id}__{name of the property}`
+    pub key: String,
+    /// The full data id of the node
+    pub id: String,
+    pub property: Property,
+    /// The value of the first case whose condition is true is set
+    pub cases: Vec<(Expression, Expression)>,
+    /// The value set if no condition is true, `null` removes an attribute
+    pub default: Expression,
+    /// The full data id of the loop template the node is in, see `ftd::executor::LoopTemplate`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum Property {
+    Text,
+    Style(String),
+    Attribute(String),
+}
+
+/// The expressions of `ftd::html1::NodeDependency`, the runtime evaluates them against the data
+/// of the page.
+#[derive(serde::Serialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum Expression {
+    Value(serde_json::Value),
+    /// A variable of the page, or a part of it, e.g. `foo#person.name`
+    Reference(String),
+    /// A variable assigned in the condition itself, e.g. `f` in `f = g + 1; f > 4`
+    Variable(String),
+    /// `pattern` with `{0}` replaced by the JSON of `value`, evaluated as JS if `eval` is true,
+    /// see `ftd::executor::Length::set_pattern_from_variant_str`
+    Format {
+        pattern: String,
+        eval: bool,
+        value: Box<Expression>,
+    },
+    Record(ftd::Map<Expression>),
+    /// A call of a function of the document, see `ftd::html1::FunctionGenerator`
+    Function(ftd::html1::Action),
+    /// A call of a function of the runtime, e.g. `ftd.is_empty`
+    Call {
+        name: String,
+        arguments: Vec<Expression>,
+    },
+    /// An operator of `ftd::evalexpr`, e.g. `==`, `!`, or `;` for a chain
+    Operation {
+        operator: String,
+        operands: Vec<Expression>,
+    },
+    /// `light` in light mode and `dark` in dark mode, see `ftd#dark-mode`
+    Theme {
+        light: Box<Expression>,
+        dark: Box<Expression>,
+    },
+    /// `desktop` on desktop and `mobile` on mobile, see `ftd#device`
+    Device {
+        desktop: Box<Expression>,
+        mobile: Box<Expression>,
+    },
+}
+
+impl Expression {
+    pub(crate) fn from_condition(condition: &ftd::interpreter2::Expression) -> Expression {
+        Expression::from_expr_node(&condition.expression, &condition.references)
+    }
+
+    fn from_expr_node(
+        node: &ftd::evalexpr::ExprNode,
+        references: &ftd::Map<ftd::interpreter2::PropertyValue>,
+    ) -> Expression {
+        use itertools::Itertools;
+
+        match node.operator() {
+            // brackets, they are not needed in a tree
+            ftd::evalexpr::Operator::RootNode if node.children().len() == 1 => {
+                Expression::from_expr_node(node.children().first().unwrap(), references)
+            }
+            ftd::evalexpr::Operator::RootNode => Expression::Operation {
+                operator: ";".to_string(),
+                operands: node
+                    .children()
+                    .iter()
+                    .map(|child| Expression::from_expr_node(child, references))
+                    .collect_vec(),
+            },
+            ftd::evalexpr::Operator::Const { value } => Expression::Value(json_from_value(value)),
+            ftd::evalexpr::Operator::VariableIdentifierRead { identifier } => {
+                match references.get(identifier) {
+                    Some(ftd::interpreter2::PropertyValue::Reference { name, .. }) => {
+                        Expression::Reference(name.to_string())
+                    }
+                    _ => Expression::Variable(identifier.to_string()),
+                }
+            }
+            ftd::evalexpr::Operator::VariableIdentifierWrite { identifier } => {
+                Expression::Variable(identifier.to_string())
+            }
+            ftd::evalexpr::Operator::FunctionIdentifier { identifier } => {
+                // the arguments are in brackets, more than one of them are a tuple
+                let mut arguments = vec![];
+                if let Some(child) = node.children().first() {
+                    for argument in child.children() {
+                        if matches!(argument.operator(), ftd::evalexpr::Operator::Tuple) {
+                            arguments.extend(
+                                argument
+                                    .children()
+                                    .iter()
+                                    .map(|v| Expression::from_expr_node(v, references)),
+                            );
+                        } else {
+                            arguments.push(Expression::from_expr_node(argument, references));
+                        }
+                    }
+                }
+                Expression::Call {
+                    name: identifier.to_string(),
+                    arguments,
+                }
+            }
+            operator => Expression::Operation {
+                operator: operator.to_string().trim().to_string(),
+                operands: node
+                    .children()
+                    .iter()
+                    .map(|child| Expression::from_expr_node(child, references))
+                    .collect_vec(),
+            },
+        }
+    }
+
+    /// Same as `ftd::html1::utils::get_formatted_dep_string_from_property_value`, but the
+    /// expression of the value instead of JS.
+    pub(crate) fn from_property_value_with_pattern(
+        id: &str,
+        doc: &ftd::interpreter2::TDoc,
+        property_value: &ftd::interpreter2::PropertyValue,
+        pattern_with_eval: &Option<(String, bool)>,
+        field: Option<String>,
+        string_needs_no_quotes: bool,
+    ) -> ftd::html1::Result<Option<Expression>> {
+        let value = if let Some(value) =
+            Expression::from_property_value(property_value, doc, field, id, string_needs_no_quotes)?
+        {
+            value
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(match pattern_with_eval {
+            Some((pattern, eval)) => Expression::Format {
+                pattern: pattern.to_string(),
+                eval: *eval,
+                value: Box::new(value),
+            },
+            None => value,
+        }))
+    }
+
+    fn from_property_value(
+        property_value: &ftd::interpreter2::PropertyValue,
+        doc: &ftd::interpreter2::TDoc,
+        field: Option<String>,
+        id: &str,
+        string_needs_no_quotes: bool,
+    ) -> ftd::html1::Result<Option<Expression>> {
+        Ok(match property_value {
+            ftd::interpreter2::PropertyValue::Reference { name, .. } => {
+                let name = ftd::html1::utils::js_reference_name(name);
+                Some(Expression::Reference(match field {
+                    Some(field) => format!("{}.{}", name, field),
+                    None => name,
+                }))
+            }
+            ftd::interpreter2::PropertyValue::FunctionCall(function_call) => {
+                Some(Expression::Function(
+                    ftd::html1::Action::from_function_call(function_call, id, doc)?,
+                ))
+            }
+            ftd::interpreter2::PropertyValue::Value {
+                value, line_number, ..
+            } => {
+                Expression::from_value(value, doc, *line_number, field, id, string_needs_no_quotes)?
+            }
+            _ => None,
+        })
+    }
+
+    fn from_value(
+        value: &ftd::interpreter2::Value,
+        doc: &ftd::interpreter2::TDoc,
+        line_number: usize,
+        field: Option<String>,
+        id: &str,
+        string_needs_no_quotes: bool,
+    ) -> ftd::html1::Result<Option<Expression>> {
+        Ok(match value {
+            ftd::interpreter2::Value::String { text } => {
+                Some(Expression::Value(serde_json::json!(text)))
+            }
+            ftd::interpreter2::Value::Integer { value } => {
+                Some(Expression::Value(serde_json::json!(value)))
+            }
+            ftd::interpreter2::Value::Decimal { value } => {
+                Some(Expression::Value(serde_json::json!(value)))
+            }
+            ftd::interpreter2::Value::Boolean { value } => {
+                Some(Expression::Value(serde_json::json!(value)))
+            }
+            ftd::interpreter2::Value::List { data, .. } => {
+                // same as `ftd::interpreter2::Value::to_html_string`, the items are joined
+                let mut values = vec![];
+                for value in data {
+                    if let Some(v) = value.clone().resolve(doc, line_number)?.to_html_string(
+                        doc,
+                        value.line_number(),
+                        None,
+                        id,
+                        string_needs_no_quotes,
+                    )? {
+                        values.push(v);
+                    }
+                }
+                Some(Expression::Value(serde_json::json!(
+                    values.join(if string_needs_no_quotes { " " } else { ", " })
+                )))
+            }
+            ftd::interpreter2::Value::Record { fields, .. }
+                if field
+                    .as_ref()
+                    .map(|v| fields.contains_key(v))
+                    .unwrap_or(false) =>
+            {
+                Expression::from_property_value(
+                    fields.get(&field.unwrap()).unwrap(),
+                    doc,
+                    None,
+                    id,
+                    string_needs_no_quotes,
+                )?
+            }
+            t if ftd::executor::Background::is_shorthand_value(t) => {
+                let (light, dark) =
+                    ftd::executor::Background::css_strings_from_value(t, doc, line_number)
+                        .map_err(|e| ftd::html1::Error::ParseError {
+                            message: e.to_string(),
+                            doc_id: doc.name.to_string(),
+                            line_number,
+                            span: None,
+                        })?;
+                let css = if field.as_deref() == Some("dark") {
+                    dark
+                } else {
+                    light
+                };
+                Some(Expression::Value(serde_json::json!(css)))
+            }
+            ftd::interpreter2::Value::OrType {
+                value,
+                variant,
+                full_variant,
+                name,
+                ..
+            } => {
+                let value =
+                    Expression::from_property_value(value, doc, field, id, string_needs_no_quotes)?;
+                let pattern = match value {
+                    Some(_) if name.eq(ftd::interpreter2::FTD_LENGTH) => {
+                        ftd::executor::Length::set_pattern_from_variant_str(
+                            variant,
+                            doc.name,
+                            line_number,
+                        )
+                        .ok()
+                    }
+                    Some(_)
+                        if name.eq(ftd::interpreter2::FTD_RESIZING)
+                            && variant.ne(ftd::interpreter2::FTD_RESIZING_FIXED) =>
+                    {
+                        ftd::executor::Resizing::set_pattern_from_variant_str(
+                            variant,
+                            full_variant,
+                            doc.name,
+                            line_number,
+                        )
+                        .ok()
+                    }
+                    _ => None,
+                };
+                match (value, pattern) {
+                    (Some(value), Some(pattern)) => Some(Expression::Format {
+                        pattern: pattern.to_string(),
+                        eval: false,
+                        value: Box::new(value),
+                    }),
+                    (value, _) => value,
+                }
+            }
+            ftd::interpreter2::Value::Record { fields, .. } => {
+                let mut values: ftd::Map<Expression> = Default::default();
+                for (k, v) in fields {
+                    let value = Expression::from_property_value(
+                        v,
+                        doc,
+                        field.clone(),
+                        id,
+                        string_needs_no_quotes,
+                    )?
+                    .unwrap_or(Expression::Value(serde_json::Value::Null));
+                    values.insert(k.to_string(), value);
+                }
+                Some(Expression::Record(values))
+            }
+            ftd::interpreter2::Value::Optional { data, .. } => match data.as_ref() {
+                Some(value) => Expression::from_value(
+                    value,
+                    doc,
+                    line_number,
+                    field,
+                    id,
+                    string_needs_no_quotes,
+                )?,
+                None => Some(Expression::Value(serde_json::Value::Null)),
+            },
+            t => {
+                return Err(ftd::html1::Error::ParseError {
+                    message: format!("{:?} can not be the value of a node dependency", t),
+                    doc_id: doc.name.to_string(),
+                    line_number,
+                    span: None,
+                })
+            }
+        })
+    }
+}
+
+fn json_from_value(value: &ftd::evalexpr::Value) -> serde_json::Value {
+    match value {
+        ftd::evalexpr::Value::String(v) => serde_json::json!(v),
+        ftd::evalexpr::Value::Float(v) => serde_json::json!(v),
+        ftd::evalexpr::Value::Int(v) => serde_json::json!(v),
+        ftd::evalexpr::Value::Boolean(v) => serde_json::json!(v),
+        ftd::evalexpr::Value::Tuple(v) => {
+            serde_json::Value::Array(v.iter().map(json_from_value).collect())
+        }
+        ftd::evalexpr::Value::Empty => serde_json::Value::Null,
+    }
+}
@@ -3,15 +3,36 @@
 pub struct HtmlUI {
     pub html: String,
     pub dependencies: String,
+    /// The properties of the nodes `dependencies` has, for the runtime to set
+    pub node_dependencies: Vec<ftd::html1::NodeDependency>,
     pub variables: String,
     pub functions: String,
     pub variable_dependencies: String,
     pub outer_events: String,
+    pub data_report: ftd::html1::DataReport,
 }
 
 impl HtmlUI {
     #[tracing::instrument(skip_all)]
     pub fn from_node_data(node_data: ftd::node::NodeData, id: &str) -> ftd::html1::Result<HtmlUI> {
+        HtmlUI::from_node_data_(node_data, id, false)
+    }
+
+    /// Same as `from_node_data`, but the page data only has the variables the runtime can read
+    /// or mutate, `data_report` has what was left out.
+    #[tracing::instrument(skip_all)]
+    pub fn from_node_data_pruned(
+        node_data: ftd::node::NodeData,
+        id: &str,
+    ) -> ftd::html1::Result<HtmlUI> {
+        HtmlUI::from_node_data_(node_data, id, true)
+    }
+
+    fn from_node_data_(
+        node_data: ftd::node::NodeData,
+        id: &str,
+        prune: bool,
+    ) -> ftd::html1::Result<HtmlUI> {
         let tdoc = ftd::interpreter2::TDoc::new(
             node_data.name.as_str(),
             &node_data.aliases,
@@ -19,23 +40,42 @@ impl HtmlUI {
         );
 
         let functions = ftd::html1::FunctionGenerator::new(id).get_functions(&node_data)?;
-        let (dependencies, var_dependencies) =
+        let (dependencies, node_dependencies, var_dependencies) =
             ftd::html1::dependencies::DependencyGenerator::new(id, &node_data.node, &tdoc)
                 .get_dependencies()?;
         let variable_dependencies = ftd::html1::VariableDependencyGenerator::new(id, &tdoc)
             .get_set_functions(&var_dependencies)?;
-        let variables = ftd::html1::data::DataGenerator::new(&tdoc).get_data()?;
-        let (html, outer_events) =
-            HtmlGenerator::new(id, &tdoc).to_html_and_outer_events(node_data.node)?;
+        let data = ftd::html1::data::DataGenerator::new(&tdoc);
+        let (variables, data_report) = if prune {
+            data.get_reachable_data(&node_data.node, &var_dependencies)?
+        } else {
+            let variables = data.get_data()?;
+            let data_report = ftd::html1::DataReport::from_data(&variables);
+            (variables, data_report)
+        };
+        // the nodes are rendered with the values the runtime would set for the initial data,
+        // the data the runtime has may be pruned, but the variables left out can not change
+        let full_data;
+        let initial_data = if prune {
+            full_data = data.get_data()?;
+            &full_data
+        } else {
+            &variables
+        };
+        let (html, outer_events) = HtmlGenerator::new(id, &tdoc)
+            .with_initial_state(&node_dependencies, initial_data)
+            .to_html_and_outer_events(node_data.node)?;
 
         Ok(HtmlUI {
             html,
             dependencies,
+            node_dependencies,
             variables: serde_json::to_string_pretty(&variables)
                 .expect("failed to convert document to json"),
             functions,
             variable_dependencies,
             outer_events,
+            data_report,
         })
     }
 }
@@ -43,6 +83,11 @@ impl HtmlUI {
 pub(crate) struct HtmlGenerator<'a> {
     pub id: String,
     pub doc: &'a ftd::interpreter2::TDoc<'a>,
+    /// The node dependencies by the full data id of the node
+    pub node_dependencies: ftd::Map<Vec<&'a ftd::html1::NodeDependency>>,
+    pub initial_data: Option<&'a ftd::Map<serde_json::Value>>,
+    /// The event handlers and the loop templates are left out, for a page without `ftd.js`
+    pub without_js: bool,
 }
 
 impl<'a> HtmlGenerator<'a> {
@@ -50,7 +95,36 @@ impl<'a> HtmlGenerator<'a> {
         HtmlGenerator {
             id: id.to_string(),
             doc,
+            node_dependencies: Default::default(),
+            initial_data: None,
+            without_js: false,
+        }
+    }
+
+    /// The styles and the attributes in `node_dependencies` are rendered with the values they
+    /// evaluate to for `data`, see `ftd::html1::NodeDependency::evaluate`, the values the
+    /// runtime would set. The nodes of loop templates are left as they are, their instances are
+    /// rendered by the runtime.
+    pub fn with_initial_state(
+        mut self,
+        node_dependencies: &'a [ftd::html1::NodeDependency],
+        data: &'a ftd::Map<serde_json::Value>,
+    ) -> HtmlGenerator<'a> {
+        for dependency in node_dependencies {
+            if dependency.template.is_none() {
+                self.node_dependencies
+                    .entry(dependency.id.to_string())
+                    .or_default()
+                    .push(dependency);
+            }
         }
+        self.initial_data = Some(data);
+        self
+    }
+
+    pub fn without_js(mut self) -> HtmlGenerator<'a> {
+        self.without_js = true;
+        self
     }
 
     pub fn to_html_and_outer_events(
@@ -69,6 +143,7 @@ impl<'a> HtmlGenerator<'a> {
         if node.is_null() {
             return Ok(("".to_string(), vec![]));
         }
+        let node = self.set_initial_state(node);
 
         let style = format!(
             "style=\"{}\"",
@@ -79,7 +154,11 @@ impl<'a> HtmlGenerator<'a> {
         let mut outer_events = vec![];
         let attrs = {
             let mut attr = self.attrs_to_html(&node);
-            let events = self.group_by_js_event(&node.events)?;
+            let events = if self.without_js {
+                Default::default()
+            } else {
+                self.group_by_js_event(&node.events)?
+            };
             for (name, actions) in events {
                 if name.eq("onclickoutside") || name.starts_with("onglobalkey") {
                     let event = format!(
@@ -109,12 +188,24 @@ impl<'a> HtmlGenerator<'a> {
             None => node
                 .children
                 .into_iter()
-                .map(|v| match self.to_html_(v) {
-                    Ok((html, events)) => {
-                        outer_events.extend(events);
-                        Ok(html)
+                .filter(|v| !(self.without_js && v.loop_template.is_some()))
+                .map(|v| {
+                    let template = v.loop_template.as_ref().map(|_| {
+                        ftd::html1::utils::full_data_id(self.id.as_str(), v.data_id.as_str())
+                    });
+                    match (self.to_html_(v), template) {
+                        // the instances of a loop template are rendered by the runtime, the
+                        // outer events of the template are not added for them
+                        (Ok((html, _)), Some(template)) => Ok(format!(
+                            "<template data-loop=\"{}\">{}</template>",
+                            template, html
+                        )),
+                        (Ok((html, events)), None) => {
+                            outer_events.extend(events);
+                            Ok(html)
+                        }
+                        (Err(e), _) => Err(e),
                     }
-                    Err(e) => Err(e),
                 })
                 .collect::<ftd::html1::Result<Vec<String>>>()?
                 .join(""),
@@ -133,6 +224,34 @@ impl<'a> HtmlGenerator<'a> {
         ))
     }
 
+    fn set_initial_state(&self, mut node: ftd::node::Node) -> ftd::node::Node {
+        let (dependencies, data) = match (
+            self.node_dependencies.get(&ftd::html1::utils::full_data_id(
+                self.id.as_str(),
+                node.data_id.as_str(),
+            )),
+            self.initial_data,
+        ) {
+            (Some(dependencies), Some(data)) => (dependencies, data),
+            _ => return node,
+        };
+        for dependency in dependencies {
+            let (values, key) = match &dependency.property {
+                // the runtime sets the text as it is, the executor renders its markup, which is
+                // kept, the executor resolves the conditions of the text the same way
+                ftd::html1::Property::Text => continue,
+                ftd::html1::Property::Style(key) => (&mut node.style, key),
+                // the classes of the node are rendered from `classes`
+                ftd::html1::Property::Attribute(key) if key.eq("class") => continue,
+                ftd::html1::Property::Attribute(key) => (&mut node.attrs, key),
+            };
+            if let Some(value) = dependency.evaluate_to_string(data) {
+                values.entry(key.to_string()).or_default().value = value;
+            }
+        }
+        node
+    }
+
     pub fn style_to_html(&self, node: &ftd::node::Node, visible: bool) -> String {
         let mut styles: ftd::Map<String> = node
             .style
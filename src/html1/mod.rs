@@ -4,15 +4,21 @@ mod test;
 
 mod data;
 mod dependencies;
+mod evaluate;
 mod events;
 mod functions;
+mod graph;
 mod main;
+mod static_html;
 pub mod utils;
 mod variable_dependencies;
 
+pub use data::{DataReport, ModuleData};
 pub use events::Action;
 pub use functions::{ExpressionGenerator, FunctionGenerator};
+pub use graph::{Expression, NodeDependency, Property};
 pub use main::HtmlUI;
+pub use static_html::StaticHtmlUI;
 pub use variable_dependencies::VariableDependencyGenerator;
 
 #[derive(thiserror::Error, Debug)]
@@ -25,6 +31,7 @@ pub enum Error {
         message: String,
         doc_id: String,
         line_number: usize,
+        span: Option<ftd::p11::Span>,
     },
 
     #[error("InterpretEvalexprErrorerError: {}", _0)]
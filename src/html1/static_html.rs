@@ -0,0 +1,209 @@
+/// The export of `ftd::html1::HtmlUI` for readers that do not run `ftd.js`, e.g. email digests
+/// or RSS readers: the conditions are resolved for the initial data, the values that depend on
+/// the dark mode or on the device are switched with media queries, and there are no event
+/// handlers or page data.
+///
+/// The interactions that are lost are in `warnings`, the events of the nodes and the mutable
+/// variables of the document. The attributes and the text of a node are the ones of the light
+/// mode on desktop.
+pub struct StaticHtmlUI {
+    pub html: String,
+    /// The rules of the media queries, `!important` as they override the styles of the nodes
+    pub css: String,
+    pub warnings: Vec<ftd::diagnostic::Diagnostic>,
+}
+
+impl StaticHtmlUI {
+    #[tracing::instrument(skip_all)]
+    pub fn from_node_data(
+        node_data: ftd::node::NodeData,
+        id: &str,
+    ) -> ftd::html1::Result<StaticHtmlUI> {
+        let tdoc = ftd::interpreter2::TDoc::new(
+            node_data.name.as_str(),
+            &node_data.aliases,
+            &node_data.bag,
+        );
+
+        let (_, node_dependencies, _) =
+            ftd::html1::dependencies::DependencyGenerator::new(id, &node_data.node, &tdoc)
+                .get_dependencies()?;
+        let data = ftd::html1::data::DataGenerator::new(&tdoc).get_data()?;
+        let css = media_queries(&node_dependencies, &data);
+
+        let mut warnings = vec![];
+        lost_events(&node_data.node, &tdoc, &mut warnings);
+        lost_variables(&tdoc, &mut warnings);
+
+        let (html, _) = ftd::html1::main::HtmlGenerator::new(id, &tdoc)
+            .with_initial_state(&node_dependencies, &data)
+            .without_js()
+            .to_html_and_outer_events(node_data.node)?;
+
+        Ok(StaticHtmlUI {
+            html,
+            css,
+            warnings,
+        })
+    }
+}
+
+/// The styles of `node_dependencies` that are not the ones of `data` in dark mode, on mobile
+/// or both, same as the runtime, which sets `ftd#dark-mode` from the system and `ftd#device`
+/// from the width of the window, see `post_init.ts`.
+fn media_queries(
+    node_dependencies: &[ftd::html1::NodeDependency],
+    data: &ftd::Map<serde_json::Value>,
+) -> String {
+    let mut dark_data = data.clone();
+    dark_data.insert("ftd#dark-mode".to_string(), serde_json::json!(true));
+    dark_data.insert("ftd#system-dark-mode".to_string(), serde_json::json!(true));
+    let mut mobile_data = data.clone();
+    mobile_data.insert("ftd#device".to_string(), serde_json::json!("mobile"));
+    let mut dark_mobile_data = dark_data.clone();
+    dark_mobile_data.insert("ftd#device".to_string(), serde_json::json!("mobile"));
+
+    let mut dark_rules: Vec<(String, Vec<String>)> = vec![];
+    let mut mobile_rules = vec![];
+    let mut dark_mobile_rules = vec![];
+    for dependency in node_dependencies {
+        let key = match (&dependency.property, &dependency.template) {
+            (ftd::html1::Property::Style(key), None) => key,
+            _ => continue,
+        };
+        let value = match dependency.evaluate_to_string(data) {
+            Some(value) => value,
+            None => continue,
+        };
+        let evaluate = |data: &ftd::Map<serde_json::Value>| {
+            dependency
+                .evaluate_to_string(data)
+                .unwrap_or_else(|| value.clone())
+        };
+        let (dark, mobile, dark_mobile) = (
+            evaluate(&dark_data),
+            evaluate(&mobile_data),
+            evaluate(&dark_mobile_data),
+        );
+        // both media queries match in dark mode on mobile, the one of mobile is the last
+        let dark_or_mobile = if mobile.ne(&value) { &mobile } else { &dark };
+        for (rules, v, previous) in [
+            (&mut dark_rules, &dark, &value),
+            (&mut mobile_rules, &mobile, &value),
+            (&mut dark_mobile_rules, &dark_mobile, dark_or_mobile),
+        ] {
+            if v.eq(previous) {
+                continue;
+            }
+            // a style the runtime removes is the one of the stylesheets
+            let declaration = format!(
+                "{}: {} !important;",
+                key,
+                v.as_deref()
+                    .map(ftd::html1::main::escape)
+                    .unwrap_or_else(|| "revert".to_string())
+            );
+            match rules.last_mut() {
+                Some((id, declarations)) if dependency.id.eq(id) => {
+                    declarations.push(declaration);
+                }
+                _ => rules.push((dependency.id.to_string(), vec![declaration])),
+            }
+        }
+    }
+
+    // the device is mobile up to `ftd#breakpoint-width.mobile`, see `get_device`
+    let breakpoint = data
+        .get("ftd#breakpoint-width")
+        .and_then(|v| v.get("mobile"))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(768.0);
+    let mobile_query = format!("(max-width: {}px)", breakpoint);
+    let dark_query = "(prefers-color-scheme: dark)".to_string();
+    [
+        (dark_query.clone(), dark_rules),
+        (mobile_query.clone(), mobile_rules),
+        (
+            format!("{} and {}", dark_query, mobile_query),
+            dark_mobile_rules,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, rules)| !rules.is_empty())
+    .map(|(query, rules)| {
+        format!(
+            "@media {} {{\n{}\n}}",
+            query,
+            rules
+                .iter()
+                .map(|(id, declarations)| format!(
+                    "[data-id=\"{}\"] {{ {} }}",
+                    id,
+                    declarations.join(" ")
+                ))
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    })
+    .collect::<Vec<String>>()
+    .join("\n")
+}
+
+/// The events of `node` and of its children, the instances of a loop template have the events
+/// of the template.
+fn lost_events(
+    node: &ftd::node::Node,
+    doc: &ftd::interpreter2::TDoc,
+    warnings: &mut Vec<ftd::diagnostic::Diagnostic>,
+) {
+    for event in node.events.iter() {
+        warnings.push(warning(
+            format!(
+                "`{}` of the node is dropped from the static html, it calls `{}`",
+                ftd::html1::events::to_event_name(&event.name),
+                event.action.name
+            ),
+            doc.name,
+            event.action.line_number,
+        ));
+    }
+    for child in node.children.iter() {
+        if child.loop_template.is_none() {
+            lost_events(child, doc, warnings);
+        }
+    }
+}
+
+/// The mutable variables of the documents, the static html only has their initial values. The
+/// variables of the `ftd` module are not, the ones the runtime sets are media queries.
+fn lost_variables(doc: &ftd::interpreter2::TDoc, warnings: &mut Vec<ftd::diagnostic::Diagnostic>) {
+    for thing in doc.bag().values() {
+        let variable = match thing {
+            ftd::interpreter2::Thing::Variable(v) if v.mutable => v,
+            _ => continue,
+        };
+        let (module, name) = match variable.name.split_once('#') {
+            // the arguments of components and the items of loops, e.g. `foo#foo:flag:0`
+            Some((_, name)) if name.contains(':') => continue,
+            // the defaults of `ftd` and the values components inherit, e.g. `inherited#colors`
+            Some(("ftd", _)) | Some((ftd::interpreter2::FTD_INHERITED, _)) => continue,
+            Some((module, name)) => (module, name),
+            None => continue,
+        };
+        warnings.push(warning(
+            format!(
+                "`${}` keeps its initial value in the static html, it is mutable",
+                name
+            ),
+            module,
+            variable.line_number,
+        ));
+    }
+}
+
+fn warning(message: String, doc_id: &str, line_number: usize) -> ftd::diagnostic::Diagnostic {
+    ftd::diagnostic::Diagnostic {
+        level: ftd::diagnostic::Level::Warning,
+        ..ftd::diagnostic::Diagnostic::new(message.as_str(), doc_id, line_number, None)
+    }
+}
@@ -81,11 +81,35 @@ fn p(s: &str, t: &str, fix: bool, file_location: &std::path::PathBuf) {
     let executor =
         ftd::executor::ExecuteDoc::from_interpreter(doc).unwrap_or_else(|e| panic!("{:?}", e));
     let node = ftd::node::NodeData::from_rt(executor);
-    let html_ui =
-        ftd::html1::HtmlUI::from_node_data(node, "main").unwrap_or_else(|e| panic!("{:?}", e));
-    let ftd_js = std::fs::read_to_string("build.js").expect("build.js not found");
     let test_css = std::fs::read_to_string("t/test.css").expect("build.js not found");
-    let html_str = ftd::html1::utils::trim_all_lines(
+    // the files in `t/html/static` are exported without `ftd.js`, the ones in `t/html/pruned`
+    // only have the data the runtime reads
+    let html_str = match file_location
+        .parent()
+        .and_then(|v| v.file_name())
+        .and_then(|v| v.to_str())
+    {
+        Some("static") => static_html(node, test_css.as_str()),
+        Some("pruned") => html(
+            ftd::html1::HtmlUI::from_node_data_pruned(node, "main")
+                .unwrap_or_else(|e| panic!("{:?}", e)),
+            test_css.as_str(),
+        ),
+        _ => html(
+            ftd::html1::HtmlUI::from_node_data(node, "main").unwrap_or_else(|e| panic!("{:?}", e)),
+            test_css.as_str(),
+        ),
+    };
+    if fix {
+        std::fs::write(file_location, html_str).unwrap();
+        return;
+    }
+    assert_eq!(&t, &html_str, "Expected JSON: {}", html_str)
+}
+
+fn html(html_ui: ftd::html1::HtmlUI, test_css: &str) -> String {
+    let ftd_js = std::fs::read_to_string("build.js").expect("build.js not found");
+    ftd::html1::utils::trim_all_lines(
         std::fs::read_to_string("build.html")
             .expect("cant read ftd.html")
             .replace("__ftd_doc_title__", "")
@@ -113,12 +137,40 @@ fn p(s: &str, t: &str, fix: bool, file_location: &std::path::PathBuf) {
             .replace("__ftd_css__", "")
             .replace("__ftd_element_css__", "")
             .as_str(),
-    );
-    if fix {
-        std::fs::write(file_location, html_str).unwrap();
-        return;
-    }
-    assert_eq!(&t, &html_str, "Expected JSON: {}", html_str)
+    )
+}
+
+/// The static html of `node`, with the warnings about the interactions that are lost in a
+/// comment at the end.
+fn static_html(node: ftd::node::NodeData, test_css: &str) -> String {
+    let static_ui = ftd::html1::StaticHtmlUI::from_node_data(node, "main")
+        .unwrap_or_else(|e| panic!("{:?}", e));
+    let warnings = static_ui
+        .warnings
+        .iter()
+        .map(|v| {
+            format!(
+                "{}: {}:{}: {}",
+                v.level.as_str(),
+                v.doc_id.as_deref().unwrap_or_default(),
+                v.line_number.unwrap_or_default(),
+                v.message
+            )
+        })
+        .collect::<Vec<_>>();
+    ftd::html1::utils::trim_all_lines(
+        std::fs::read_to_string("build-static.html")
+            .expect("cant read build-static.html")
+            .replace("__ftd_doc_title__", "")
+            .replace("__ftd__", static_ui.html.as_str())
+            .replace("__base_url__", "/")
+            .replace(
+                "__extra_css__",
+                format!("<style>{}</style>", test_css).as_str(),
+            )
+            .replace("__ftd_css__", static_ui.css.as_str())
+            .as_str(),
+    ) + format!("\n<!--\n{}\n-->\n", warnings.join("\n")).as_str()
 }
 
 #[test]
@@ -197,3 +249,24 @@ fn filename_with_second_last_extension_replaced_with_json(
         }
     ))
 }
+
+#[test]
+fn reachable_data() {
+    let source = std::fs::read_to_string("t/html/pruned/1-reachable-data.ftd").unwrap();
+    let doc = interpret_helper("foo", source.as_str()).unwrap_or_else(|e| panic!("{:?}", e));
+    let executor =
+        ftd::executor::ExecuteDoc::from_interpreter(doc).unwrap_or_else(|e| panic!("{:?}", e));
+    let node = ftd::node::NodeData::from_rt(executor);
+    let html_ui = ftd::html1::HtmlUI::from_node_data_pruned(node, "main")
+        .unwrap_or_else(|e| panic!("{:?}", e));
+    // `unused` is not interpreted, as nothing refers to it, `title` is only read at build time
+    assert_eq!(
+        html_ui.data_report.modules.get("foo"),
+        Some(&ftd::html1::ModuleData {
+            kept: 1,
+            kept_bytes: 5,
+            dropped: 1,
+            dropped_bytes: 7,
+        })
+    );
+}
@@ -161,7 +161,8 @@ pub(crate) fn is_dark_mode_dependent(
     let value = value.clone().resolve(doc, value.line_number())?;
     Ok(value.is_record(ftd::interpreter2::FTD_IMAGE_SRC)
         || value.is_record(ftd::interpreter2::FTD_COLOR)
-        || value.is_or_type_variant(ftd::interpreter2::FTD_BACKGROUND_SOLID))
+        || value.is_or_type_variant(ftd::interpreter2::FTD_BACKGROUND_SOLID)
+        || ftd::executor::Background::is_shorthand_value(&value))
 }
 
 pub(crate) fn is_device_dependent(
@@ -344,6 +345,22 @@ impl ftd::interpreter2::Value {
                     string_needs_no_quotes,
                 )?
             }
+            t if ftd::executor::Background::is_shorthand_value(t) => {
+                let (light, dark) =
+                    ftd::executor::Background::css_strings_from_value(t, doc, line_number)
+                        .map_err(|e| ftd::html1::Error::ParseError {
+                            message: e.to_string(),
+                            doc_id: doc.name.to_string(),
+                            line_number,
+                            span: None,
+                        })?;
+                let css = if field.as_deref() == Some("dark") {
+                    dark
+                } else {
+                    light
+                };
+                Some(serde_json::to_string(&css).unwrap())
+            }
             ftd::interpreter2::Value::OrType {
                 value,
                 variant,
@@ -398,6 +415,12 @@ impl ftd::interpreter2::Value {
 
                 Some(format!("{{{}}}", values.join(", ")))
             }
+            ftd::interpreter2::Value::Optional { data, .. } => match data.as_ref() {
+                Some(value) => {
+                    value.to_html_string(doc, line_number, field, id, string_needs_no_quotes)?
+                }
+                None => Some("null".to_string()),
+            },
             t => unimplemented!("{:?}", t),
         })
     }
@@ -56,6 +56,7 @@ impl Variable {
                 message: format!("Expected boolean found: {:?}", t),
                 doc_id: doc_id.to_string(),
                 line_number: s.line_number,
+                span: None,
             }),
         }
     }
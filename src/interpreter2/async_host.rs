@@ -0,0 +1,220 @@
+//! [`ftd::interpreter2::Host`] for hosts whose modules, processors and foreign variables come
+//! from I/O, e.g. a server reading them from a database. Enabled by the `async` feature.
+
+/// The future returned by the methods of [`AsyncHost`].
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/**
+ * Same as [`ftd::interpreter2::Host`], with methods returning futures. [`interpret_async`] may
+ * call `load_module` for several modules at once, so the methods take `&self`.
+ */
+pub trait AsyncHost {
+    /// Module `module`, imported by `caller_module`, or `None` if there is no such module.
+    fn load_module<'a>(
+        &'a self,
+        module: &'a str,
+        caller_module: &'a str,
+    ) -> BoxFuture<'a, Option<ftd::interpreter2::Module>>;
+
+    /// Value of the variable defined by `ast` in `module`, which uses `processor`.
+    fn process<'a>(
+        &'a self,
+        processor: &'a str,
+        ast: &'a ftd::ast::AST,
+        module: &'a str,
+    ) -> BoxFuture<'a, ftd::interpreter2::Result<ftd::interpreter2::Value>> {
+        Box::pin(async move {
+            ftd::interpreter2::utils::e2(
+                format!("processor `{}` is not supported", processor),
+                module,
+                ast.line_number(),
+            )
+        })
+    }
+
+    /// See [`ftd::interpreter2::Host::processors`].
+    fn processors(&self) -> Option<&ftd::interpreter2::ProcessorRegistry> {
+        None
+    }
+
+    /// Value of `variable` of `module`, see [`ftd::interpreter2::Host::foreign_variable`].
+    fn foreign_variable<'a>(
+        &'a self,
+        module: &'a str,
+        variable: &'a str,
+        caller_module: &'a str,
+    ) -> BoxFuture<'a, ftd::interpreter2::Result<ftd::interpreter2::Value>> {
+        Box::pin(async move {
+            ftd::interpreter2::utils::e2(
+                format!(
+                    "foreign variable `{}.{}` is not supported",
+                    module, variable
+                ),
+                caller_module,
+                0,
+            )
+        })
+    }
+}
+
+/// Interprets document `id` to the end, see [`AsyncHost`].
+pub async fn interpret_async<H: AsyncHost>(
+    id: &str,
+    source: &str,
+    host: &H,
+) -> ftd::interpreter2::Result<ftd::interpreter2::Document> {
+    continue_async(ftd::interpreter2::interpret(id, source)?, host).await
+}
+
+/**
+ * Drives `interpreter` to [`ftd::interpreter2::Interpreter::Done`].
+ *
+ * The interpreter asks for one module at a time, when it first needs a thing of it. When it
+ * does, the modules imported by the documents parsed so far and not loaded yet are loaded
+ * along with it, concurrently, as they are likely to be asked for next. A module that is
+ * never asked for is not an error, even if it cannot be found.
+ */
+pub async fn continue_async<H: AsyncHost>(
+    mut interpreter: ftd::interpreter2::Interpreter,
+    host: &H,
+) -> ftd::interpreter2::Result<ftd::interpreter2::Document> {
+    // modules loaded ahead of the interpreter asking for them, by module name
+    let mut loaded: ftd::Map<Option<ftd::interpreter2::Module>> = Default::default();
+    loop {
+        interpreter = match interpreter {
+            ftd::interpreter2::Interpreter::Done { document } => return Ok(document),
+            ftd::interpreter2::Interpreter::StuckOnImport {
+                module,
+                state,
+                caller_module,
+            } => {
+                if !loaded.contains_key(module.as_str()) {
+                    let mut modules = vec![(module.to_string(), caller_module.to_string())];
+                    modules.extend(unloaded_imports(&state, &loaded, module.as_str()));
+                    let results = join_all(
+                        modules
+                            .iter()
+                            .map(|(module, caller)| host.load_module(module, caller))
+                            .collect(),
+                    )
+                    .await;
+                    loaded.extend(modules.into_iter().map(|v| v.0).zip(results));
+                }
+                let module_source = match loaded.remove(module.as_str()).flatten() {
+                    Some(module_source) => module_source,
+                    None => {
+                        return ftd::interpreter2::utils::e2(
+                            format!("Cannot find module `{}`", module),
+                            caller_module.as_str(),
+                            ftd::interpreter2::host::import_line_number(
+                                &state,
+                                module.as_str(),
+                                caller_module.as_str(),
+                            ),
+                        )
+                    }
+                };
+                let document = ftd::interpreter2::ParsedDocument::parse(
+                    module.as_str(),
+                    module_source.source.as_str(),
+                )?;
+                state.continue_after_import(
+                    module.as_str(),
+                    document,
+                    module_source.foreign_variables,
+                    module_source.foreign_functions,
+                    0,
+                )?
+            }
+            ftd::interpreter2::Interpreter::StuckOnProcessor {
+                mut state,
+                ast,
+                module,
+                processor,
+                caller_module,
+            } => match host.processors() {
+                Some(registry) if registry.contains(module.as_str(), processor.as_str()) => {
+                    match registry.process(
+                        module.as_str(),
+                        processor.as_str(),
+                        &ast,
+                        caller_module.as_str(),
+                        &mut state,
+                    )? {
+                        ftd::interpreter2::StateWithThing::Thing(value) => {
+                            state.continue_after_processor(value)?
+                        }
+                        ftd::interpreter2::StateWithThing::State(s) => s.into_interpreter(state),
+                        ftd::interpreter2::StateWithThing::Continue => {
+                            state.continue_processing()?
+                        }
+                    }
+                }
+                _ => {
+                    let value = host
+                        .process(processor.as_str(), &ast, module.as_str())
+                        .await?;
+                    state.continue_after_processor(value)?
+                }
+            },
+            ftd::interpreter2::Interpreter::StuckOnForeignVariable {
+                state,
+                module,
+                variable,
+                caller_module,
+            } => {
+                let value = host
+                    .foreign_variable(module.as_str(), variable.as_str(), caller_module.as_str())
+                    .await?;
+                state.continue_after_variable(module.as_str(), variable.as_str(), value)?
+            }
+        };
+    }
+}
+
+/// `(module, caller module)` of the imports of the parsed documents, other than `module`, that
+/// are neither parsed nor `loaded`.
+fn unloaded_imports(
+    state: &ftd::interpreter2::InterpreterState,
+    loaded: &ftd::Map<Option<ftd::interpreter2::Module>>,
+    module: &str,
+) -> Vec<(String, String)> {
+    let mut imports: Vec<(String, String)> = vec![];
+    for (name, document) in state.parsed_libs.iter() {
+        for ast in document.ast.iter() {
+            if let ftd::ast::AST::Import(import) = ast {
+                if import.module.ne(module)
+                    && !state.parsed_libs.contains_key(import.module.as_str())
+                    && !loaded.contains_key(import.module.as_str())
+                    && !imports.iter().any(|v| v.0.eq(&import.module))
+                {
+                    imports.push((import.module.to_string(), name.to_string()));
+                }
+            }
+        }
+    }
+    imports
+}
+
+/// Polls all of `futures` until they are all ready, and returns their outputs in order.
+async fn join_all<T>(mut futures: Vec<BoxFuture<'_, T>>) -> Vec<T> {
+    use std::future::Future;
+
+    let mut outputs: Vec<Option<T>> = futures.iter().map(|_| None).collect();
+    std::future::poll_fn(move |cx| {
+        let mut pending = false;
+        for (future, output) in futures.iter_mut().zip(outputs.iter_mut()) {
+            if output.is_none() {
+                match future.as_mut().poll(cx) {
+                    std::task::Poll::Ready(v) => *output = Some(v),
+                    std::task::Poll::Pending => pending = true,
+                }
+            }
+        }
+        if pending {
+            return std::task::Poll::Pending;
+        }
+        std::task::Poll::Ready(outputs.iter_mut().filter_map(|v| v.take()).collect())
+    })
+    .await
+}
@@ -0,0 +1,358 @@
+/**
+ * Modules interpreted by earlier calls of [`interpret_with_cache`], to interpret documents
+ * importing them without parsing and resolving them again.
+ *
+ * A module is known by its name and the hash of its source. Its parsed document is kept, along
+ * with the `Thing`s of the module that were resolved, which are put back in
+ * `InterpreterState.bag` when a document imports the module. [`Cache::update`] drops a module
+ * whose source changed, and every module importing it, directly or not; they are interpreted
+ * again the next time they are needed. [`interpret_with_cache`] does so for the modules the
+ * host gives a different source for.
+ *
+ * The things of a module that a variable invocation of a document updates, e.g. `-- lib.x: 10`,
+ * are not kept, as their value depends on the document, nor the ones of the modules importing
+ * it. Neither are the things of a module with processors or foreign variables, as the host may
+ * give them another value the next time, nor the ones of the modules importing it.
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cache {
+    modules: ftd::Map<CachedModule>,
+}
+
+/**
+ * A module of a [`Cache`].
+ *
+ * # Fields
+ *
+ * - `hash`: hash of the source of the module
+ * - `document`: the parsed module, with its foreign variables and functions
+ * - `imports`: the modules the module imports
+ * - `things`: the things of the module resolved so far, by full name
+ */
+#[derive(Debug, Clone, PartialEq)]
+struct CachedModule {
+    hash: u64,
+    document: ftd::interpreter2::ParsedDocument,
+    imports: Vec<String>,
+    things: ftd::Map<ftd::interpreter2::Thing>,
+}
+
+impl CachedModule {
+    fn new(hash: u64, document: ftd::interpreter2::ParsedDocument) -> CachedModule {
+        CachedModule {
+            hash,
+            imports: imports(&document),
+            document,
+            things: Default::default(),
+        }
+    }
+}
+
+impl Cache {
+    pub fn new() -> Cache {
+        Default::default()
+    }
+
+    pub fn contains(&self, module: &str) -> bool {
+        self.modules.contains_key(module)
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    /// Tells the cache the source of `module` is now `source`. If it changed, the module is
+    /// invalidated, see [`Cache::invalidate`].
+    pub fn update(&mut self, module: &str, source: &str) -> Vec<String> {
+        match self.modules.get(module) {
+            Some(cached) if cached.hash.ne(&hash(source)) => self.invalidate(module),
+            _ => vec![],
+        }
+    }
+
+    /// Drops `module` and the modules importing it, directly or not, and returns their names.
+    pub fn invalidate(&mut self, module: &str) -> Vec<String> {
+        if !self.modules.contains_key(module) {
+            return vec![];
+        }
+        let invalidated = self.importers(vec![module.to_string()]);
+        for module in invalidated.iter() {
+            self.modules.remove(module);
+        }
+        invalidated
+    }
+
+    /// `modules`, and the modules of the cache importing them, directly or not.
+    fn importers(&self, modules: Vec<String>) -> Vec<String> {
+        let mut importers: Vec<String> = vec![];
+        let mut to_visit = modules;
+        while let Some(module) = to_visit.pop() {
+            if importers.contains(&module) {
+                continue;
+            }
+            to_visit.extend(
+                self.modules
+                    .iter()
+                    .filter(|(_, cached)| cached.imports.contains(&module))
+                    .map(|(name, _)| name.to_string()),
+            );
+            importers.push(module);
+        }
+        importers
+    }
+
+    /// Modules of the cache `document` imports, directly or not.
+    fn imported(&self, document: &ftd::interpreter2::ParsedDocument) -> Vec<&CachedModule> {
+        let mut imported: Vec<&CachedModule> = vec![];
+        let mut to_import = imports(document);
+        while let Some(module) = to_import.pop() {
+            if module.eq(&document.name) || imported.iter().any(|v| v.document.name.eq(&module)) {
+                continue;
+            }
+            if let Some(cached) = self.modules.get(module.as_str()) {
+                to_import.extend(cached.imports.iter().cloned());
+                imported.push(cached);
+            }
+        }
+        imported
+    }
+}
+
+/**
+ * Interprets document `id` to the end like [`ftd::interpreter2::interpret_with_host`], reusing
+ * the modules of `cache`, and adding the ones loaded to it.
+ *
+ * The host is still asked for every module the document imports, directly or not, and the
+ * cached ones whose source or foreign things changed are dropped, see [`Cache::invalidate`].
+ * The processors and the foreign variables are always given by the host. The document itself
+ * is added to the cache too, for the documents importing it.
+ */
+pub fn interpret_with_cache<H: ftd::interpreter2::Host>(
+    id: &str,
+    source: &str,
+    host: &mut H,
+    cache: &mut Cache,
+) -> ftd::interpreter2::Result<ftd::interpreter2::Document> {
+    cache.update(id, source);
+    let document = match cache.modules.get(id) {
+        Some(cached) => cached.document.clone(),
+        None => ftd::interpreter2::ParsedDocument::parse(id, source)?,
+    };
+    let mut host = Preloaded {
+        modules: refresh(cache, &document, host),
+        host,
+    };
+    let mut loaded: Vec<(String, CachedModule)> = vec![];
+    if !cache.contains(id) {
+        loaded.push((
+            id.to_string(),
+            CachedModule::new(hash(source), document.clone()),
+        ));
+    }
+
+    let mut state = ftd::interpreter2::InterpreterState::new(id.to_string());
+    for cached in cache.imported(&document) {
+        state
+            .parsed_libs
+            .insert(cached.document.name.to_string(), cached.document.clone());
+        state.bag.extend(cached.things.clone());
+    }
+
+    let document = ftd::interpreter2::host::continue_with_imports(
+        state.start(document)?,
+        &mut host,
+        |module, module_source, document| {
+            let mut document = document.clone();
+            document.add_foreign_variable(module_source.foreign_variables.clone());
+            document.add_foreign_function(module_source.foreign_functions.clone());
+            loaded.push((
+                module.to_string(),
+                CachedModule::new(hash(module_source.source.as_str()), document),
+            ));
+        },
+    )?;
+
+    cache.modules.extend(loaded);
+    // the things of these modules may depend on the updates, or on the host
+    let mut updated = updated_modules(cache, &document);
+    updated.extend(dynamic_modules(cache, &document));
+    let updated = cache.importers(updated);
+    for (name, thing) in document.data.iter() {
+        let module = match name.split_once('#') {
+            Some((module, _)) if !updated.iter().any(|v| v.as_str().eq(module)) => module,
+            _ => continue,
+        };
+        if let Some(cached) = cache.modules.get_mut(module) {
+            cached
+                .things
+                .entry(name.to_string())
+                .or_insert_with(|| thing.clone());
+        }
+    }
+    Ok(document)
+}
+
+/// Modules, imported by `document` or `document` itself, with a thing updated by a variable
+/// invocation.
+fn updated_modules(cache: &Cache, document: &ftd::interpreter2::Document) -> Vec<String> {
+    let mut updated = vec![];
+    let cached = match cache.modules.get(document.name.as_str()) {
+        Some(cached) => cached,
+        None => return updated,
+    };
+    for parsed_document in std::iter::once(&cached.document).chain(
+        cache
+            .imported(&cached.document)
+            .into_iter()
+            .map(|v| &v.document),
+    ) {
+        for ast in parsed_document.ast.iter() {
+            if !ast.is_variable_invocation() {
+                continue;
+            }
+            let name = ftd::interpreter2::utils::resolve_name(
+                ast.name().as_str(),
+                parsed_document.name.as_str(),
+                &parsed_document.doc_aliases,
+            );
+            if let Some((module, _)) = name.split_once('#') {
+                if !updated.iter().any(|v: &String| v.as_str().eq(module)) {
+                    updated.push(module.to_string());
+                }
+            }
+        }
+    }
+    updated
+}
+
+/// Modules, imported by `document` or `document` itself, with a processor or a foreign
+/// variable.
+fn dynamic_modules(cache: &Cache, document: &ftd::interpreter2::Document) -> Vec<String> {
+    let cached = match cache.modules.get(document.name.as_str()) {
+        Some(cached) => cached,
+        None => return vec![],
+    };
+    std::iter::once(&cached.document)
+        .chain(
+            cache
+                .imported(&cached.document)
+                .into_iter()
+                .map(|v| &v.document),
+        )
+        .filter(|parsed_document| {
+            !parsed_document.foreign_variable.is_empty()
+                || parsed_document.ast.iter().any(|ast| match ast {
+                    ftd::ast::AST::VariableDefinition(v) => v.processor.is_some(),
+                    _ => false,
+                })
+        })
+        .map(|v| v.name.to_string())
+        .collect()
+}
+
+/// Asks `host` for the modules of `cache` that `document` imports, directly or not, and drops
+/// the ones that changed. Returns the modules the host gave, for the interpreter not to ask for
+/// them again.
+fn refresh<H: ftd::interpreter2::Host>(
+    cache: &mut Cache,
+    document: &ftd::interpreter2::ParsedDocument,
+    host: &mut H,
+) -> ftd::Map<ftd::interpreter2::Module> {
+    let mut loaded: ftd::Map<ftd::interpreter2::Module> = Default::default();
+    let mut to_check: Vec<(String, String)> = imports(document)
+        .into_iter()
+        .map(|v| (v, document.name.to_string()))
+        .collect();
+    while let Some((module, caller_module)) = to_check.pop() {
+        if module.eq(&document.name) || loaded.contains_key(module.as_str()) {
+            continue;
+        }
+        let cached = match cache.modules.get(module.as_str()) {
+            Some(cached) => cached,
+            None => continue,
+        };
+        to_check.extend(
+            cached
+                .imports
+                .iter()
+                .map(|v| (v.to_string(), module.to_string())),
+        );
+        let module_source = host.load_module(module.as_str(), caller_module.as_str());
+        let unchanged = module_source.as_ref().map_or(false, |v| {
+            cached.hash.eq(&hash(v.source.as_str()))
+                && cached.document.foreign_variable.eq(&v.foreign_variables)
+                && cached.document.foreign_function.eq(&v.foreign_functions)
+        });
+        if !unchanged {
+            cache.invalidate(module.as_str());
+        }
+        if let Some(module_source) = module_source {
+            loaded.insert(module, module_source);
+        }
+    }
+    loaded
+}
+
+/// `host`, giving the modules of `modules` without asking it again.
+struct Preloaded<'a, H> {
+    host: &'a mut H,
+    modules: ftd::Map<ftd::interpreter2::Module>,
+}
+
+impl<'a, H: ftd::interpreter2::Host> ftd::interpreter2::Host for Preloaded<'a, H> {
+    fn load_module(
+        &mut self,
+        module: &str,
+        caller_module: &str,
+    ) -> Option<ftd::interpreter2::Module> {
+        self.modules
+            .remove(module)
+            .or_else(|| self.host.load_module(module, caller_module))
+    }
+
+    fn process(
+        &mut self,
+        processor: &str,
+        ast: &ftd::ast::AST,
+        module: &str,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+        self.host.process(processor, ast, module)
+    }
+
+    fn processors(&self) -> Option<&ftd::interpreter2::ProcessorRegistry> {
+        self.host.processors()
+    }
+
+    fn foreign_variable(
+        &mut self,
+        module: &str,
+        variable: &str,
+        caller_module: &str,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+        self.host.foreign_variable(module, variable, caller_module)
+    }
+}
+
+/// Names of the modules `document` imports.
+fn imports(document: &ftd::interpreter2::ParsedDocument) -> Vec<String> {
+    document
+        .ast
+        .iter()
+        .filter_map(|ast| match ast {
+            ftd::ast::AST::Import(import) => Some(import.module.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn hash(source: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    source.hash(&mut hasher);
+    hasher.finish()
+}
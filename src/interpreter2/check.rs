@@ -0,0 +1,443 @@
+/**
+ * Checks the kinds of the values of the things of `document` and of the components of its
+ * tree: every property, loop, event action and function call is checked against the
+ * [`ftd::interpreter2::KindData`] of what it is passed to, including the items of lists, the
+ * fields of records and the variants of or-types.
+ *
+ * All the errors are returned, in the order they are found, and not only the first one. The
+ * errors the interpreter can not go on with are found while interpreting, use
+ * [`ftd::interpreter2::interpret_to_check`] to interpret the document, so that they are kept in
+ * [`ftd::interpreter2::Document::kind_errors`] and returned first. The things of imported
+ * modules are not checked, check their documents instead.
+ */
+pub fn check(document: &ftd::interpreter2::Document) -> Vec<ftd::interpreter2::Error> {
+    let mut checker = Checker {
+        document,
+        errors: vec![],
+    };
+    for error in document.kind_errors.iter() {
+        checker.push(error.clone());
+    }
+    let prefix = format!("{}#", document.name);
+    for (name, thing) in document.data.iter() {
+        if name.starts_with(prefix.as_str()) {
+            checker.thing(thing);
+        }
+    }
+    for component in document.tree.iter() {
+        checker.component(component);
+    }
+    checker.errors.into_iter().map(Into::into).collect()
+}
+
+/// An error of [`check`], also kept by the interpreter, see
+/// [`ftd::interpreter2::Document::kind_errors`].
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct KindError {
+    pub message: String,
+    pub doc_id: String,
+    pub line_number: usize,
+}
+
+impl KindError {
+    pub(crate) fn mismatch(
+        expected: &ftd::interpreter2::Kind,
+        found: &ftd::interpreter2::Kind,
+        doc_id: &str,
+        line_number: usize,
+    ) -> KindError {
+        KindError {
+            message: format!("expected `{}`, found `{}`", expected.name(), found.name()),
+            doc_id: doc_id.to_string(),
+            line_number,
+        }
+    }
+}
+
+impl From<KindError> for ftd::interpreter2::Error {
+    fn from(e: KindError) -> ftd::interpreter2::Error {
+        ftd::interpreter2::utils::invalid_kind_error(e.message, e.doc_id.as_str(), e.line_number)
+    }
+}
+
+struct Checker<'a> {
+    document: &'a ftd::interpreter2::Document,
+    errors: Vec<KindError>,
+}
+
+impl<'a> Checker<'a> {
+    fn error(&mut self, message: String, line_number: usize) {
+        self.push(KindError {
+            message,
+            doc_id: self.document.name.to_string(),
+            line_number,
+        });
+    }
+
+    /// The interpreter finds some errors already, and may find one more than once, e.g. when
+    /// it interprets a component again once an import is resolved.
+    fn push(&mut self, error: KindError) {
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
+        }
+    }
+
+    fn thing(&mut self, thing: &ftd::interpreter2::Thing) {
+        match thing {
+            ftd::interpreter2::Thing::Variable(variable) => {
+                self.value(&variable.value, &variable.kind.kind, variable.line_number);
+                for conditional_value in variable.conditional_value.iter() {
+                    self.condition(&conditional_value.condition);
+                    self.value(
+                        &conditional_value.value,
+                        &variable.kind.kind,
+                        conditional_value.line_number,
+                    );
+                }
+            }
+            ftd::interpreter2::Thing::Record(record) => self.arguments(record.fields.as_slice()),
+            ftd::interpreter2::Thing::OrType(or_type) => {
+                for variant in or_type.variants.iter() {
+                    for field in variant.fields() {
+                        self.arguments(std::slice::from_ref(field));
+                    }
+                }
+            }
+            ftd::interpreter2::Thing::Component(definition) => {
+                self.arguments(definition.arguments.as_slice());
+                self.component(&definition.definition);
+            }
+            ftd::interpreter2::Thing::Function(function) => {
+                self.arguments(function.arguments.as_slice())
+            }
+            ftd::interpreter2::Thing::OrTypeWithVariant { .. } => {}
+        }
+    }
+
+    /// Checks the default values of `arguments`, which are also the fields of records.
+    fn arguments(&mut self, arguments: &[ftd::interpreter2::Argument]) {
+        for argument in arguments {
+            if let Some(ref value) = argument.value {
+                self.value(value, &argument.kind.kind, argument.line_number);
+            }
+        }
+    }
+
+    fn component(&mut self, component: &ftd::interpreter2::Component) {
+        if let Some(iteration) = component.iteration.as_ref() {
+            let kind = iteration.on.kind();
+            if kind.ref_inner().is_list() {
+                self.value(&iteration.on, &kind, iteration.line_number);
+            } else {
+                self.error(
+                    format!("`$loop$` expects a list, found `{}`", kind.name()),
+                    iteration.line_number,
+                );
+            }
+        }
+        if let Some(condition) = component.condition.as_ref() {
+            self.condition(condition);
+        }
+        for event in component.events.iter() {
+            self.function_call(&event.action, event.action.line_number);
+        }
+
+        let document: &'a ftd::interpreter2::Document = self.document;
+        let arguments = match document.data.get(component.name.as_str()) {
+            Some(ftd::interpreter2::Thing::Component(definition)) => {
+                Some(definition.arguments.as_slice())
+            }
+            // components of local `ftd.ui` arguments are checked where they are passed
+            _ => None,
+        };
+        if let Some(arguments) = arguments {
+            self.properties(component, arguments);
+        }
+
+        for child in component.children.iter() {
+            self.component(child);
+        }
+    }
+
+    fn properties(
+        &mut self,
+        component: &ftd::interpreter2::Component,
+        arguments: &[ftd::interpreter2::Argument],
+    ) {
+        let argument_for = |source: &ftd::interpreter2::PropertySource| {
+            arguments
+                .iter()
+                .find(|v| v.to_sources().iter().any(|s| s.is_equal(source)))
+        };
+
+        for property in component.properties.iter() {
+            match argument_for(&property.source) {
+                Some(argument) => {
+                    self.value(&property.value, &argument.kind.kind, property.line_number)
+                }
+                None => self.error(
+                    format!(
+                        "component `{}` has no {}",
+                        component.name,
+                        source_name(&property.source)
+                    ),
+                    property.line_number,
+                ),
+            }
+            if let Some(ref condition) = property.condition {
+                self.condition(condition);
+            }
+        }
+
+        for argument in arguments.iter().filter(|v| is_required(v)) {
+            let sources = argument.to_sources();
+            if !component
+                .properties
+                .iter()
+                .any(|p| sources.iter().any(|s| s.is_equal(&p.source)))
+            {
+                self.error(
+                    format!(
+                        "missing argument `{}` of component `{}`",
+                        argument.name, component.name
+                    ),
+                    component.line_number,
+                );
+            }
+        }
+    }
+
+    /// Checks the values the condition refers to, e.g. the arguments of a function it calls.
+    fn condition(&mut self, condition: &ftd::interpreter2::Expression) {
+        for value in condition.references.values() {
+            self.value(value, &value.kind(), condition.line_number);
+        }
+    }
+
+    fn value(
+        &mut self,
+        value: &ftd::interpreter2::PropertyValue,
+        expected: &ftd::interpreter2::Kind,
+        line_number: usize,
+    ) {
+        let line_number = match value.line_number() {
+            0 => line_number,
+            value_line_number => value_line_number,
+        };
+        let found = value.kind();
+        if !is_assignable(expected, &found) {
+            return self.push(KindError::mismatch(
+                expected,
+                &found,
+                self.document.name.as_str(),
+                line_number,
+            ));
+        }
+
+        match value {
+            ftd::interpreter2::PropertyValue::Value { value, .. } => {
+                self.data(value, expected, line_number)
+            }
+            ftd::interpreter2::PropertyValue::FunctionCall(function_call) => {
+                self.function_call(function_call, line_number)
+            }
+            ftd::interpreter2::PropertyValue::Reference { .. }
+            | ftd::interpreter2::PropertyValue::Clone { .. } => {}
+        }
+    }
+
+    /// Checks the parts of `value`, whose own kind is already checked against `expected`.
+    fn data(
+        &mut self,
+        value: &ftd::interpreter2::Value,
+        expected: &ftd::interpreter2::Kind,
+        line_number: usize,
+    ) {
+        match value {
+            ftd::interpreter2::Value::Optional { data, kind } => match data.as_ref() {
+                Some(data) if is_assignable(&kind.kind, &data.kind()) => {
+                    self.data(data, &kind.kind, line_number)
+                }
+                Some(data) => self.error(
+                    format!(
+                        "expected `{}`, found `{}`",
+                        kind.kind.name(),
+                        data.kind().name()
+                    ),
+                    line_number,
+                ),
+                None if !expected.is_optional() => self.error(
+                    format!("expected `{}`, found no value", expected.name()),
+                    line_number,
+                ),
+                None => {}
+            },
+            ftd::interpreter2::Value::List { data, kind } => {
+                for item in data.iter() {
+                    self.value(item, &kind.kind, line_number);
+                }
+            }
+            ftd::interpreter2::Value::Record { name, fields } => {
+                let record_fields = match self.record_fields(name) {
+                    Some(record_fields) => record_fields,
+                    None => return,
+                };
+                for (field_name, field_value) in fields.iter() {
+                    match record_fields.iter().find(|v| v.name.eq(field_name)) {
+                        Some(field) => self.value(field_value, &field.kind.kind, line_number),
+                        None => self.error(
+                            format!("record `{}` has no field `{}`", name, field_name),
+                            line_number,
+                        ),
+                    }
+                }
+            }
+            ftd::interpreter2::Value::OrType {
+                name,
+                variant,
+                value,
+                ..
+            } => {
+                let document: &'a ftd::interpreter2::Document = self.document;
+                let or_type = match document.data.get(name.as_str()) {
+                    Some(ftd::interpreter2::Thing::OrType(or_type)) => or_type,
+                    _ => return,
+                };
+                match or_type.variants.iter().find(|v| v.name().eq(variant)) {
+                    Some(ftd::interpreter2::OrTypeVariant::AnonymousRecord(record)) => {
+                        self.value(
+                            value,
+                            &ftd::interpreter2::Kind::record(record.name.as_str()),
+                            line_number,
+                        );
+                    }
+                    Some(ftd::interpreter2::OrTypeVariant::Regular(field))
+                    | Some(ftd::interpreter2::OrTypeVariant::Constant(field)) => {
+                        self.value(value, &field.kind.kind, line_number);
+                    }
+                    None => self.error(
+                        format!("or-type `{}` has no variant `{}`", name, variant),
+                        line_number,
+                    ),
+                }
+            }
+            ftd::interpreter2::Value::UI { component, .. } => self.component(component),
+            ftd::interpreter2::Value::String { .. }
+            | ftd::interpreter2::Value::Integer { .. }
+            | ftd::interpreter2::Value::Decimal { .. }
+            | ftd::interpreter2::Value::Boolean { .. }
+            | ftd::interpreter2::Value::Object { .. } => {}
+        }
+    }
+
+    /// The fields of record `name`, which is either a record or the anonymous record of an
+    /// or-type variant.
+    fn record_fields(&self, name: &str) -> Option<&'a [ftd::interpreter2::Field]> {
+        let document: &'a ftd::interpreter2::Document = self.document;
+        if let Some(ftd::interpreter2::Thing::Record(record)) = document.data.get(name) {
+            return Some(record.fields.as_slice());
+        }
+        let (or_type, _) = name.rsplit_once('.')?;
+        match document.data.get(or_type) {
+            Some(ftd::interpreter2::Thing::OrType(or_type)) => {
+                or_type.variants.iter().find_map(|v| match v {
+                    ftd::interpreter2::OrTypeVariant::AnonymousRecord(record)
+                        if record.name.eq(name) =>
+                    {
+                        Some(record.fields.as_slice())
+                    }
+                    _ => None,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn function_call(
+        &mut self,
+        function_call: &ftd::interpreter2::FunctionCall,
+        line_number: usize,
+    ) {
+        let line_number = match function_call.line_number {
+            0 => line_number,
+            call_line_number => call_line_number,
+        };
+        let document: &'a ftd::interpreter2::Document = self.document;
+        // foreign functions have no definition to check against
+        let function = match document.data.get(function_call.name.as_str()) {
+            Some(ftd::interpreter2::Thing::Function(function)) => function,
+            _ => return,
+        };
+
+        for (name, value) in function_call.values.iter() {
+            let argument = match function.arguments.iter().find(|v| v.name.eq(name)) {
+                Some(argument) => argument,
+                None => {
+                    self.error(
+                        format!("function `{}` has no argument `{}`", function.name, name),
+                        line_number,
+                    );
+                    continue;
+                }
+            };
+            if argument.mutable && !value.is_mutable() && argument.value.as_ref().ne(&Some(value)) {
+                self.error(
+                    format!(
+                        "argument `{}` of function `{}` expects a mutable reference",
+                        name, function.name
+                    ),
+                    line_number,
+                );
+            }
+            self.value(value, &argument.kind.kind, line_number);
+        }
+
+        for argument in function.arguments.iter().filter(|v| is_required(v)) {
+            if !function_call.values.contains_key(argument.name.as_str()) {
+                self.error(
+                    format!(
+                        "missing argument `{}` of function `{}`",
+                        argument.name, function.name
+                    ),
+                    line_number,
+                );
+            }
+        }
+    }
+}
+
+/**
+ * Whether a value of kind `found` can be passed where `expected` is expected. This is
+ * [`ftd::interpreter2::Kind::is_same_as`], except that any component can be passed as
+ * `ftd.ui`, a constant has the kind of its value and a record can be passed as `object`.
+ */
+fn is_assignable(expected: &ftd::interpreter2::Kind, found: &ftd::interpreter2::Kind) -> bool {
+    use ftd::interpreter2::Kind;
+
+    match (expected, found) {
+        (Kind::Optional { kind }, _) | (Kind::Constant { kind }, _) => is_assignable(kind, found),
+        (_, Kind::Optional { kind }) | (_, Kind::Constant { kind }) => {
+            is_assignable(expected, kind)
+        }
+        (Kind::List { kind }, Kind::List { kind: found }) => is_assignable(kind, found),
+        (Kind::UI { .. }, Kind::UI { .. }) => true,
+        (Kind::Object, Kind::Record { .. }) => true,
+        (Kind::OrType { name, .. }, Kind::OrType { name: found, .. }) => name.eq(found),
+        _ => expected.eq(found),
+    }
+}
+
+/// An argument that must be passed: it has no default value, and is neither optional nor a
+/// list, which is empty by default.
+fn is_required(argument: &ftd::interpreter2::Argument) -> bool {
+    argument.value.is_none() && !argument.kind.is_optional() && !argument.kind.is_list()
+}
+
+fn source_name(source: &ftd::interpreter2::PropertySource) -> String {
+    match source {
+        ftd::interpreter2::PropertySource::Caption => "caption".to_string(),
+        ftd::interpreter2::PropertySource::Body => "body".to_string(),
+        ftd::interpreter2::PropertySource::Subsection => "children".to_string(),
+        ftd::interpreter2::PropertySource::Header { name, .. } => format!("argument `{}`", name),
+    }
+}
@@ -36,11 +36,70 @@ pub const FTD_RESIZING_FILL_CONTAINER: &str = "ftd#resizing.fill-container";
 pub const FTD_RESIZING_AUTO: &str = "ftd#resizing.auto";
 pub const FTD_RESIZING_FIXED: &str = "ftd#resizing.fixed";
 
+pub const FTD_GRID_TRACK: &str = "ftd#grid-track";
+pub const FTD_GRID_TRACK_FIXED: &str = "ftd#grid-track.fixed";
+pub const FTD_GRID_TRACK_FRACTION: &str = "ftd#grid-track.fraction";
+pub const FTD_GRID_TRACK_AUTO: &str = "ftd#grid-track.auto";
+pub const FTD_GRID_TRACK_MIN_CONTENT: &str = "ftd#grid-track.min-content";
+pub const FTD_GRID_TRACK_MAX_CONTENT: &str = "ftd#grid-track.max-content";
+
 pub const FTD_COLOR: &str = "ftd#color";
 pub const FTD_COLOR_LIGHT: &str = "ftd#color.light";
 
 pub const FTD_BACKGROUND: &str = "ftd#background";
 pub const FTD_BACKGROUND_SOLID: &str = "ftd#background.solid";
+pub const FTD_BACKGROUND_LINEAR_GRADIENT: &str = "ftd#background.linear-gradient";
+pub const FTD_BACKGROUND_RADIAL_GRADIENT: &str = "ftd#background.radial-gradient";
+pub const FTD_BACKGROUND_IMAGE: &str = "ftd#background.image";
+pub const FTD_BACKGROUND_LAYERS: &str = "ftd#background.layers";
+
+pub const FTD_COLOR_STOP: &str = "ftd#color-stop";
+pub const FTD_LINEAR_GRADIENT: &str = "ftd#linear-gradient";
+pub const FTD_RADIAL_GRADIENT: &str = "ftd#radial-gradient";
+pub const FTD_IMAGE_BACKGROUND: &str = "ftd#image-background";
+
+pub const FTD_LINEAR_GRADIENT_DIRECTIONS: &str = "ftd#linear-gradient-directions";
+pub const FTD_LINEAR_GRADIENT_DIRECTIONS_TOP: &str = "ftd#linear-gradient-directions.top";
+pub const FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM: &str = "ftd#linear-gradient-directions.bottom";
+pub const FTD_LINEAR_GRADIENT_DIRECTIONS_LEFT: &str = "ftd#linear-gradient-directions.left";
+pub const FTD_LINEAR_GRADIENT_DIRECTIONS_RIGHT: &str = "ftd#linear-gradient-directions.right";
+pub const FTD_LINEAR_GRADIENT_DIRECTIONS_TOP_LEFT: &str = "ftd#linear-gradient-directions.top-left";
+pub const FTD_LINEAR_GRADIENT_DIRECTIONS_TOP_RIGHT: &str =
+    "ftd#linear-gradient-directions.top-right";
+pub const FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM_LEFT: &str =
+    "ftd#linear-gradient-directions.bottom-left";
+pub const FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM_RIGHT: &str =
+    "ftd#linear-gradient-directions.bottom-right";
+pub const FTD_LINEAR_GRADIENT_DIRECTIONS_ANGLE: &str = "ftd#linear-gradient-directions.angle";
+
+pub const FTD_RADIAL_GRADIENT_SHAPE: &str = "ftd#radial-gradient-shape";
+pub const FTD_RADIAL_GRADIENT_SHAPE_CIRCLE: &str = "ftd#radial-gradient-shape.circle";
+pub const FTD_RADIAL_GRADIENT_SHAPE_ELLIPSE: &str = "ftd#radial-gradient-shape.ellipse";
+
+pub const FTD_BACKGROUND_REPEAT: &str = "ftd#background-repeat";
+pub const FTD_BACKGROUND_REPEAT_REPEAT: &str = "ftd#background-repeat.repeat";
+pub const FTD_BACKGROUND_REPEAT_REPEAT_X: &str = "ftd#background-repeat.repeat-x";
+pub const FTD_BACKGROUND_REPEAT_REPEAT_Y: &str = "ftd#background-repeat.repeat-y";
+pub const FTD_BACKGROUND_REPEAT_NO_REPEAT: &str = "ftd#background-repeat.no-repeat";
+pub const FTD_BACKGROUND_REPEAT_SPACE: &str = "ftd#background-repeat.space";
+pub const FTD_BACKGROUND_REPEAT_ROUND: &str = "ftd#background-repeat.round";
+
+pub const FTD_BACKGROUND_SIZE: &str = "ftd#background-size";
+pub const FTD_BACKGROUND_SIZE_AUTO: &str = "ftd#background-size.auto";
+pub const FTD_BACKGROUND_SIZE_COVER: &str = "ftd#background-size.cover";
+pub const FTD_BACKGROUND_SIZE_CONTAIN: &str = "ftd#background-size.contain";
+pub const FTD_BACKGROUND_SIZE_FIXED: &str = "ftd#background-size.fixed";
+
+pub const FTD_BACKGROUND_POSITION: &str = "ftd#background-position";
+pub const FTD_BACKGROUND_POSITION_LEFT: &str = "ftd#background-position.left";
+pub const FTD_BACKGROUND_POSITION_CENTER: &str = "ftd#background-position.center";
+pub const FTD_BACKGROUND_POSITION_RIGHT: &str = "ftd#background-position.right";
+pub const FTD_BACKGROUND_POSITION_TOP: &str = "ftd#background-position.top";
+pub const FTD_BACKGROUND_POSITION_BOTTOM: &str = "ftd#background-position.bottom";
+pub const FTD_BACKGROUND_POSITION_LEFT_TOP: &str = "ftd#background-position.left-top";
+pub const FTD_BACKGROUND_POSITION_LEFT_BOTTOM: &str = "ftd#background-position.left-bottom";
+pub const FTD_BACKGROUND_POSITION_RIGHT_TOP: &str = "ftd#background-position.right-top";
+pub const FTD_BACKGROUND_POSITION_RIGHT_BOTTOM: &str = "ftd#background-position.right-bottom";
 
 pub const FTD_IMAGE_SRC: &str = "ftd#image-src";
 pub const FTD_IMAGE_SRC_LIGHT: &str = "ftd#image-src.light";
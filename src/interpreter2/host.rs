@@ -0,0 +1,249 @@
+/**
+ * What the interpreter needs from the application a document is interpreted in: the modules
+ * the document imports, and the values of its processors and foreign variables.
+ *
+ * [`interpret_with_host`] interprets a document to the end, asking the host whenever the
+ * [`ftd::interpreter2::Interpreter`] state machine is stuck. [`Directory`] and
+ * `HashMap<String, String>` are hosts that only provide modules.
+ */
+pub trait Host {
+    /// Module `module`, imported by `caller_module`, or `None` if there is no such module.
+    fn load_module(&mut self, module: &str, caller_module: &str) -> Option<Module>;
+
+    /// Value of the variable defined by `ast` in `module`, which uses `processor`.
+    fn process(
+        &mut self,
+        processor: &str,
+        ast: &ftd::ast::AST,
+        module: &str,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+        ftd::interpreter2::utils::e2(
+            format!("processor `{}` is not supported", processor),
+            module,
+            ast.line_number(),
+        )
+    }
+
+    /// Processors that [`Host::process`] is not called for, see
+    /// [`ftd::interpreter2::ProcessorRegistry`].
+    fn processors(&self) -> Option<&ftd::interpreter2::ProcessorRegistry> {
+        None
+    }
+
+    /// Value of `variable` of `module`, declared as foreign by [`Host::load_module`]. For a
+    /// field of a record `variable` is `name.field`.
+    fn foreign_variable(
+        &mut self,
+        module: &str,
+        variable: &str,
+        caller_module: &str,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+        ftd::interpreter2::utils::e2(
+            format!(
+                "foreign variable `{}.{}` is not supported",
+                module, variable
+            ),
+            caller_module,
+            0,
+        )
+    }
+}
+
+/**
+ * A module returned by [`Host::load_module`].
+ *
+ * # Fields
+ *
+ * - `source`: content of the module
+ * - `foreign_variables`: variables of the module whose value is given by
+ *   [`Host::foreign_variable`] instead of the source
+ * - `foreign_functions`: functions of the module that are implemented by the javascript of the
+ *   page
+ */
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Module {
+    pub source: String,
+    pub foreign_variables: Vec<String>,
+    pub foreign_functions: Vec<String>,
+}
+
+impl From<String> for Module {
+    fn from(source: String) -> Module {
+        Module {
+            source,
+            ..Default::default()
+        }
+    }
+}
+
+/// Interprets document `id` to the end, see [`Host`].
+pub fn interpret_with_host<H: Host>(
+    id: &str,
+    source: &str,
+    host: &mut H,
+) -> ftd::interpreter2::Result<ftd::interpreter2::Document> {
+    continue_with_host(ftd::interpreter2::interpret(id, source)?, host)
+}
+
+/// Drives `interpreter` to [`ftd::interpreter2::Interpreter::Done`], for callers that start
+/// the interpretation themselves.
+pub fn continue_with_host<H: Host>(
+    interpreter: ftd::interpreter2::Interpreter,
+    host: &mut H,
+) -> ftd::interpreter2::Result<ftd::interpreter2::Document> {
+    continue_with_imports(interpreter, host, |_, _, _| ())
+}
+
+/// Same as [`continue_with_host`], calling `on_import` with every module loaded, once parsed.
+pub(crate) fn continue_with_imports<H, F>(
+    mut interpreter: ftd::interpreter2::Interpreter,
+    host: &mut H,
+    mut on_import: F,
+) -> ftd::interpreter2::Result<ftd::interpreter2::Document>
+where
+    H: Host,
+    F: FnMut(&str, &Module, &ftd::interpreter2::ParsedDocument),
+{
+    loop {
+        interpreter = match interpreter {
+            ftd::interpreter2::Interpreter::Done { document } => return Ok(document),
+            ftd::interpreter2::Interpreter::StuckOnImport {
+                module,
+                state,
+                caller_module,
+            } => {
+                let loaded = match host.load_module(module.as_str(), caller_module.as_str()) {
+                    Some(loaded) => loaded,
+                    None => {
+                        return ftd::interpreter2::utils::e2(
+                            format!("Cannot find module `{}`", module),
+                            caller_module.as_str(),
+                            import_line_number(&state, module.as_str(), caller_module.as_str()),
+                        )
+                    }
+                };
+                let document = ftd::interpreter2::ParsedDocument::parse(
+                    module.as_str(),
+                    loaded.source.as_str(),
+                )?;
+                on_import(module.as_str(), &loaded, &document);
+                state.continue_after_import(
+                    module.as_str(),
+                    document,
+                    loaded.foreign_variables,
+                    loaded.foreign_functions,
+                    0,
+                )?
+            }
+            ftd::interpreter2::Interpreter::StuckOnProcessor {
+                mut state,
+                ast,
+                module,
+                processor,
+                caller_module,
+            } => match host.processors() {
+                Some(registry) if registry.contains(module.as_str(), processor.as_str()) => {
+                    match registry.process(
+                        module.as_str(),
+                        processor.as_str(),
+                        &ast,
+                        caller_module.as_str(),
+                        &mut state,
+                    )? {
+                        ftd::interpreter2::StateWithThing::Thing(value) => {
+                            state.continue_after_processor(value)?
+                        }
+                        ftd::interpreter2::StateWithThing::State(s) => s.into_interpreter(state),
+                        ftd::interpreter2::StateWithThing::Continue => {
+                            state.continue_processing()?
+                        }
+                    }
+                }
+                _ => state.continue_after_processor(host.process(
+                    processor.as_str(),
+                    &ast,
+                    module.as_str(),
+                )?)?,
+            },
+            ftd::interpreter2::Interpreter::StuckOnForeignVariable {
+                state,
+                module,
+                variable,
+                caller_module,
+            } => {
+                let value = host.foreign_variable(
+                    module.as_str(),
+                    variable.as_str(),
+                    caller_module.as_str(),
+                )?;
+                state.continue_after_variable(module.as_str(), variable.as_str(), value)?
+            }
+        };
+    }
+}
+
+/// Line of the `-- import:` of `module` in `caller_module`, `0` if it is not found.
+pub(crate) fn import_line_number(
+    state: &ftd::interpreter2::InterpreterState,
+    module: &str,
+    caller_module: &str,
+) -> usize {
+    state
+        .parsed_libs
+        .get(caller_module)
+        .and_then(|document| {
+            document.ast.iter().find_map(|ast| match ast {
+                ftd::ast::AST::Import(import) if import.module.eq(module) => {
+                    Some(import.line_number)
+                }
+                _ => None,
+            })
+        })
+        .unwrap_or_default()
+}
+
+/**
+ * Reads modules from directories: module `foo/bar` is `foo/bar.ftd` or `foo/bar/index.ftd`,
+ * in the first directory of `search_path` that has either.
+ *
+ * # Fields
+ *
+ * - `search_path`: the directories, in the order they are searched
+ * - `loaded`: the file every module was read from, by module name
+ */
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Directory {
+    pub search_path: Vec<std::path::PathBuf>,
+    pub loaded: ftd::Map<std::path::PathBuf>,
+}
+
+impl Directory {
+    pub fn new(search_path: Vec<std::path::PathBuf>) -> Directory {
+        Directory {
+            search_path,
+            loaded: Default::default(),
+        }
+    }
+}
+
+impl Host for Directory {
+    fn load_module(&mut self, module: &str, _caller_module: &str) -> Option<Module> {
+        let (path, source) = self.search_path.iter().find_map(|dir| {
+            [format!("{}.ftd", module), format!("{}/index.ftd", module)]
+                .iter()
+                .find_map(|name| {
+                    let path = dir.join(name);
+                    std::fs::read_to_string(&path).ok().map(|v| (path, v))
+                })
+        })?;
+        self.loaded.insert(module.to_string(), path);
+        Some(source.into())
+    }
+}
+
+/// Source of the modules, by module name.
+impl Host for std::collections::HashMap<String, String> {
+    fn load_module(&mut self, module: &str, _caller_module: &str) -> Option<Module> {
+        self.get(module).map(|v| v.to_string().into())
+    }
+}
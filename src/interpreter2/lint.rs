@@ -0,0 +1,509 @@
+/// A problem of a document that does not stop it from being rendered, see [`lint`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    pub id: LintId,
+    pub level: ftd::diagnostic::Level,
+    pub message: String,
+    pub doc_id: String,
+    pub line_number: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintId {
+    /// an `import` whose alias is never referred to
+    UnusedImport,
+    /// an argument of a component that its definition never refers to
+    UnusedArgument,
+    /// a variable the document never refers to
+    UnusedVariable,
+    /// a record, or-type, component or function the document never refers to
+    UnusedDefinition,
+    /// a `$loop$` alias with the name of a variable of the document or of an outer loop alias
+    ShadowedLoopAlias,
+    /// a mutable variable that is never passed as a mutable reference
+    UnmutatedVariable,
+}
+
+impl LintId {
+    pub const ALL: [LintId; 6] = [
+        LintId::UnusedImport,
+        LintId::UnusedArgument,
+        LintId::UnusedVariable,
+        LintId::UnusedDefinition,
+        LintId::ShadowedLoopAlias,
+        LintId::UnmutatedVariable,
+    ];
+
+    /// The name of the lint in `;; ftd-allow:` comments.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintId::UnusedImport => "unused-import",
+            LintId::UnusedArgument => "unused-argument",
+            LintId::UnusedVariable => "unused-variable",
+            LintId::UnusedDefinition => "unused-definition",
+            LintId::ShadowedLoopAlias => "shadowed-loop-alias",
+            LintId::UnmutatedVariable => "unmutated-variable",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LintId> {
+        LintId::ALL.into_iter().find(|v| v.as_str().eq(name))
+    }
+
+    /// A shadowed loop alias is an error, as the shadowed variable can't be referred to in the
+    /// loop, the other lints are warnings.
+    pub fn level(&self) -> ftd::diagnostic::Level {
+        match self {
+            LintId::ShadowedLoopAlias => ftd::diagnostic::Level::Error,
+            _ => ftd::diagnostic::Level::Warning,
+        }
+    }
+}
+
+/// Prefix of the comments that turn lints off, e.g. `;; ftd-allow: unused-variable`.
+pub const ALLOW_COMMENT: &str = ";; ftd-allow:";
+
+/**
+ * Lints `document`, the interpreted `source`: reports the unused imports, component arguments,
+ * variables and definitions, the shadowed loop aliases and the mutable variables that are never
+ * mutated, sorted by line.
+ *
+ * Unused variables and definitions are only reported for documents that render something, the
+ * things of a library are meant to be used by other documents.
+ *
+ * A `;; ftd-allow: <lint>, <lint>...` comment right before a top level section, or in it, turns
+ * these lints off for that section, sub sections included.
+ */
+pub fn lint(
+    document: &ftd::interpreter2::Document,
+    source: &str,
+) -> ftd::interpreter2::Result<Vec<Lint>> {
+    let sections = ftd::p11::parse(source, document.name.as_str())?;
+    let ast = ftd::ast::AST::from_sections(sections.as_slice(), document.name.as_str())?;
+
+    let mut linter = Linter {
+        document,
+        owner: None,
+        used: Default::default(),
+        local: Default::default(),
+        mutated: Default::default(),
+        loop_aliases: vec![],
+        globals: Default::default(),
+        lints: vec![],
+    };
+    linter.globals = ast
+        .iter()
+        .filter(|v| !v.is_import() && !v.is_component() && !v.is_variable_invocation())
+        .map(|v| linter.full_name(v.name().as_str()))
+        .collect();
+    for thing in linter.own_things() {
+        linter.owner = Some(thing.name());
+        linter.thing(thing);
+    }
+    linter.owner = None;
+    for component in document.tree.iter() {
+        linter.component(component);
+    }
+    for ast in ast.iter() {
+        if let ftd::ast::AST::VariableDefinition(ftd::ast::VariableDefinition {
+            processor: Some(processor),
+            ..
+        }) = ast
+        {
+            let processor = linter.full_name(processor.as_str());
+            linter.used.insert(processor);
+        }
+    }
+
+    linter.unused(ast.as_slice());
+    linter.unused_arguments();
+    linter.unmutated_variables();
+
+    let mut lints = allowed(linter.lints, source, sections.as_slice());
+    lints.sort_by_key(|v| v.line_number);
+    Ok(lints)
+}
+
+/**
+ * `lints` without the ones turned off by `;; ftd-allow:` comments. A comment applies to the top
+ * level section that follows it, if only comments and blank lines are in between, or else to
+ * the top level section it is in.
+ */
+fn allowed(lints: Vec<Lint>, source: &str, sections: &[ftd::p11::Section]) -> Vec<Lint> {
+    let starts = sections.iter().map(|v| v.line_number).collect::<Vec<_>>();
+    let section_of = |line_number: usize| starts.iter().filter(|v| **v <= line_number).max();
+
+    let lines = source.split('\n').collect::<Vec<_>>();
+    let mut allowed: std::collections::BTreeMap<usize, Vec<LintId>> = Default::default();
+    for (idx, line) in lines.iter().enumerate() {
+        let names = match line.strip_prefix(ALLOW_COMMENT) {
+            Some(names) => names,
+            None => continue,
+        };
+        let next = starts.iter().find(|v| **v > idx + 1).filter(|next| {
+            lines[idx + 1..**next - 1]
+                .iter()
+                .all(|v| v.trim().is_empty() || v.starts_with(";;"))
+        });
+        if let Some(start) = next.or_else(|| section_of(idx + 1)) {
+            allowed.entry(*start).or_default().extend(
+                names
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter_map(LintId::from_name),
+            );
+        }
+    }
+
+    lints
+        .into_iter()
+        .filter(|lint| {
+            !section_of(lint.line_number)
+                .and_then(|v| allowed.get(v))
+                .map_or(false, |ids| ids.contains(&lint.id))
+        })
+        .collect()
+}
+
+struct Linter<'a> {
+    document: &'a ftd::interpreter2::Document,
+    /// the thing of the document being walked, `None` for the tree
+    owner: Option<String>,
+    /// full names referred to from outside of the thing they belong to, e.g. `foo#x` or
+    /// `foo#person.name`
+    used: std::collections::BTreeSet<String>,
+    /// full names referred to from inside of the thing they belong to, e.g. the arguments of a
+    /// component referred to in its definition
+    local: std::collections::BTreeSet<String>,
+    /// full names passed as mutable references
+    mutated: std::collections::BTreeSet<String>,
+    /// aliases of the loops the component being walked is in
+    loop_aliases: Vec<String>,
+    /// full names of the things defined in the document
+    globals: std::collections::BTreeSet<String>,
+    lints: Vec<Lint>,
+}
+
+impl<'a> Linter<'a> {
+    fn full_name(&self, name: &str) -> String {
+        ftd::interpreter2::utils::resolve_name(
+            name,
+            self.document.name.as_str(),
+            &self.document.aliases,
+        )
+    }
+
+    fn own_things(&self) -> Vec<&'a ftd::interpreter2::Thing> {
+        let document: &'a ftd::interpreter2::Document = self.document;
+        let prefix = format!("{}#", document.name);
+        document
+            .data
+            .iter()
+            .filter(|(name, _)| name.starts_with(prefix.as_str()))
+            .map(|(_, thing)| thing)
+            .collect()
+    }
+
+    fn report(&mut self, id: LintId, message: String, line_number: usize) {
+        self.lints.push(Lint {
+            id,
+            level: id.level(),
+            message,
+            doc_id: self.document.name.to_string(),
+            line_number,
+        });
+    }
+
+    /// Whether `name`, or something in it (e.g. a field of a record variable), is referred to.
+    fn is_in(names: &std::collections::BTreeSet<String>, name: &str) -> bool {
+        let prefix = format!("{}.", name);
+        names.contains(name) || names.iter().any(|v| v.starts_with(prefix.as_str()))
+    }
+
+    fn reference(&mut self, name: &str) {
+        let is_local = self.owner.as_ref().map_or(false, |owner| {
+            name.eq(owner) || name.starts_with(format!("{}.", owner).as_str())
+        });
+        if is_local {
+            self.local.insert(name.to_string());
+        } else {
+            self.used.insert(name.to_string());
+        }
+    }
+
+    fn thing(&mut self, thing: &ftd::interpreter2::Thing) {
+        match thing {
+            ftd::interpreter2::Thing::Variable(variable) => {
+                self.kind(&variable.kind.kind);
+                self.value(&variable.value);
+                for conditional_value in variable.conditional_value.iter() {
+                    self.condition(&conditional_value.condition);
+                    self.value(&conditional_value.value);
+                }
+            }
+            ftd::interpreter2::Thing::Record(record) => self.arguments(record.fields.as_slice()),
+            ftd::interpreter2::Thing::OrType(or_type) => {
+                for variant in or_type.variants.iter() {
+                    for field in variant.fields() {
+                        self.arguments(std::slice::from_ref(field));
+                    }
+                }
+            }
+            ftd::interpreter2::Thing::Component(definition) => {
+                self.arguments(definition.arguments.as_slice());
+                self.component(&definition.definition);
+            }
+            ftd::interpreter2::Thing::Function(function) => {
+                self.kind(&function.return_kind.kind);
+                self.arguments(function.arguments.as_slice());
+            }
+            ftd::interpreter2::Thing::OrTypeWithVariant { .. } => {}
+        }
+    }
+
+    fn arguments(&mut self, arguments: &[ftd::interpreter2::Argument]) {
+        for argument in arguments {
+            self.kind(&argument.kind.kind);
+            if let Some(ref value) = argument.value {
+                self.value(value);
+            }
+        }
+    }
+
+    fn kind(&mut self, kind: &ftd::interpreter2::Kind) {
+        match kind {
+            ftd::interpreter2::Kind::Record { name }
+            | ftd::interpreter2::Kind::OrType { name, .. }
+            | ftd::interpreter2::Kind::UI {
+                name: Some(name), ..
+            } => self.reference(name),
+            ftd::interpreter2::Kind::List { kind }
+            | ftd::interpreter2::Kind::Optional { kind }
+            | ftd::interpreter2::Kind::Constant { kind } => self.kind(kind),
+            _ => {}
+        }
+    }
+
+    fn component(&mut self, component: &ftd::interpreter2::Component) {
+        self.reference(component.name.as_str());
+
+        let alias = match component.iteration.as_ref() {
+            Some(iteration) => {
+                self.value(&iteration.on);
+                let shadowed = if self.loop_aliases.contains(&iteration.alias) {
+                    Some("the alias of an outer loop")
+                } else if self.globals.contains(&iteration.alias) {
+                    Some("a variable of the document")
+                } else {
+                    None
+                };
+                if let Some(shadowed) = shadowed {
+                    self.report(
+                        LintId::ShadowedLoopAlias,
+                        format!(
+                            "loop alias `{}` shadows {}",
+                            iteration
+                                .alias
+                                .split_once('#')
+                                .map_or(iteration.alias.as_str(), |v| v.1),
+                            shadowed
+                        ),
+                        iteration.line_number,
+                    );
+                }
+                self.loop_aliases.push(iteration.alias.to_string());
+                true
+            }
+            None => false,
+        };
+
+        if let Some(condition) = component.condition.as_ref() {
+            self.condition(condition);
+        }
+        for event in component.events.iter() {
+            self.function_call(&event.action);
+        }
+        for property in component.properties.iter() {
+            self.value(&property.value);
+            if let Some(ref condition) = property.condition {
+                self.condition(condition);
+            }
+        }
+        for child in component.children.iter() {
+            self.component(child);
+        }
+
+        if alias {
+            self.loop_aliases.pop();
+        }
+    }
+
+    fn condition(&mut self, condition: &ftd::interpreter2::Expression) {
+        for value in condition.references.values() {
+            self.value(value);
+        }
+    }
+
+    fn function_call(&mut self, function_call: &ftd::interpreter2::FunctionCall) {
+        self.reference(function_call.name.as_str());
+        for value in function_call.values.values() {
+            self.value(value);
+        }
+    }
+
+    fn value(&mut self, value: &ftd::interpreter2::PropertyValue) {
+        match value {
+            ftd::interpreter2::PropertyValue::Reference {
+                name, is_mutable, ..
+            } => {
+                if *is_mutable {
+                    self.mutated.insert(name.to_string());
+                }
+                self.reference(name);
+            }
+            ftd::interpreter2::PropertyValue::Clone { name, .. } => self.reference(name),
+            ftd::interpreter2::PropertyValue::FunctionCall(function_call) => {
+                self.function_call(function_call)
+            }
+            ftd::interpreter2::PropertyValue::Value { value, .. } => self.data(value),
+        }
+    }
+
+    fn data(&mut self, value: &ftd::interpreter2::Value) {
+        match value {
+            ftd::interpreter2::Value::String { text } => self.markup(text),
+            ftd::interpreter2::Value::Object { values } => {
+                for value in values.values() {
+                    self.value(value);
+                }
+            }
+            ftd::interpreter2::Value::Record { name, fields } => {
+                self.reference(name);
+                for value in fields.values() {
+                    self.value(value);
+                }
+            }
+            ftd::interpreter2::Value::OrType { name, value, .. } => {
+                self.reference(name);
+                self.value(value);
+            }
+            ftd::interpreter2::Value::List { data, kind } => {
+                self.kind(&kind.kind);
+                for value in data.iter() {
+                    self.value(value);
+                }
+            }
+            ftd::interpreter2::Value::Optional { data, kind } => {
+                self.kind(&kind.kind);
+                if let Some(data) = data.as_ref() {
+                    self.data(data);
+                }
+            }
+            ftd::interpreter2::Value::UI { component, .. } => self.component(component),
+            ftd::interpreter2::Value::Integer { .. }
+            | ftd::interpreter2::Value::Decimal { .. }
+            | ftd::interpreter2::Value::Boolean { .. } => {}
+        }
+    }
+
+    /// The references of the inline markup of a text, e.g. `{$name}`, only resolved when the
+    /// text is rendered.
+    fn markup(&mut self, text: &str) {
+        let mut rest = text;
+        while let Some(start) = rest.find("{$") {
+            rest = &rest[start + 2..];
+            let end = rest
+                .find(|c: char| c == '}' || c == ':' || c.is_whitespace())
+                .unwrap_or(rest.len());
+            if end > 0 {
+                let name = self.full_name(&rest[..end]);
+                self.reference(name.as_str());
+            }
+        }
+    }
+
+    fn unused(&mut self, ast: &[ftd::ast::AST]) {
+        for ast in ast.iter() {
+            if let ftd::ast::AST::Import(ftd::ast::Import {
+                module,
+                alias,
+                line_number,
+            }) = ast
+            {
+                let prefix = format!("{}#", module);
+                if !self.used.iter().any(|v| v.starts_with(prefix.as_str())) {
+                    self.report(
+                        LintId::UnusedImport,
+                        format!("unused import `{}`", alias),
+                        *line_number,
+                    );
+                }
+            }
+        }
+
+        if self.document.tree.is_empty() {
+            return;
+        }
+        for ast in ast.iter() {
+            let (id, what) = match ast {
+                ftd::ast::AST::VariableDefinition(_) => (LintId::UnusedVariable, "variable"),
+                ftd::ast::AST::Record(_) => (LintId::UnusedDefinition, "record"),
+                ftd::ast::AST::OrType(_) => (LintId::UnusedDefinition, "or-type"),
+                ftd::ast::AST::ComponentDefinition(_) => (LintId::UnusedDefinition, "component"),
+                ftd::ast::AST::FunctionDefinition(_) => (LintId::UnusedDefinition, "function"),
+                ftd::ast::AST::Import(_)
+                | ftd::ast::AST::VariableInvocation(_)
+                | ftd::ast::AST::ComponentInvocation(_) => continue,
+            };
+            let name = ast.name();
+            if !Linter::is_in(&self.used, self.full_name(name.as_str()).as_str()) {
+                self.report(id, format!("unused {} `{}`", what, name), ast.line_number());
+            }
+        }
+    }
+
+    fn unused_arguments(&mut self) {
+        for thing in self.own_things() {
+            let definition = match thing {
+                ftd::interpreter2::Thing::Component(definition) => definition,
+                _ => continue,
+            };
+            for argument in definition.arguments.iter() {
+                let name = format!("{}.{}", definition.name, argument.name);
+                if !Linter::is_in(&self.local, name.as_str()) {
+                    self.report(
+                        LintId::UnusedArgument,
+                        format!(
+                            "unused argument `{}` of component `{}`",
+                            argument.name,
+                            definition.name.split_once('#').map_or("", |v| v.1)
+                        ),
+                        argument.line_number,
+                    );
+                }
+            }
+        }
+    }
+
+    fn unmutated_variables(&mut self) {
+        // the mutable variables of a library are there for the documents importing it to mutate
+        if self.document.tree.is_empty() {
+            return;
+        }
+        for thing in self.own_things() {
+            let variable = match thing {
+                ftd::interpreter2::Thing::Variable(variable) if variable.mutable => variable,
+                _ => continue,
+            };
+            if !Linter::is_in(&self.mutated, variable.name.as_str()) {
+                self.report(
+                    LintId::UnmutatedVariable,
+                    format!(
+                        "variable `{}` is mutable but never mutated",
+                        variable.name.split_once('#').map_or("", |v| v.1)
+                    ),
+                    variable.line_number,
+                );
+            }
+        }
+    }
+}
@@ -22,6 +22,8 @@
 ///
 /// - `instructions`: a `Vec` of `ftd::interpreter2::Component`s that represents the instructions
 /// that the interpreter has processed.
+///
+/// - `kind_errors`: the kind errors found so far by [`interpret_to_check`], `None` otherwise.
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct InterpreterState {
     pub id: String,
@@ -30,6 +32,7 @@ pub struct InterpreterState {
     pub pending_imports: PendingImports,
     pub parsed_libs: ftd::Map<ParsedDocument>,
     pub instructions: Vec<ftd::interpreter2::Component>,
+    pub kind_errors: Option<Vec<ftd::interpreter2::KindError>>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -59,7 +62,7 @@ pub struct ToProcess {
 
 impl InterpreterState {
     /// The `new` function returns the new `InterpreterState` instance that it has created.
-    fn new(id: String) -> InterpreterState {
+    pub(crate) fn new(id: String) -> InterpreterState {
         InterpreterState {
             id,
             bag: ftd::interpreter2::default::default_bag(),
@@ -96,6 +99,7 @@ impl InterpreterState {
                     message: format!("Cannot find this document: `{}`", doc_name),
                     doc_id: doc_name.to_string(),
                     line_number,
+                    span: None,
                 })?;
         Ok(ftd::interpreter2::TDoc::new(
             &parsed_document.name,
@@ -104,6 +108,32 @@ impl InterpreterState {
         ))
     }
 
+    /// Starts interpreting `document`, the document `id` of the state, with the things and the
+    /// documents already in the state.
+    pub(crate) fn start(
+        mut self,
+        document: ParsedDocument,
+    ) -> ftd::interpreter2::Result<Interpreter> {
+        use itertools::Itertools;
+
+        let components = document
+            .ast
+            .iter()
+            .filter_map(|v| {
+                if v.is_component() {
+                    Some((0, v.to_owned()))
+                } else {
+                    None
+                }
+            })
+            .collect_vec();
+        self.parsed_libs.insert(self.id.to_string(), document);
+        self.to_process
+            .stack
+            .push((self.id.to_string(), components));
+        self.continue_processing()
+    }
+
     pub fn get_current_processing_module(&self) -> Option<String> {
         self.to_process.stack.last().map(|v| v.0.clone())
     }
@@ -121,6 +151,7 @@ impl InterpreterState {
     #[tracing::instrument(name = "continue_processing", skip_all)]
     pub fn continue_processing(mut self) -> ftd::interpreter2::Result<Interpreter> {
         while let Some((doc_name, number_of_scan, ast)) = self.get_next_ast() {
+            let frames = self.to_process.stack.len();
             if let Some(interpreter) = self.resolve_pending_imports::<ftd::interpreter2::Thing>()? {
                 match interpreter {
                     ftd::interpreter2::StateWithThing::State(s) => {
@@ -132,6 +163,10 @@ impl InterpreterState {
                     ftd::interpreter2::StateWithThing::Continue => continue,
                 }
             }
+            // the things the pending imports need were pushed over `ast`: resolve them first
+            if self.to_process.stack.len().ne(&frames) {
+                continue;
+            }
 
             self.increase_scan_count();
             let parsed_document = self.parsed_libs.get(doc_name.as_str()).unwrap();
@@ -296,6 +331,7 @@ impl InterpreterState {
                     .clone(),
                 tree: self.instructions,
                 name: self.id,
+                kind_errors: self.kind_errors.unwrap_or_default(),
             };
 
             Ok(Interpreter::Done { document })
@@ -464,6 +500,7 @@ impl InterpreterState {
                     .clone(),
                 tree: self.instructions,
                 name: self.id,
+                kind_errors: self.kind_errors.unwrap_or_default(),
             };
 
             Ok(Interpreter::Done { document })
@@ -525,6 +562,37 @@ impl InterpreterState {
         }
     }
 
+    /// The chain of things waiting for `doc_name#thing_name`, if it is already being resolved:
+    /// resolving it again would wait for itself, forever.
+    ///
+    /// A frame is pushed on `to_process.stack` for what the first ast of the frame below needs,
+    /// so the definitions first in their frame, and already scanned, form a chain of
+    /// dependencies. The returned chain starts and ends with `doc_name#thing_name`.
+    pub fn dependency_cycle(&self, doc_name: &str, thing_name: &str) -> Option<Vec<String>> {
+        use itertools::Itertools;
+
+        let in_progress = self
+            .to_process
+            .stack
+            .iter()
+            .filter_map(|(doc, asts)| match asts.first() {
+                Some((number_of_scan, ast))
+                    if number_of_scan.gt(&0)
+                        && !ast.is_component()
+                        && !ast.is_variable_invocation() =>
+                {
+                    Some(format!("{}#{}", doc, ast.name()))
+                }
+                _ => None,
+            })
+            .collect_vec();
+        let name = format!("{}#{}", doc_name, thing_name);
+        let start = in_progress.iter().position(|v| v.eq(&name))?;
+        let mut cycle = in_progress[start..].to_vec();
+        cycle.push(name);
+        Some(cycle)
+    }
+
     pub fn remove_last(&mut self) {
         let mut pop_last = false;
         if let Some((doc_name, asts)) = self.to_process.stack.last_mut() {
@@ -722,6 +790,20 @@ impl InterpreterState {
             StateWithThing::Continue => return self.continue_processing(),
         };
 
+        if !value.kind().is_same_as(&kind.kind) {
+            return Err(ftd::interpreter2::Error::InvalidKind {
+                doc_id: doc.name.to_string(),
+                line_number: variable_definition.line_number,
+                message: format!(
+                    "processor returned `{}` for `{}`, expected `{}`",
+                    value.kind().name(),
+                    variable_definition.name,
+                    kind.kind.name()
+                ),
+                span: None,
+            });
+        }
+
         let value =
             value.into_property_value(variable_definition.mutable, variable_definition.line_number);
 
@@ -780,36 +862,30 @@ pub fn interpret<'a>(id: &'a str, source: &'a str) -> ftd::interpreter2::Result<
     interpret_with_line_number(id, doc, 0)
 }
 
+/// Same as [`interpret`], except that a value of the wrong kind does not stop the
+/// interpretation: the error is kept in [`Document::kind_errors`], so that
+/// [`ftd::interpreter2::check`] reports all of them.
+pub fn interpret_to_check<'a>(
+    id: &'a str,
+    source: &'a str,
+) -> ftd::interpreter2::Result<Interpreter> {
+    let doc = ParsedDocument::parse_with_line_number(id, source, 0)?;
+    InterpreterState {
+        kind_errors: Some(vec![]),
+        ..InterpreterState::new(id.to_string())
+    }
+    .start(doc)
+}
+
 #[tracing::instrument(skip_all)]
 pub fn interpret_with_line_number<'a>(
     id: &'a str,
     document: ParsedDocument,
     _line_number: usize,
 ) -> ftd::interpreter2::Result<Interpreter> {
-    use itertools::Itertools;
-
     tracing::info!(msg = "ftd: interpreting", doc = id);
 
-    let mut s = InterpreterState::new(id.to_string());
-    s.parsed_libs.insert(id.to_string(), document);
-    s.to_process.stack.push((
-        id.to_string(),
-        s.parsed_libs
-            .get(id)
-            .unwrap()
-            .ast
-            .iter()
-            .filter_map(|v| {
-                if v.is_component() {
-                    Some((0, v.to_owned()))
-                } else {
-                    None
-                }
-            })
-            .collect_vec(),
-    ));
-
-    s.continue_processing()
+    InterpreterState::new(id.to_string()).start(document)
 }
 
 #[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
@@ -834,10 +910,19 @@ impl ParsedDocument {
         source: &str,
         line_number: usize,
     ) -> ftd::interpreter2::Result<ParsedDocument> {
-        let ast = ftd::ast::AST::from_sections(
-            ftd::p11::parse_with_line_number(source, id, line_number)?.as_slice(),
+        ParsedDocument::from_sections(
             id,
-        )?;
+            ftd::p11::parse_with_line_number(source, id, line_number)?.as_slice(),
+        )
+    }
+
+    /// Builds the document out of already parsed sections, e.g. the ones returned by
+    /// [`ftd::p11::parse_with_recovery`]. Broken sections are skipped.
+    pub fn from_sections(
+        id: &str,
+        sections: &[ftd::p11::Section],
+    ) -> ftd::interpreter2::Result<ParsedDocument> {
+        let ast = ftd::ast::AST::from_sections(sections, id)?;
         let doc_aliases = {
             let mut doc_aliases = ftd::interpreter2::default::default_aliases();
             for ast in ast.iter().filter(|v| v.is_import()) {
@@ -993,6 +1078,59 @@ pub struct Document {
     pub name: String,
     pub tree: Vec<ftd::interpreter2::Component>,
     pub aliases: ftd::Map<String>,
+    /// The kind errors found by [`interpret_to_check`], always empty otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kind_errors: Vec<ftd::interpreter2::KindError>,
+}
+
+impl Document {
+    /// Version of the format of [`Document::to_bytes`]. It changes whenever the serialized form
+    /// of the document or of its things does, so that stale artifacts are rejected.
+    pub const FORMAT_VERSION: u32 = 1;
+
+    /// Header of [`Document::to_bytes`], followed by the format version and a newline.
+    const FORMAT_HEADER: &'static str = "ftd-document/";
+
+    /**
+     * Serializes the interpreted document, e.g. to store a library module once it is
+     * interpreted. The bytes start with a `ftd-document/<FORMAT_VERSION>` line, followed by the
+     * document in JSON.
+     */
+    pub fn to_bytes(&self) -> ftd::interpreter2::Result<Vec<u8>> {
+        let mut bytes =
+            format!("{}{}\n", Document::FORMAT_HEADER, Document::FORMAT_VERSION).into_bytes();
+        serde_json::to_writer(&mut bytes, self)?;
+        Ok(bytes)
+    }
+
+    /// Reads a document written by [`Document::to_bytes`], of the current format version.
+    pub fn from_bytes(bytes: &[u8]) -> ftd::interpreter2::Result<Document> {
+        let (header, body) = match bytes.iter().position(|v| *v == b'\n') {
+            Some(position) => (&bytes[..position], &bytes[position + 1..]),
+            None => (bytes, &[][..]),
+        };
+        let version = match std::str::from_utf8(header)
+            .ok()
+            .and_then(|v| v.strip_prefix(Document::FORMAT_HEADER))
+        {
+            Some(version) => version,
+            None => {
+                return Err(ftd::interpreter2::Error::InvalidDocumentBytes {
+                    message: "not a serialized ftd document".to_string(),
+                })
+            }
+        };
+        if version.ne(Document::FORMAT_VERSION.to_string().as_str()) {
+            return Err(ftd::interpreter2::Error::InvalidDocumentBytes {
+                message: format!(
+                    "document has format version `{}`, expected `{}`",
+                    version,
+                    Document::FORMAT_VERSION
+                ),
+            });
+        }
+        Ok(serde_json::from_slice(body)?)
+    }
 }
 
 #[derive(Debug)]
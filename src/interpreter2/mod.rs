@@ -30,11 +30,18 @@ macro_rules! try_state {
 
 #[cfg(test)]
 #[macro_use]
-mod test;
+pub(crate) mod test;
+#[cfg(feature = "async")]
+mod async_host;
+mod cache;
+mod check;
 mod constants;
+mod host;
+mod lint;
 mod main;
 mod main2;
 pub mod prelude;
+mod processor;
 mod tdoc;
 mod things;
 pub mod utils;
@@ -58,6 +65,7 @@ pub enum Error {
         doc_id: String,
         line_number: usize,
         message: String,
+        span: Option<ftd::p11::Span>,
     },
 
     #[error("ValueNotFound: {doc_id}:{line_number} -> {message}")]
@@ -65,6 +73,7 @@ pub enum Error {
         doc_id: String,
         line_number: usize,
         message: String,
+        span: Option<ftd::p11::Span>,
     },
 
     #[error("ParseIntError: {}", _0)]
@@ -81,11 +90,15 @@ pub enum Error {
         message: String,
         doc_id: String,
         line_number: usize,
+        span: Option<ftd::p11::Span>,
     },
 
     #[error("EvalexprError: {}", _0)]
     EvalexprError(#[from] ftd::evalexpr::EvalexprError),
 
+    #[error("InvalidDocumentBytes: {message}")]
+    InvalidDocumentBytes { message: String },
+
     #[error("serde error: {source}")]
     Serde {
         #[from]
@@ -93,4 +106,32 @@ pub enum Error {
     },
 }
 
+impl Error {
+    /// Points the error at `span` if it is reported for the line `span` is on and does not
+    /// already point somewhere more precise.
+    pub(crate) fn with_span(mut self, span: ftd::p11::Span) -> Error {
+        match &mut self {
+            Error::InvalidKind {
+                line_number,
+                span: error_span,
+                ..
+            }
+            | Error::ValueNotFound {
+                line_number,
+                span: error_span,
+                ..
+            }
+            | Error::ParseError {
+                line_number,
+                span: error_span,
+                ..
+            } if error_span.is_none() && !span.is_empty() && *line_number == span.line => {
+                *error_span = Some(span)
+            }
+            _ => {}
+        }
+        self
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
@@ -1,8 +1,17 @@
+#[cfg(feature = "async")]
+pub use ftd::interpreter2::async_host::{continue_async, interpret_async, AsyncHost, BoxFuture};
+pub use ftd::interpreter2::cache::{interpret_with_cache, Cache};
+pub use ftd::interpreter2::check::{check, KindError};
 pub use ftd::interpreter2::constants::*;
+pub use ftd::interpreter2::host::{
+    continue_with_host, interpret_with_host, Directory, Host, Module,
+};
+pub use ftd::interpreter2::lint::{lint, Lint, LintId, ALLOW_COMMENT};
 pub use ftd::interpreter2::main2::{
-    interpret, interpret_with_line_number, Document, Interpreter, InterpreterState,
-    InterpreterWithoutState, ParsedDocument, StateWithThing, ToProcess,
+    interpret, interpret_to_check, interpret_with_line_number, Document, Interpreter,
+    InterpreterState, InterpreterWithoutState, ParsedDocument, StateWithThing, ToProcess,
 };
+pub use ftd::interpreter2::processor::{Processor, ProcessorInput, ProcessorRegistry};
 
 pub use ftd::interpreter2::things::{
     component::{
@@ -0,0 +1,352 @@
+/**
+ * Processors implemented by the host, by full name, `lib#read-package` below, see
+ * [`ftd::interpreter2::Host::processors`]. A foreign function of another module with the same
+ * name is still given to [`ftd::interpreter2::Host::process`].
+ *
+ * A processor declares the kind of value it returns, and the headers of the section it takes
+ * as arguments, with their kinds:
+ *
+ * ```ftd
+ * -- import: lib
+ *
+ * -- string list dependencies:
+ * $processor$: lib.read-package
+ * file: Cargo.toml
+ * ```
+ *
+ * The interpreter resolves the arguments before calling the processor, and checks the kind of
+ * the variable against the declared one. The module of the processor, `lib` above, has to list
+ * it in its [`ftd::interpreter2::Module::foreign_functions`], see [`ProcessorRegistry::names`].
+ */
+#[derive(Default)]
+pub struct ProcessorRegistry {
+    processors: ftd::Map<Processor>,
+}
+
+/**
+ * A processor of a [`ProcessorRegistry`].
+ *
+ * # Fields
+ *
+ * - `kind`: kind of the returned value. A record kind with an empty name stands for any record,
+ *   the processor reading the actual one from [`ProcessorInput::kind`]
+ * - `arguments`: name and kind of the headers the processor takes. An optional argument may be
+ *   left out by the section
+ * - `function`: the processor
+ */
+pub struct Processor {
+    pub kind: ftd::interpreter2::Kind,
+    pub arguments: Vec<(String, ftd::interpreter2::Kind)>,
+    #[allow(clippy::type_complexity)]
+    pub function: Box<
+        dyn Fn(&ProcessorInput) -> ftd::interpreter2::Result<ftd::interpreter2::Value>
+            + Send
+            + Sync,
+    >,
+}
+
+/**
+ * What a [`Processor`] is called with.
+ *
+ * # Fields
+ *
+ * - `arguments`: value of the arguments given by the section, by name
+ * - `kind`: kind of the variable the processor gives the value of
+ * - `line_number`: line of the variable
+ * - `doc`: document of the variable
+ */
+pub struct ProcessorInput<'a, 'b> {
+    pub arguments: ftd::Map<ftd::interpreter2::Value>,
+    pub kind: ftd::interpreter2::KindData,
+    pub line_number: usize,
+    pub doc: &'b ftd::interpreter2::TDoc<'a>,
+}
+
+impl ProcessorRegistry {
+    pub fn new() -> ProcessorRegistry {
+        Default::default()
+    }
+
+    /// Registry of the built-in processors, `read-package` and `read-records` of `module`.
+    pub fn builtins(module: &str) -> ProcessorRegistry {
+        let mut registry = ProcessorRegistry::new();
+        registry.register(
+            format!("{}#read-package", module).as_str(),
+            ftd::interpreter2::Kind::string().into_list(),
+            vec![(
+                "file".to_string(),
+                ftd::interpreter2::Kind::string().into_optional(),
+            )],
+            read_package,
+        );
+        registry.register(
+            format!("{}#read-records", module).as_str(),
+            ftd::interpreter2::Kind::record("").into_list(),
+            vec![(
+                "file".to_string(),
+                ftd::interpreter2::Kind::string().into_optional(),
+            )],
+            read_records,
+        );
+        registry
+    }
+
+    /// Adds processor `name`, e.g. `lib#read-package`, replacing the one of the same name if
+    /// any.
+    pub fn register<F>(
+        &mut self,
+        name: &str,
+        kind: ftd::interpreter2::Kind,
+        arguments: Vec<(String, ftd::interpreter2::Kind)>,
+        function: F,
+    ) where
+        F: Fn(&ProcessorInput) -> ftd::interpreter2::Result<ftd::interpreter2::Value>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.processors.insert(
+            name.to_string(),
+            Processor {
+                kind,
+                arguments,
+                function: Box::new(function),
+            },
+        );
+    }
+
+    /// If `processor` of `module` is registered.
+    pub fn contains(&self, module: &str, processor: &str) -> bool {
+        self.processors
+            .contains_key(format!("{}#{}", module, processor).as_str())
+    }
+
+    /// Names of the processors of `module`, without the module, to be listed as its foreign
+    /// functions.
+    pub fn names(&self, module: &str) -> Vec<String> {
+        let prefix = format!("{}#", module);
+        self.processors
+            .keys()
+            .filter_map(|v| v.strip_prefix(prefix.as_str()))
+            .map(|v| v.to_string())
+            .collect()
+    }
+
+    /// Value of the variable defined by `ast` in `caller_module`, which uses `processor` of
+    /// `module`. The interpreter may have to resolve the kinds of the variable and of the
+    /// arguments first, see [`ftd::interpreter2::StateWithThing`].
+    pub fn process(
+        &self,
+        module: &str,
+        processor: &str,
+        ast: &ftd::ast::AST,
+        caller_module: &str,
+        state: &mut ftd::interpreter2::InterpreterState,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::StateWithThing<ftd::interpreter2::Value>>
+    {
+        let (name, aliases) = match state.parsed_libs.get(caller_module) {
+            Some(document) => (document.name.to_string(), document.doc_aliases.clone()),
+            None => {
+                return ftd::interpreter2::utils::e2(
+                    format!("Cannot find module `{}`", caller_module),
+                    caller_module,
+                    ast.line_number(),
+                )
+            }
+        };
+        let mut doc = ftd::interpreter2::TDoc::new_state(&name, &aliases, state);
+        let variable_definition = ast.clone().get_variable_definition(doc.name)?;
+        let line_number = variable_definition.line_number;
+        let definition = match self
+            .processors
+            .get(format!("{}#{}", module, processor).as_str())
+        {
+            Some(definition) => definition,
+            None => {
+                return ftd::interpreter2::utils::e2(
+                    format!("processor `{}` is not supported", processor),
+                    doc.name,
+                    line_number,
+                )
+            }
+        };
+
+        let kind = try_ok_state!(ftd::interpreter2::KindData::from_ast_kind(
+            variable_definition.kind,
+            &Default::default(),
+            &mut doc,
+            line_number,
+        )?);
+        if !returns(&definition.kind, &kind.kind) {
+            return Err(ftd::interpreter2::Error::InvalidKind {
+                doc_id: doc.name.to_string(),
+                line_number,
+                message: format!(
+                    "processor `{}` returns `{}`, found `{}`",
+                    processor,
+                    definition.kind.name(),
+                    kind.kind.name()
+                ),
+                span: None,
+            });
+        }
+
+        let headers = match variable_definition.value {
+            ftd::ast::VariableValue::Record { headers, .. } => headers,
+            _ => ftd::ast::HeaderValues(vec![]),
+        };
+        let mut arguments: ftd::Map<ftd::interpreter2::Value> = Default::default();
+        for (argument, argument_kind) in definition.arguments.iter() {
+            let header = match headers.get_by_key_optional(argument, doc.name, line_number)? {
+                Some(header) => header,
+                None if argument_kind.is_optional() => continue,
+                None => {
+                    return ftd::interpreter2::utils::e2(
+                        format!("processor `{}` expects argument `{}`", processor, argument),
+                        doc.name,
+                        line_number,
+                    )
+                }
+            };
+            let value = try_ok_state!(ftd::interpreter2::PropertyValue::from_ast_value(
+                header.value.clone(),
+                &mut doc,
+                false,
+                Some(&argument_kind.clone().into_kind_data()),
+            )?);
+            arguments.insert(
+                argument.to_string(),
+                value.resolve(&doc, header.line_number)?,
+            );
+        }
+
+        Ok(ftd::interpreter2::StateWithThing::new_thing((definition
+            .function)(
+            &ProcessorInput {
+                arguments,
+                kind,
+                line_number,
+                doc: &doc,
+            },
+        )?))
+    }
+}
+
+impl std::fmt::Debug for ProcessorRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.processors.keys()).finish()
+    }
+}
+
+impl<'a, 'b> ProcessorInput<'a, 'b> {
+    /// Value of string argument `name`, `None` if it is left out.
+    pub fn string(&self, name: &str) -> Option<&str> {
+        match self.arguments.get(name) {
+            Some(ftd::interpreter2::Value::String { text }) => Some(text.as_str()),
+            Some(ftd::interpreter2::Value::Optional { data, .. }) => match data.as_ref() {
+                Some(ftd::interpreter2::Value::String { text }) => Some(text.as_str()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn error<T>(&self, message: String) -> ftd::interpreter2::Result<T> {
+        ftd::interpreter2::utils::e2(message, self.doc.name, self.line_number)
+    }
+}
+
+/// If a processor declared to return `result` can give the value of a variable of kind `kind`.
+fn returns(result: &ftd::interpreter2::Kind, kind: &ftd::interpreter2::Kind) -> bool {
+    match (result, kind) {
+        (ftd::interpreter2::Kind::Record { name }, ftd::interpreter2::Kind::Record { .. })
+            if name.is_empty() =>
+        {
+            true
+        }
+        (
+            ftd::interpreter2::Kind::List { kind: k1 },
+            ftd::interpreter2::Kind::List { kind: k2 },
+        ) => returns(k1, k2),
+        (_, ftd::interpreter2::Kind::Optional { kind }) => returns(result, kind),
+        _ => result.is_same_as(kind),
+    }
+}
+
+/// `key = value` lines of the first block of file `file` of `input`, `Cargo.toml` by default.
+fn read_key_values(input: &ProcessorInput) -> ftd::interpreter2::Result<Vec<(String, String)>> {
+    let file = input.string("file").unwrap_or("Cargo.toml");
+    let content = match std::fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) => return input.error(format!("Cannot read `{}`: {}", file, e)),
+    };
+    Ok(content
+        .lines()
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect())
+}
+
+/// The values of the `key = value` lines, see `ftd::p2::TestLibrary`.
+fn read_package(input: &ProcessorInput) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+    Ok(ftd::interpreter2::Value::List {
+        data: read_key_values(input)?
+            .into_iter()
+            .map(|(_, value)| {
+                ftd::interpreter2::Value::String { text: value }
+                    .into_property_value(false, input.line_number)
+            })
+            .collect(),
+        kind: ftd::interpreter2::Kind::string().into_kind_data(),
+    })
+}
+
+/// A record per `key = value` line, its string fields taking the key and the value in order,
+/// see `ftd::p2::TestLibrary`.
+fn read_records(input: &ProcessorInput) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+    let name = match input
+        .kind
+        .kind
+        .ref_inner()
+        .ref_inner_list()
+        .get_record_name()
+    {
+        Some(name) => name,
+        None => return input.error("read-records expects a record list".to_string()),
+    };
+    let record = input.doc.get_record(name, input.line_number)?;
+    if record.fields.len() != 2 || record.fields.iter().any(|field| !field.kind.is_string()) {
+        return input.error(format!(
+            "`{}` should have two string fields, for the key and the value",
+            record.name
+        ));
+    }
+
+    let mut data = vec![];
+    for (key, value) in read_key_values(input)? {
+        let fields = record
+            .fields
+            .iter()
+            .zip([key, value])
+            .map(|(field, text)| {
+                (
+                    field.name.to_string(),
+                    ftd::interpreter2::Value::String { text }
+                        .into_property_value(false, input.line_number),
+                )
+            })
+            .collect();
+        data.push(
+            ftd::interpreter2::Value::Record {
+                name: record.name.to_string(),
+                fields,
+            }
+            .into_property_value(false, input.line_number),
+        );
+    }
+    Ok(ftd::interpreter2::Value::List {
+        data,
+        kind: ftd::interpreter2::Kind::record(record.name.as_str()).into_kind_data(),
+    })
+}
@@ -43,6 +43,28 @@ impl<'a> TDoc<'a> {
         }
     }
 
+    /**
+     * Reports a value of kind `found` passed where `expected` is expected. It is an error,
+     * unless the document is interpreted to be checked, see
+     * [`ftd::interpreter2::interpret_to_check`]: then the error is kept for
+     * [`ftd::interpreter2::check`] and the interpretation goes on.
+     */
+    pub(crate) fn kind_mismatch(
+        &mut self,
+        expected: &ftd::interpreter2::Kind,
+        found: &ftd::interpreter2::Kind,
+        line_number: usize,
+    ) -> ftd::interpreter2::Result<()> {
+        let error = ftd::interpreter2::KindError::mismatch(expected, found, self.name, line_number);
+        if let BagOrState::State(state) = &mut self.bag {
+            if let Some(kind_errors) = state.kind_errors.as_mut() {
+                kind_errors.push(error);
+                return Ok(());
+            }
+        }
+        Err(error.into())
+    }
+
     pub fn resolve_name(&self, name: &str) -> String {
         ftd::interpreter2::utils::resolve_name(name, self.name, self.aliases)
     }
@@ -229,6 +251,7 @@ impl<'a> TDoc<'a> {
                             message: format!("Can't find field `{}` in record `{}`", p1, rec_name),
                             doc_id: doc.name.to_string(),
                             line_number,
+                            span: None,
                         })?
                         .clone()
                         .resolve(doc, line_number)?;
@@ -248,6 +271,7 @@ impl<'a> TDoc<'a> {
                             ),
                             doc_id: doc.name.to_string(),
                             line_number,
+                            span: None,
                         })?
                         .clone()
                         .resolve(doc, line_number)?;
@@ -334,6 +358,7 @@ impl<'a> TDoc<'a> {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number,
+                        span: None,
                     },
                 )?;
 
@@ -351,6 +376,7 @@ impl<'a> TDoc<'a> {
                                 ),
                                 doc_id: doc.name.to_string(),
                                 line_number,
+                                span: None,
                             })?
                             .to_owned();
                         if let Some(variable) =
@@ -407,6 +433,7 @@ impl<'a> TDoc<'a> {
                                     ),
                                     doc_id: doc.name.to_string(),
                                     line_number,
+                                    span: None,
                                 },
                             )?;
                             change_value(field, set, p2, doc, line_number)?;
@@ -458,6 +485,7 @@ impl<'a> TDoc<'a> {
                                 ),
                                 doc_id: doc.name.to_string(),
                                 line_number,
+                                span: None,
                             })?;
                         *value = ftd::interpreter2::PropertyValue::Value {
                             value: resolved_value,
@@ -1289,6 +1317,17 @@ impl<'a> TDoc<'a> {
                 .map(|v| (0, v.to_owned()))
                 .collect_vec();
             if !current_doc_contains_thing.is_empty() {
+                if let Some(cycle) = current_doc_contains_thing
+                    .iter()
+                    .find_map(|(_, v)| state.dependency_cycle(state.id.as_str(), &v.name()))
+                {
+                    return ftd::interpreter2::utils::e2(
+                        format!("Cyclic dependency: {}", cycle.join(" -> ")),
+                        self.name,
+                        line_number,
+                    );
+                }
+
                 state
                     .to_process
                     .stack
@@ -1342,6 +1381,14 @@ impl<'a> TDoc<'a> {
                 return self.err("not found", name, "search_thing", line_number);
             }
 
+            if let Some(cycle) = state.dependency_cycle(doc_name.as_str(), thing_name.as_str()) {
+                return ftd::interpreter2::utils::e2(
+                    format!("Cyclic dependency: {}", cycle.join(" -> ")),
+                    self.name,
+                    line_number,
+                );
+            }
+
             state
                 .to_process
                 .stack
@@ -1482,6 +1529,7 @@ impl<'a> TDoc<'a> {
                             message: format!("Can't parse to string, found: {:?}", row),
                             doc_id: self.name.to_string(),
                             line_number,
+                            span: None,
                         })?,
                 }
             }
@@ -1492,6 +1540,7 @@ impl<'a> TDoc<'a> {
                             message: format!("Can't parse to integer, found: {:?}", row),
                             doc_id: self.name.to_string(),
                             line_number,
+                            span: None,
                         },
                     )?,
                 }
@@ -1503,6 +1552,7 @@ impl<'a> TDoc<'a> {
                             message: format!("Can't parse to decimal, found: {:?}", row),
                             doc_id: self.name.to_string(),
                             line_number,
+                            span: None,
                         },
                     )?,
                 }
@@ -1514,6 +1564,7 @@ impl<'a> TDoc<'a> {
                             message: format!("Can't parse to boolean,found: {:?}", row),
                             doc_id: self.name.to_string(),
                             line_number,
+                            span: None,
                         })?,
                 }
             }
@@ -1540,6 +1591,7 @@ impl<'a> TDoc<'a> {
                 message: format!("Can't serialize to json: {:?}, found: {:?}", e, json),
                 doc_id: self.name.to_string(),
                 line_number,
+                span: None,
             })?;
 
         self.as_json_(line_number, &json, kind.to_owned())
@@ -1558,6 +1610,7 @@ impl<'a> TDoc<'a> {
                         message: format!("Can't parse to string, found: {}", json),
                         doc_id: self.name.to_string(),
                         line_number,
+                        span: None,
                     }
                 })?,
             },
@@ -1567,6 +1620,7 @@ impl<'a> TDoc<'a> {
                         message: format!("Can't parse to integer, found: {}", json),
                         doc_id: self.name.to_string(),
                         line_number,
+                        span: None,
                     }
                 })?,
             },
@@ -1576,6 +1630,7 @@ impl<'a> TDoc<'a> {
                         message: format!("Can't parse to decimal, found: {}", json),
                         doc_id: self.name.to_string(),
                         line_number,
+                        span: None,
                     }
                 })?,
             },
@@ -1585,6 +1640,7 @@ impl<'a> TDoc<'a> {
                         message: format!("Can't parse to boolean,found: {}", json),
                         doc_id: self.name.to_string(),
                         line_number,
+                        span: None,
                     }
                 })?,
             },
@@ -1,73 +1,61 @@
 use pretty_assertions::assert_eq; // macro
 
+/// Host of the tests: modules are empty, except that module `test` has the foreign variable
+/// `var` and the foreign function `fn`. Processors return their caption, or their name, in
+/// uppercase.
+pub(crate) struct TestHost;
+
+impl ftd::interpreter2::Host for TestHost {
+    fn load_module(
+        &mut self,
+        module: &str,
+        _caller_module: &str,
+    ) -> Option<ftd::interpreter2::Module> {
+        let mut loaded = ftd::interpreter2::Module::default();
+        if module.eq("test") {
+            loaded.foreign_variables.push("var".to_string());
+            loaded.foreign_functions.push("fn".to_string());
+        }
+        Some(loaded)
+    }
+
+    fn process(
+        &mut self,
+        processor: &str,
+        ast: &ftd::ast::AST,
+        module: &str,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+        let variable_definition = ast.clone().get_variable_definition(module)?;
+        Ok(ftd::interpreter2::Value::String {
+            text: variable_definition
+                .value
+                .caption()
+                .unwrap_or_else(|| processor.to_string())
+                .to_uppercase(),
+        })
+    }
+
+    fn foreign_variable(
+        &mut self,
+        module: &str,
+        variable: &str,
+        _caller_module: &str,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+        if module.eq("test") {
+            Ok(ftd::interpreter2::Value::String {
+                text: variable.to_uppercase(),
+            })
+        } else {
+            ftd::interpreter2::utils::e2(format!("Unknown module {}", module), module, 0)
+        }
+    }
+}
+
 pub fn interpret_helper(
     name: &str,
     source: &str,
 ) -> ftd::interpreter2::Result<ftd::interpreter2::Document> {
-    let mut s = ftd::interpreter2::interpret(name, source)?;
-    let document;
-    loop {
-        match s {
-            ftd::interpreter2::Interpreter::Done { document: doc } => {
-                document = doc;
-                break;
-            }
-            ftd::interpreter2::Interpreter::StuckOnImport {
-                module, state: st, ..
-            } => {
-                let source = "";
-                let mut foreign_variable = vec![];
-                let mut foreign_function = vec![];
-                if module.eq("test") {
-                    foreign_variable.push("var".to_string());
-                    foreign_function.push("fn".to_string());
-                }
-                let document = ftd::interpreter2::ParsedDocument::parse(module.as_str(), source)?;
-                s = st.continue_after_import(
-                    module.as_str(),
-                    document,
-                    foreign_variable,
-                    foreign_function,
-                    0,
-                )?;
-            }
-            ftd::interpreter2::Interpreter::StuckOnProcessor {
-                state, ast, module, ..
-            } => {
-                let variable_definition = ast.get_variable_definition(module.as_str())?;
-                let processor = variable_definition.processor.unwrap();
-                let value = ftd::interpreter2::Value::String {
-                    text: variable_definition
-                        .value
-                        .caption()
-                        .unwrap_or(processor)
-                        .to_uppercase()
-                        .to_string(),
-                };
-                s = state.continue_after_processor(value)?;
-            }
-            ftd::interpreter2::Interpreter::StuckOnForeignVariable {
-                state,
-                module,
-                variable,
-                ..
-            } => {
-                if module.eq("test") {
-                    let value = ftd::interpreter2::Value::String {
-                        text: variable.to_uppercase().to_string(),
-                    };
-                    s = state.continue_after_variable(module.as_str(), variable.as_str(), value)?;
-                } else {
-                    return ftd::interpreter2::utils::e2(
-                        format!("Unknown module {}", module),
-                        module.as_str(),
-                        0,
-                    );
-                }
-            }
-        }
-    }
-    Ok(document)
+    ftd::interpreter2::interpret_with_host(name, source, &mut TestHost)
 }
 
 #[track_caller]
@@ -272,3 +260,1050 @@ fn evalexpr_test() {
         }
             */
 }
+
+#[test]
+fn error_span() {
+    let source = "-- ftd.text: hello\ncolour: red\n";
+    match interpret_helper("foo", source) {
+        Err(ftd::interpreter2::Error::ParseError {
+            line_number, span, ..
+        }) => {
+            assert_eq!(line_number, 2);
+            assert_eq!(span, Some(ftd::p11::Span::new(19, 2, 1, 6)));
+        }
+        t => panic!("expected parse error, found: {:?}", t.map(|_| ())),
+    }
+}
+
+#[test]
+fn interpret_with_host() {
+    let mut modules: std::collections::HashMap<String, String> = Default::default();
+    modules.insert("lib".to_string(), "-- integer y: 10\n".to_string());
+
+    let document = ftd::interpreter2::interpret_with_host(
+        "foo",
+        "-- import: lib\n\n-- ftd.integer: $lib.y\n",
+        &mut modules,
+    )
+    .unwrap();
+    assert_eq!(document.tree.len(), 1);
+    assert!(document.data.contains_key("lib#y"));
+
+    match ftd::interpreter2::interpret_with_host(
+        "foo",
+        "-- import: missing\n\n-- ftd.integer: $missing.y\n",
+        &mut modules,
+    ) {
+        Err(ftd::interpreter2::Error::ParseError {
+            message,
+            doc_id,
+            line_number,
+            ..
+        }) => {
+            assert_eq!(message, "Cannot find module `missing`");
+            assert_eq!(doc_id, "foo");
+            assert_eq!(line_number, 1);
+        }
+        r => panic!("expected a missing module error, got {:?}", r),
+    }
+}
+
+#[test]
+fn cyclic_dependency() {
+    let mut modules: std::collections::HashMap<String, String> = Default::default();
+    modules.insert(
+        "lib".to_string(),
+        "-- import: foo\n\n-- integer y: $foo.x\n".to_string(),
+    );
+
+    let message = |source: &str, modules: &mut std::collections::HashMap<String, String>| {
+        match ftd::interpreter2::interpret_with_host("foo", source, modules) {
+            Err(ftd::interpreter2::Error::ParseError { message, .. }) => message,
+            r => panic!("expected a cyclic dependency error, got {:?}", r),
+        }
+    };
+    assert_eq!(
+        message(
+            "-- import: lib\n\n-- integer x: $lib.y\n\n-- ftd.integer: $x\n",
+            &mut modules
+        ),
+        "Cyclic dependency: foo#x -> lib#y -> foo#x"
+    );
+    assert_eq!(
+        message("-- integer x: $x\n\n-- ftd.integer: $x\n", &mut modules),
+        "Cyclic dependency: foo#x -> foo#x"
+    );
+    assert_eq!(
+        message(
+            "-- integer x: $y\n\n-- integer y: $z\n\n-- integer z: $x\n\n-- ftd.integer: $x\n",
+            &mut modules
+        ),
+        "Cyclic dependency: foo#x -> foo#y -> foo#z -> foo#x"
+    );
+}
+
+/// Host whose module `lib` provides the processors of a registry, and whose module `other` has
+/// a foreign function `add` the host does not implement and a foreign variable `file`.
+struct RegistryHost(ftd::interpreter2::ProcessorRegistry);
+
+impl ftd::interpreter2::Host for RegistryHost {
+    fn load_module(
+        &mut self,
+        module: &str,
+        _caller_module: &str,
+    ) -> Option<ftd::interpreter2::Module> {
+        match module {
+            "lib" | "other" => Some(ftd::interpreter2::Module {
+                foreign_functions: if module.eq("lib") {
+                    self.0.names(module)
+                } else {
+                    vec!["add".to_string()]
+                },
+                foreign_variables: if module.eq("other") {
+                    vec!["file".to_string()]
+                } else {
+                    vec![]
+                },
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+
+    fn foreign_variable(
+        &mut self,
+        _module: &str,
+        _variable: &str,
+        _caller_module: &str,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+        Ok(ftd::interpreter2::Value::String {
+            text: "Cargo.toml".to_string(),
+        })
+    }
+
+    fn processors(&self) -> Option<&ftd::interpreter2::ProcessorRegistry> {
+        Some(&self.0)
+    }
+}
+
+/// Value of variable `name` of `document`.
+fn variable_value(document: &ftd::interpreter2::Document, name: &str) -> ftd::interpreter2::Value {
+    match document.data.get(name) {
+        Some(ftd::interpreter2::Thing::Variable(ftd::interpreter2::Variable {
+            value: ftd::interpreter2::PropertyValue::Value { value, .. },
+            ..
+        })) => value.clone(),
+        t => panic!("expected a variable with a value, got {:?}", t),
+    }
+}
+
+#[test]
+fn processor_registry() {
+    let mut registry = ftd::interpreter2::ProcessorRegistry::builtins("lib");
+    registry.register(
+        "lib#add",
+        ftd::interpreter2::Kind::integer(),
+        vec![
+            ("a".to_string(), ftd::interpreter2::Kind::integer()),
+            ("b".to_string(), ftd::interpreter2::Kind::integer()),
+        ],
+        |input| match (input.arguments.get("a"), input.arguments.get("b")) {
+            (
+                Some(ftd::interpreter2::Value::Integer { value: a }),
+                Some(ftd::interpreter2::Value::Integer { value: b }),
+            ) => Ok(ftd::interpreter2::Value::Integer { value: a + b }),
+            _ => unreachable!(),
+        },
+    );
+    let mut host = RegistryHost(registry);
+
+    let document = ftd::interpreter2::interpret_with_host(
+        "foo",
+        indoc::indoc!(
+            "
+            -- import: lib
+
+            -- record package-data:
+            string name:
+            string value:
+
+            -- string list package:
+            $processor$: lib.read-package
+
+            -- package-data list records:
+            $processor$: lib.read-records
+            file: Cargo.toml
+
+            -- integer sum:
+            $processor$: lib.add
+            a: 1
+            b: 2
+
+            -- ftd.text: $name
+            $loop$: $package as $name
+
+            -- ftd.text: $record.name
+            $loop$: $records as $record
+
+            -- ftd.integer: $sum
+            "
+        ),
+        &mut host,
+    )
+    .unwrap();
+    match variable_value(&document, "foo#package") {
+        ftd::interpreter2::Value::List { data, .. } => assert_eq!(
+            data.first(),
+            Some(&ftd::interpreter2::PropertyValue::Value {
+                value: ftd::interpreter2::Value::String {
+                    text: "\"ftd\"".to_string()
+                },
+                is_mutable: false,
+                line_number: 7,
+            })
+        ),
+        v => panic!("expected a list, got {:?}", v),
+    }
+    match variable_value(&document, "foo#records") {
+        ftd::interpreter2::Value::List { data, kind } => {
+            assert_eq!(
+                kind.kind,
+                ftd::interpreter2::Kind::record("foo#package-data")
+            );
+            match data.first() {
+                Some(ftd::interpreter2::PropertyValue::Value {
+                    value: ftd::interpreter2::Value::Record { name, fields },
+                    ..
+                }) => {
+                    assert_eq!(name, "foo#package-data");
+                    assert_eq!(fields.len(), 2);
+                    assert_eq!(
+                        fields.get("name"),
+                        Some(&ftd::interpreter2::PropertyValue::Value {
+                            value: ftd::interpreter2::Value::String {
+                                text: "name".to_string()
+                            },
+                            is_mutable: false,
+                            line_number: 10,
+                        })
+                    );
+                }
+                v => panic!("expected a record, got {:?}", v),
+            }
+        }
+        v => panic!("expected a list, got {:?}", v),
+    }
+    assert_eq!(
+        variable_value(&document, "foo#sum"),
+        ftd::interpreter2::Value::Integer { value: 3 }
+    );
+
+    let error = |source: &str, host: &mut RegistryHost| match ftd::interpreter2::interpret_with_host(
+        "foo", source, host,
+    ) {
+        Err(ftd::interpreter2::Error::InvalidKind { message, .. })
+        | Err(ftd::interpreter2::Error::ParseError { message, .. }) => message,
+        r => panic!("expected an error, got {:?}", r),
+    };
+    assert_eq!(
+        error(
+            "-- import: lib\n\n-- integer sum:\n$processor$: lib.add\na: 1\n\n-- ftd.integer: $sum\n",
+            &mut host
+        ),
+        "processor `add` expects argument `b`"
+    );
+    assert_eq!(
+        error(
+            "-- import: lib\n\n-- integer package:\n$processor$: lib.read-package\n\n-- ftd.integer: $package\n",
+            &mut host
+        ),
+        "processor `read-package` returns `string list`, found `integer`"
+    );
+    assert_eq!(
+        error(
+            "-- import: lib\n\n-- record pair:\nstring key:\n\n-- pair list pairs:\n$processor$: lib.read-records\n\n-- ftd.text: $pair.key\n$loop$: $pairs as $pair\n",
+            &mut host
+        ),
+        "`foo#pair` should have two string fields, for the key and the value"
+    );
+    // the processors of the registry are the ones of `lib`
+    assert_eq!(
+        error(
+            "-- import: other\n\n-- integer sum:\n$processor$: other.add\na: 1\nb: 2\n\n-- ftd.integer: $sum\n",
+            &mut host
+        ),
+        "processor `add` is not supported"
+    );
+}
+
+#[test]
+fn processor_foreign_argument() {
+    let mut host = RegistryHost(ftd::interpreter2::ProcessorRegistry::builtins("lib"));
+    // the host gives the value of `other.file` before the processor is called
+    let document = ftd::interpreter2::interpret_with_host(
+        "foo",
+        indoc::indoc!(
+            "
+            -- import: lib
+            -- import: other
+
+            -- package-data list records:
+            $processor$: lib.read-records
+            file: $other.file
+
+            -- ftd.text: $record.value
+            $loop$: $records as $record
+
+            -- record package-data:
+            string name:
+            string value:
+            "
+        ),
+        &mut host,
+    )
+    .unwrap();
+    match variable_value(&document, "foo#records") {
+        ftd::interpreter2::Value::List { data, .. } => assert!(!data.is_empty()),
+        v => panic!("expected a list, got {:?}", v),
+    }
+}
+
+#[test]
+fn processor_kind() {
+    match interpret_helper(
+        "foo",
+        "-- import: test\n\n-- integer value:\n$processor$: test.fn\n\n-- ftd.integer: $value\n",
+    ) {
+        Err(ftd::interpreter2::Error::InvalidKind {
+            message,
+            line_number,
+            ..
+        }) => {
+            assert_eq!(
+                message,
+                "processor returned `string` for `value`, expected `integer`"
+            );
+            assert_eq!(line_number, 3);
+        }
+        r => panic!("expected a kind error, got {:?}", r),
+    }
+}
+
+/// Host reading modules from a map, recording the modules it is asked for.
+#[derive(Default)]
+struct RecordingHost {
+    modules: std::collections::HashMap<String, String>,
+    loaded: Vec<String>,
+}
+
+impl ftd::interpreter2::Host for RecordingHost {
+    fn load_module(
+        &mut self,
+        module: &str,
+        _caller_module: &str,
+    ) -> Option<ftd::interpreter2::Module> {
+        self.loaded.push(module.to_string());
+        self.modules.get(module).map(|v| v.to_string().into())
+    }
+}
+
+#[test]
+fn interpret_with_cache() {
+    let mut host = RecordingHost::default();
+    host.modules.insert(
+        "a".to_string(),
+        "-- import: b\n\n-- integer x: $b.y\n".to_string(),
+    );
+    host.modules
+        .insert("b".to_string(), "-- integer y: 10\n".to_string());
+    host.modules
+        .insert("c".to_string(), "-- integer z: 20\n".to_string());
+    let source = "-- import: a\n-- import: c\n\n-- ftd.integer: $a.x\n\n-- ftd.integer: $c.z\n";
+    let mut cache = ftd::interpreter2::Cache::new();
+
+    let document =
+        ftd::interpreter2::interpret_with_cache("foo", source, &mut host, &mut cache).unwrap();
+    assert_eq!(document.tree.len(), 2);
+    host.loaded.sort();
+    assert_eq!(host.loaded, vec!["a", "b", "c"]);
+    assert_eq!(cache.len(), 4);
+
+    // nothing changed: the host is only asked for the sources, every module comes from the cache
+    host.loaded.clear();
+    let cached =
+        ftd::interpreter2::interpret_with_cache("foo", source, &mut host, &mut cache).unwrap();
+    host.loaded.sort();
+    assert_eq!(host.loaded, vec!["a", "b", "c"]);
+    assert_eq!(cache.len(), 4);
+    assert_eq!(cached.tree, document.tree);
+    assert_eq!(cached.data.get("a#x"), document.data.get("a#x"),);
+
+    // `b` changed: `a` and `foo` import it, `c` is kept
+    host.modules
+        .insert("b".to_string(), "-- integer y: 30\n".to_string());
+    let mut invalidated = cache.update("b", "-- integer y: 30\n");
+    invalidated.sort();
+    assert_eq!(invalidated, vec!["a", "b", "foo"]);
+    assert!(cache.contains("c"));
+    assert!(cache.update("c", "-- integer z: 20\n").is_empty());
+
+    host.loaded.clear();
+    let document =
+        ftd::interpreter2::interpret_with_cache("foo", source, &mut host, &mut cache).unwrap();
+    host.loaded.sort();
+    assert_eq!(host.loaded, vec!["a", "b", "c"]);
+    assert!(document.data.contains_key("a#x"));
+    assert_eq!(
+        variable_value(&document, "b#y"),
+        ftd::interpreter2::Value::Integer { value: 30 }
+    );
+    assert_eq!(
+        variable_value(&document, "c#z"),
+        ftd::interpreter2::Value::Integer { value: 20 }
+    );
+
+    // `c` changed without `Cache::update`, each module is still loaded once
+    host.modules
+        .insert("c".to_string(), "-- integer z: 25\n".to_string());
+    host.loaded.clear();
+    let document =
+        ftd::interpreter2::interpret_with_cache("foo", source, &mut host, &mut cache).unwrap();
+    host.loaded.sort();
+    assert_eq!(host.loaded, vec!["a", "b", "c"]);
+    assert_eq!(
+        variable_value(&document, "c#z"),
+        ftd::interpreter2::Value::Integer { value: 25 }
+    );
+}
+
+/// Host whose module `lib` has a processor `count`, giving the number of times it is called.
+#[derive(Default)]
+struct CountingHost {
+    calls: i64,
+}
+
+impl ftd::interpreter2::Host for CountingHost {
+    fn load_module(
+        &mut self,
+        module: &str,
+        _caller_module: &str,
+    ) -> Option<ftd::interpreter2::Module> {
+        match module {
+            "lib" => Some(ftd::interpreter2::Module {
+                foreign_functions: vec!["count".to_string()],
+                ..Default::default()
+            }),
+            "a" => Some(
+                "-- import: lib\n\n-- integer n:\n$processor$: lib.count\n"
+                    .to_string()
+                    .into(),
+            ),
+            _ => None,
+        }
+    }
+
+    fn process(
+        &mut self,
+        _processor: &str,
+        _ast: &ftd::ast::AST,
+        _module: &str,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+        self.calls += 1;
+        Ok(ftd::interpreter2::Value::Integer { value: self.calls })
+    }
+}
+
+#[test]
+fn interpret_with_cache_processor() {
+    let mut host = CountingHost::default();
+    let mut cache = ftd::interpreter2::Cache::new();
+    let source = "-- import: a\n\n-- ftd.integer: $a.n\n";
+
+    for calls in 1..=2 {
+        let document =
+            ftd::interpreter2::interpret_with_cache("foo", source, &mut host, &mut cache).unwrap();
+        assert_eq!(
+            variable_value(&document, "a#n"),
+            ftd::interpreter2::Value::Integer { value: calls }
+        );
+    }
+    assert!(cache.contains("a"));
+}
+
+#[test]
+fn document_bytes() {
+    let document = interpret_helper(
+        "foo",
+        "-- record person:\nstring name:\n\n-- person p:\nname: Jane\n\n-- ftd.text: $p.name\n",
+    )
+    .unwrap();
+    let bytes = document.to_bytes().unwrap();
+    assert!(bytes.starts_with(
+        format!(
+            "ftd-document/{}\n",
+            ftd::interpreter2::Document::FORMAT_VERSION
+        )
+        .as_bytes()
+    ));
+    assert_eq!(
+        ftd::interpreter2::Document::from_bytes(bytes.as_slice()).unwrap(),
+        document
+    );
+
+    let message = |bytes: &[u8]| match ftd::interpreter2::Document::from_bytes(bytes) {
+        Err(ftd::interpreter2::Error::InvalidDocumentBytes { message }) => message,
+        r => panic!("expected an invalid document error, got {:?}", r),
+    };
+    assert_eq!(message(b"{}"), "not a serialized ftd document");
+    assert_eq!(
+        message(b"ftd-document/0\n{}"),
+        format!(
+            "document has format version `0`, expected `{}`",
+            ftd::interpreter2::Document::FORMAT_VERSION
+        )
+    );
+    assert!(matches!(
+        ftd::interpreter2::Document::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ftd::interpreter2::Error::Serde { .. })
+    ));
+}
+
+#[test]
+fn check() {
+    let mut document = interpret_helper(
+        "foo",
+        indoc::indoc!(
+            "
+            -- integer list numbers:
+
+            -- integer: 1
+            -- integer: 2
+
+            -- end: numbers
+
+            -- component show:
+            integer value:
+
+            -- ftd.integer: $show.value
+
+            -- end: show
+
+            -- show:
+            value: 1
+
+            -- show:
+            value: 2
+
+            -- ftd.integer: $number
+            $loop$: $numbers as $number
+            "
+        ),
+    )
+    .unwrap();
+    assert!(ftd::interpreter2::check(&document).is_empty());
+
+    if let Some(ftd::interpreter2::Thing::Variable(ftd::interpreter2::Variable {
+        value:
+            ftd::interpreter2::PropertyValue::Value {
+                value: ftd::interpreter2::Value::List { data, .. },
+                ..
+            },
+        ..
+    })) = document.data.get_mut("foo#numbers")
+    {
+        data[1] = ftd::interpreter2::Value::new_string("two").into_property_value(false, 4);
+    }
+    document.tree[0].properties[0].value =
+        ftd::interpreter2::Value::Boolean { value: true }.into_property_value(false, 16);
+    document.tree[1].properties.clear();
+
+    let errors = ftd::interpreter2::check(&document)
+        .into_iter()
+        .map(|e| match e {
+            ftd::interpreter2::Error::InvalidKind {
+                message,
+                line_number,
+                ..
+            } => (line_number, message),
+            e => panic!("expected an invalid kind error, got {:?}", e),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        vec![
+            (4, "expected `integer`, found `string`".to_string()),
+            (16, "expected `integer`, found `boolean`".to_string()),
+            (
+                document.tree[1].line_number,
+                "missing argument `value` of component `foo#show`".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn check_while_interpreting() {
+    let source = indoc::indoc!(
+        "
+        -- string s: hello
+
+        -- component show:
+        integer value:
+
+        -- ftd.integer: $show.value
+
+        -- end: show
+
+        -- show:
+        value: $s
+
+        -- show:
+        "
+    );
+    match ftd::interpreter2::interpret_with_host(
+        "foo",
+        source,
+        &mut ftd::interpreter2::Directory::new(vec![]),
+    ) {
+        Err(ftd::interpreter2::Error::InvalidKind {
+            message,
+            line_number,
+            ..
+        }) => assert_eq!(
+            (line_number, message.as_str()),
+            (11, "expected `integer`, found `string`")
+        ),
+        r => panic!("expected an invalid kind error, got {:?}", r),
+    }
+
+    let document = ftd::interpreter2::continue_with_host(
+        ftd::interpreter2::interpret_to_check("foo", source).unwrap(),
+        &mut ftd::interpreter2::Directory::new(vec![]),
+    )
+    .unwrap();
+    let errors = ftd::interpreter2::check(&document)
+        .into_iter()
+        .map(|e| match e {
+            ftd::interpreter2::Error::InvalidKind {
+                message,
+                line_number,
+                ..
+            } => (line_number, message),
+            e => panic!("expected an invalid kind error, got {:?}", e),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        vec![
+            (11, "expected `integer`, found `string`".to_string()),
+            (
+                13,
+                "missing argument `value` of component `foo#show`".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn lint() {
+    let source = indoc::indoc!(
+        "
+        -- import: lib
+
+        -- string $title: Hello
+
+        -- integer unused: 1
+
+        -- record person:
+        string name:
+
+        -- string list names:
+
+        -- string: a
+
+        -- end: names
+
+        -- component show:
+        caption title:
+        optional integer count:
+
+        -- ftd.text: $show.title
+
+        -- end: show
+
+        -- show: $title
+
+        -- ftd.text: hello
+        $loop$: $names as $title
+
+        ;; ftd-allow: shadowed-loop-alias
+        -- ftd.text: world
+        $loop$: $names as $title
+        "
+    );
+    let document = interpret_helper("foo", source).unwrap();
+    let lints = ftd::interpreter2::lint(&document, source).unwrap();
+
+    assert_eq!(
+        lints
+            .iter()
+            .map(|v| (v.id, v.message.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                ftd::interpreter2::LintId::UnusedImport,
+                "unused import `lib`"
+            ),
+            (
+                ftd::interpreter2::LintId::UnmutatedVariable,
+                "variable `title` is mutable but never mutated"
+            ),
+            (
+                ftd::interpreter2::LintId::UnusedVariable,
+                "unused variable `unused`"
+            ),
+            (
+                ftd::interpreter2::LintId::UnusedDefinition,
+                "unused record `person`"
+            ),
+            (
+                ftd::interpreter2::LintId::UnusedArgument,
+                "unused argument `count` of component `show`"
+            ),
+            (
+                ftd::interpreter2::LintId::ShadowedLoopAlias,
+                "loop alias `title` shadows a variable of the document"
+            ),
+        ]
+    );
+    assert_eq!(lints[0].line_number, 1);
+    assert_eq!(
+        lints[5].level,
+        ftd::diagnostic::Level::Error,
+        "a shadowed loop alias is an error"
+    );
+
+    let library = "-- string $title: Hello\n\n-- integer unused: 1\n";
+    let document = interpret_helper("lib", library).unwrap();
+    assert!(
+        ftd::interpreter2::lint(&document, library)
+            .unwrap()
+            .is_empty(),
+        "the things of a library are used by the documents importing it"
+    );
+}
+
+#[test]
+fn list_operations() {
+    use ftd::evalexpr::*;
+
+    let mut context = ftd::interpreter2::default::default_context().unwrap();
+    context
+        .set_value(
+            "a".to_string(),
+            Value::Tuple(vec![Value::Int(1), Value::Int(2), Value::Int(3)]),
+        )
+        .unwrap();
+    let list = |items: &[i64]| -> EvalexprResult<Value> {
+        Ok(Value::Tuple(items.iter().map(|v| Value::Int(*v)).collect()))
+    };
+    assert_eq!(
+        eval_with_context_mut("ftd.length(a)", &mut context),
+        Ok(Value::Int(3))
+    );
+    assert_eq!(
+        eval_with_context_mut("ftd.insert_at(a, 1, 5)", &mut context),
+        list(&[1, 5, 2, 3])
+    );
+    assert_eq!(
+        eval_with_context_mut("ftd.delete_at(a, 0)", &mut context),
+        list(&[2, 3])
+    );
+    assert_eq!(
+        eval_with_context_mut("ftd.clear(a)", &mut context),
+        list(&[])
+    );
+    assert_eq!(
+        eval_with_context_mut("ftd.filter(a, 2)", &mut context),
+        list(&[2])
+    );
+    assert_eq!(
+        eval_with_context_mut("ftd.contains(a, 3)", &mut context),
+        Ok(Value::Boolean(true))
+    );
+    assert!(eval_with_context_mut("ftd.delete_at(a, 3)", &mut context).is_err());
+    assert_eq!(
+        eval_with_context_mut("ftd.filter()", &mut context),
+        Err(EvalexprError::WrongFunctionArgumentAmount {
+            expected: 2,
+            actual: 0
+        })
+    );
+    assert_eq!(
+        eval_with_context_mut("ftd.filter(a, \"title\", 2, 3)", &mut context),
+        Err(EvalexprError::WrongFunctionArgumentAmount {
+            expected: 3,
+            actual: 4
+        })
+    );
+
+    let document = interpret_helper(
+        "foo",
+        indoc::indoc!(
+            "
+            -- record todo:
+            string title:
+            boolean done:
+
+            -- todo list todos:
+
+            -- todo:
+            title: write
+            done: true
+
+            -- todo:
+            title: read
+            done: false
+
+            -- end: todos
+
+            -- integer list list matrix:
+
+            -- integer list:
+            -- integer: 1
+            -- integer: 2
+            -- end: list
+
+            -- integer list:
+            -- integer: 3
+            -- end: list
+
+            -- end: matrix
+
+            -- todo list sort-todos(items):
+            todo list items:
+
+            ftd.sort_by(items, \"title\")
+
+            -- todo list sorted-todos: $sort-todos(items = $todos)
+
+            -- ftd.text: $item.title
+            $loop$: $sorted-todos as $item
+
+            -- component row:
+            integer list values:
+
+            -- ftd.row:
+
+            -- ftd.integer: $value
+            $loop$: $row.values as $value
+
+            -- end: ftd.row
+
+            -- end: row
+
+            -- row:
+            values: $values
+            $loop$: $matrix as $values
+            "
+        ),
+    )
+    .unwrap();
+
+    match variable_value(&document, "foo#matrix") {
+        ftd::interpreter2::Value::List { data, kind } => {
+            assert_eq!(kind.kind, ftd::interpreter2::Kind::integer().into_list());
+            assert_eq!(data.len(), 2);
+            assert!(matches!(
+                &data[1],
+                ftd::interpreter2::PropertyValue::Value {
+                    value: ftd::interpreter2::Value::List { data, .. },
+                    ..
+                } if data.len() == 1
+            ));
+        }
+        v => panic!("expected a list, got {:?}", v),
+    }
+
+    let doc = ftd::interpreter2::TDoc::new(&document.name, &document.aliases, &document.data);
+    let function = doc.get_function("foo#sort-todos", 0).unwrap();
+    let values: ftd::Map<ftd::interpreter2::PropertyValue> = std::iter::once((
+        "items".to_string(),
+        variable_value(&document, "foo#todos").into_property_value(false, 0),
+    ))
+    .collect();
+    match function.resolve(&function.return_kind, &values, &doc, 0) {
+        Ok(Some(ftd::interpreter2::Value::List { data, .. })) => {
+            let titles = data
+                .iter()
+                .map(|item| match item {
+                    ftd::interpreter2::PropertyValue::Value {
+                        value: ftd::interpreter2::Value::Record { fields, .. },
+                        ..
+                    } => fields.get("title").cloned(),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                titles,
+                vec![
+                    Some(
+                        ftd::interpreter2::Value::new_string("read").into_property_value(false, 0)
+                    ),
+                    Some(
+                        ftd::interpreter2::Value::new_string("write").into_property_value(false, 0)
+                    ),
+                ]
+            );
+        }
+        r => panic!("expected a sorted list, got {:?}", r),
+    }
+}
+
+/// Runs `future` on the current thread, polling it until it is ready.
+#[cfg(feature = "async")]
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    use std::future::Future;
+
+    struct Noop;
+    impl std::task::Wake for Noop {
+        fn wake(self: std::sync::Arc<Self>) {}
+    }
+
+    let waker = std::task::Waker::from(std::sync::Arc::new(Noop));
+    let mut cx = std::task::Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let std::task::Poll::Ready(v) = future.as_mut().poll(&mut cx) {
+            return v;
+        }
+    }
+}
+
+/// Returns `Pending` once, so that other futures get polled in between.
+#[cfg(feature = "async")]
+async fn yield_now() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            return std::task::Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        std::task::Poll::Pending
+    })
+    .await
+}
+
+/// Host reading modules from `modules`, and counting how many are being loaded at once.
+/// Processors return their name in uppercase.
+#[cfg(feature = "async")]
+#[derive(Default)]
+struct AsyncTestHost {
+    modules: std::collections::HashMap<String, ftd::interpreter2::Module>,
+    loading: std::sync::atomic::AtomicUsize,
+    max_loading: std::sync::atomic::AtomicUsize,
+}
+
+#[cfg(feature = "async")]
+impl ftd::interpreter2::AsyncHost for AsyncTestHost {
+    fn load_module<'a>(
+        &'a self,
+        module: &'a str,
+        _caller_module: &'a str,
+    ) -> ftd::interpreter2::BoxFuture<'a, Option<ftd::interpreter2::Module>> {
+        use std::sync::atomic::Ordering;
+
+        Box::pin(async move {
+            let loading = self.loading.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_loading.fetch_max(loading, Ordering::SeqCst);
+            yield_now().await;
+            self.loading.fetch_sub(1, Ordering::SeqCst);
+            self.modules.get(module).cloned()
+        })
+    }
+
+    fn process<'a>(
+        &'a self,
+        processor: &'a str,
+        _ast: &'a ftd::ast::AST,
+        _module: &'a str,
+    ) -> ftd::interpreter2::BoxFuture<'a, ftd::interpreter2::Result<ftd::interpreter2::Value>> {
+        Box::pin(async move {
+            yield_now().await;
+            Ok(ftd::interpreter2::Value::String {
+                text: processor.to_uppercase(),
+            })
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn interpret_async() {
+    let mut host = AsyncTestHost::default();
+    host.modules
+        .insert("a".to_string(), "-- integer x: 1\n".to_string().into());
+    host.modules
+        .insert("b".to_string(), "-- integer y: 2\n".to_string().into());
+    host.modules.insert(
+        "p".to_string(),
+        ftd::interpreter2::Module {
+            foreign_functions: vec!["greet".to_string()],
+            ..Default::default()
+        },
+    );
+
+    let document = block_on(ftd::interpreter2::interpret_async(
+        "foo",
+        indoc::indoc!(
+            "
+            -- import: a
+            -- import: b
+            -- import: p
+
+            -- string greeting: hello
+            $processor$: p.greet
+
+            -- ftd.integer: $a.x
+
+            -- ftd.integer: $b.y
+
+            -- ftd.text: $greeting
+            "
+        ),
+        &host,
+    ))
+    .unwrap();
+    assert_eq!(document.tree.len(), 3);
+    assert!(document.data.contains_key("a#x"));
+    assert!(document.data.contains_key("b#y"));
+    match document.data.get("foo#greeting") {
+        Some(ftd::interpreter2::Thing::Variable(ftd::interpreter2::Variable {
+            value: ftd::interpreter2::PropertyValue::Value { value, .. },
+            ..
+        })) => assert_eq!(
+            value,
+            &ftd::interpreter2::Value::String {
+                text: "GREET".to_string()
+            }
+        ),
+        t => panic!("expected the value of `greeting`, got {:?}", t),
+    }
+    // `b` and `p` are loaded along with `a`, before the interpreter asks for them
+    assert_eq!(
+        host.max_loading.load(std::sync::atomic::Ordering::SeqCst),
+        3
+    );
+
+    match block_on(ftd::interpreter2::interpret_async(
+        "foo",
+        "-- import: c\n\n-- ftd.integer: $c.z\n",
+        &host,
+    )) {
+        Err(ftd::interpreter2::Error::ParseError { message, .. }) => {
+            assert_eq!(message, "Cannot find module `c`")
+        }
+        r => panic!("expected a missing module error, got {:?}", r),
+    }
+}
@@ -73,6 +73,7 @@ impl ComponentDefinition {
                     .to_string(),
                 doc_id: doc.name.to_string(),
                 line_number: iteration.line_number,
+                span: Some(iteration.span),
             });
         }
         Ok(ftd::interpreter2::StateWithThing::new_thing(
@@ -105,6 +106,8 @@ pub struct Component {
     pub events: Vec<Event>,
     pub children: Vec<Component>,
     pub line_number: usize,
+    #[serde(default, skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
 }
 
 impl Component {
@@ -117,6 +120,7 @@ impl Component {
             events: vec![],
             children: vec![],
             line_number: 0,
+            span: Default::default(),
         }
     }
 
@@ -221,13 +225,25 @@ impl Component {
         ast_component: ftd::ast::Component,
         definition_name_with_arguments: Option<(&str, &[Argument])>,
         doc: &mut ftd::interpreter2::TDoc,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::StateWithThing<Component>> {
+        let span = ast_component.span;
+        Component::from_ast_component_with_span(ast_component, definition_name_with_arguments, doc)
+            .map_err(|e| e.with_span(span))
+    }
+
+    fn from_ast_component_with_span(
+        ast_component: ftd::ast::Component,
+        definition_name_with_arguments: Option<(&str, &[Argument])>,
+        doc: &mut ftd::interpreter2::TDoc,
     ) -> ftd::interpreter2::Result<ftd::interpreter2::StateWithThing<Component>> {
         let name = doc.resolve_name(ast_component.name.as_str());
 
         let mut loop_object_name_and_kind = None;
         let iteration = if let Some(v) = ast_component.iteration {
+            let span = v.span;
             let iteration =
-                try_ok_state!(Loop::from_ast_loop(v, definition_name_with_arguments, doc)?);
+                try_ok_state!(Loop::from_ast_loop(v, definition_name_with_arguments, doc)
+                    .map_err(|e| e.with_span(span))?);
             loop_object_name_and_kind = Some((
                 iteration.alias.to_string(),
                 iteration.loop_object_as_argument(doc)?,
@@ -238,13 +254,15 @@ impl Component {
         };
 
         let condition = if let Some(v) = ast_component.condition {
+            let span = v.span;
             Some(try_ok_state!(
                 ftd::interpreter2::Expression::from_ast_condition(
                     v,
                     definition_name_with_arguments,
                     &loop_object_name_and_kind,
                     doc,
-                )?
+                )
+                .map_err(|e| e.with_span(span))?
             ))
         } else {
             None
@@ -267,6 +285,47 @@ impl Component {
             ast_component.line_number,
         )?);
 
+        if name.eq("ftd#text") {
+            // the executor renders the markup of the text with the things it refers to
+            for property in properties.iter() {
+                let text = match &property.value {
+                    ftd::interpreter2::PropertyValue::Value {
+                        value: ftd::interpreter2::Value::String { text },
+                        ..
+                    } => text,
+                    _ => continue,
+                };
+                let (references, components) = ftd::interpreter2::utils::markup_names(text);
+                for reference in references {
+                    if ftd::interpreter2::utils::get_argument_for_reference_and_remaining(
+                        reference.as_str(),
+                        doc,
+                        definition_name_with_arguments,
+                        &loop_object_name_and_kind,
+                        ast_component.line_number,
+                    )?
+                    .is_none()
+                    {
+                        try_ok_state!(doc
+                            .search_initial_thing(reference.as_str(), ast_component.line_number)?);
+                    }
+                }
+                for component in components {
+                    if !ftd::interpreter2::utils::is_markup_component_name(component.as_str(), doc)
+                    {
+                        continue;
+                    }
+                    // a name that is not a thing of the document is left to the executor to
+                    // render as text
+                    if let Ok(state) =
+                        doc.search_initial_thing(component.as_str(), ast_component.line_number)
+                    {
+                        try_ok_state!(state);
+                    }
+                }
+            }
+        }
+
         Ok(ftd::interpreter2::StateWithThing::new_thing(Component {
             name,
             properties,
@@ -275,6 +334,7 @@ impl Component {
             events,
             children: vec![],
             line_number: ast_component.line_number,
+            span: ast_component.span,
         }))
     }
 }
@@ -331,6 +391,8 @@ pub struct Property {
     pub source: ftd::interpreter2::PropertySource,
     pub condition: Option<ftd::interpreter2::Expression>,
     pub line_number: usize,
+    #[serde(default, skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
 }
 
 impl Property {
@@ -444,6 +506,7 @@ impl Property {
         }
 
         let line_number = ast_children.first().unwrap().line_number;
+        let span = ast_children.first().unwrap().span;
         let component_arguments = try_ok_state!(Argument::for_component(
             component_name,
             &definition_name_with_arguments,
@@ -456,6 +519,7 @@ impl Property {
                 message: "Subsection is unexpected".to_string(),
                 doc_id: doc.name.to_string(),
                 line_number,
+                span: Some(span),
             },
         )?;
 
@@ -497,6 +561,7 @@ impl Property {
                 source: ftd::interpreter2::PropertySource::Subsection,
                 condition: None,
                 line_number,
+                span: Default::default(),
             },
         )))
     }
@@ -549,7 +614,7 @@ impl Property {
 
         if let Some(ref v) = ast_property.condition {
             ftd::interpreter2::Expression::scan_ast_condition(
-                ftd::ast::Condition::new(v, ast_property.line_number),
+                ftd::ast::Condition::new(v, ast_property.line_number).with_span(ast_property.span),
                 definition_name_with_arguments,
                 loop_object_name_and_kind,
                 doc,
@@ -575,6 +640,7 @@ impl Property {
             line_number,
         )?);
         for property in ast_properties {
+            let span = property.span;
             properties.push(try_ok_state!(Property::from_ast_property(
                 property,
                 component_name,
@@ -582,7 +648,8 @@ impl Property {
                 definition_name_with_arguments,
                 loop_object_name_and_kind,
                 doc,
-            )?));
+            )
+            .map_err(|e| e.with_span(span))?));
         }
         Ok(ftd::interpreter2::StateWithThing::new_thing(properties))
     }
@@ -616,7 +683,8 @@ impl Property {
         let condition = if let Some(ref v) = ast_property.condition {
             Some(try_ok_state!(
                 ftd::interpreter2::Expression::from_ast_condition(
-                    ftd::ast::Condition::new(v, ast_property.line_number),
+                    ftd::ast::Condition::new(v, ast_property.line_number)
+                        .with_span(ast_property.span),
                     definition_name_with_arguments,
                     loop_object_name_and_kind,
                     doc,
@@ -650,6 +718,7 @@ impl Property {
             source,
             condition,
             line_number: ast_property.line_number,
+            span: ast_property.span,
         }))
     }
 
@@ -671,6 +740,7 @@ impl Property {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number: ast_property.line_number,
+                        span: Some(ast_property.span),
                     })
                     .map(ToOwned::to_owned)?,
             )),
@@ -685,6 +755,7 @@ impl Property {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number: ast_property.line_number,
+                        span: Some(ast_property.span),
                     })
                     .map(ToOwned::to_owned)?,
             )),
@@ -700,6 +771,7 @@ impl Property {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number: ast_property.line_number,
+                        span: Some(ast_property.span),
                     })?
                     .to_owned();
                 if !argument.mutable.eq(mutable) {
@@ -734,14 +806,22 @@ pub struct Loop {
     pub on: ftd::interpreter2::PropertyValue,
     pub alias: String,
     pub line_number: usize,
+    #[serde(default, skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
 }
 
 impl Loop {
-    fn new(on: ftd::interpreter2::PropertyValue, alias: &str, line_number: usize) -> Loop {
+    fn new(
+        on: ftd::interpreter2::PropertyValue,
+        alias: &str,
+        line_number: usize,
+        span: ftd::p11::Span,
+    ) -> Loop {
         Loop {
             on,
             alias: alias.to_string(),
             line_number,
+            span,
         }
     }
 
@@ -809,6 +889,7 @@ impl Loop {
             on,
             doc.resolve_name(ast_loop.alias.as_str()).as_str(),
             ast_loop.line_number,
+            ast_loop.span,
         )))
     }
 
@@ -837,6 +918,8 @@ pub struct Event {
     pub name: ftd::interpreter2::EventName,
     pub action: ftd::interpreter2::FunctionCall,
     line_number: usize,
+    #[serde(default, skip_serializing_if = "ftd::p11::Span::is_empty")]
+    span: ftd::p11::Span,
 }
 
 impl Event {
@@ -865,6 +948,7 @@ impl Event {
             name: event_name,
             action,
             line_number: ast_event.line_number,
+            span: ast_event.span,
         }))
     }
 
@@ -876,12 +960,14 @@ impl Event {
     ) -> ftd::interpreter2::Result<ftd::interpreter2::StateWithThing<Vec<Event>>> {
         let mut events = vec![];
         for event in ast_events {
+            let span = event.span;
             events.push(try_ok_state!(Event::from_ast_event(
                 event,
                 definition_name_with_arguments,
                 loop_object_name_and_kind,
                 doc,
-            )?));
+            )
+            .map_err(|e| e.with_span(span))?));
         }
         Ok(ftd::interpreter2::StateWithThing::new_thing(events))
     }
@@ -29,6 +29,11 @@ enable light mode in the application.
 4. `enable_system_mode` - This function takes no arguments and returns an empty value. It is used to
 enable system mode in the application, which means the application will use the system's default
 color scheme.
+
+5. The list functions `append`, `length`, `insert_at`, `delete_at`, `clear`, `filter`, `contains`
+and `sort_by`. A list is a tuple and a record is a tuple of `(field-name, value)` pairs, see
+`ftd::interpreter2::Value::to_evalexpr_value`. The same functions are available in the generated
+javascript as `window.ftd.<name>`.
 **/
 pub fn default_functions() -> ftd::Map<ftd::evalexpr::Function> {
     use ftd::evalexpr::*;
@@ -70,6 +75,96 @@ pub fn default_functions() -> ftd::Map<ftd::evalexpr::Function> {
                 }
             }),
         ),
+        (
+            "ftd.length".to_string(),
+            Function::new(|argument| Ok(Value::Int(argument.as_tuple()?.len() as IntType))),
+        ),
+        (
+            "ftd.insert_at".to_string(),
+            Function::new(|argument| {
+                let arguments = list_function_arguments(argument, 3)?;
+                let mut list = arguments[0].as_tuple()?;
+                let index = list_index(&arguments[1], list.len() + 1)?;
+                list.insert(index, arguments[2].to_owned());
+                Ok(Value::Tuple(list))
+            }),
+        ),
+        (
+            "ftd.delete_at".to_string(),
+            Function::new(|argument| {
+                let arguments = list_function_arguments(argument, 2)?;
+                let mut list = arguments[0].as_tuple()?;
+                let index = list_index(&arguments[1], list.len())?;
+                list.remove(index);
+                Ok(Value::Tuple(list))
+            }),
+        ),
+        (
+            "ftd.clear".to_string(),
+            Function::new(|argument| {
+                argument.as_tuple()?;
+                Ok(Value::Tuple(vec![]))
+            }),
+        ),
+        (
+            "ftd.filter".to_string(),
+            Function::new(|argument| {
+                // `ftd.filter()` is called with `Value::Empty`
+                let arguments = match argument {
+                    Value::Empty => vec![],
+                    argument => argument.as_tuple()?,
+                };
+                match arguments.as_slice() {
+                    // `ftd.filter(list, value)` keeps the items equal to `value`
+                    [list, value] => Ok(Value::Tuple(
+                        list.as_tuple()?
+                            .into_iter()
+                            .filter(|item| item.eq(value))
+                            .collect(),
+                    )),
+                    // `ftd.filter(list, field, value)` keeps the records whose `field` is
+                    // equal to `value`
+                    [list, field, value] => {
+                        let field = field.as_string()?;
+                        Ok(Value::Tuple(
+                            list.as_tuple()?
+                                .into_iter()
+                                .filter(|item| record_field(item, field.as_str()) == Some(value))
+                                .collect(),
+                        ))
+                    }
+                    // it takes 2 or 3 arguments, the closest one is expected
+                    _ => Err(EvalexprError::WrongFunctionArgumentAmount {
+                        expected: if arguments.len() < 2 { 2 } else { 3 },
+                        actual: arguments.len(),
+                    }),
+                }
+            }),
+        ),
+        (
+            "ftd.contains".to_string(),
+            Function::new(|argument| {
+                let arguments = list_function_arguments(argument, 2)?;
+                Ok(Value::Boolean(
+                    arguments[0].as_tuple()?.contains(&arguments[1]),
+                ))
+            }),
+        ),
+        (
+            "ftd.sort_by".to_string(),
+            Function::new(|argument| {
+                let arguments = list_function_arguments(argument, 2)?;
+                let field = arguments[1].as_string()?;
+                let mut list = arguments[0].as_tuple()?;
+                list.sort_by(|a, b| {
+                    compare_values(
+                        record_field(a, field.as_str()),
+                        record_field(b, field.as_str()),
+                    )
+                });
+                Ok(Value::Tuple(list))
+            }),
+        ),
         (
             "enable_dark_mode".to_string(),
             Function::new(|_| Ok(Value::Empty)),
@@ -86,6 +181,71 @@ pub fn default_functions() -> ftd::Map<ftd::evalexpr::Function> {
     .collect()
 }
 
+/// The arguments of a list function called with `expected` arguments, e.g. `(list, index)`.
+fn list_function_arguments(
+    argument: &ftd::evalexpr::Value,
+    expected: usize,
+) -> ftd::evalexpr::EvalexprResult<ftd::evalexpr::TupleType> {
+    let arguments = argument.as_tuple()?;
+    if arguments.len() != expected {
+        return Err(
+            ftd::evalexpr::error::EvalexprError::WrongFunctionArgumentAmount {
+                expected,
+                actual: arguments.len(),
+            },
+        );
+    }
+    Ok(arguments)
+}
+
+/// `index` as a position in a list, which must be less than `len`.
+fn list_index(index: &ftd::evalexpr::Value, len: usize) -> ftd::evalexpr::EvalexprResult<usize> {
+    let index = index.as_int()?;
+    if index < 0 || index as usize >= len {
+        return Err(ftd::evalexpr::error::EvalexprError::CustomMessage(format!(
+            "index `{}` is out of bounds",
+            index
+        )));
+    }
+    Ok(index as usize)
+}
+
+/// The value of `field` of a record, a tuple of `(field-name, value)` pairs.
+fn record_field<'a>(
+    record: &'a ftd::evalexpr::Value,
+    field: &str,
+) -> Option<&'a ftd::evalexpr::Value> {
+    match record {
+        ftd::evalexpr::Value::Tuple(pairs) => pairs.iter().find_map(|pair| match pair {
+            ftd::evalexpr::Value::Tuple(pair) => match pair.as_slice() {
+                [ftd::evalexpr::Value::String(name), value] if name.eq(field) => Some(value),
+                _ => None,
+            },
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+fn compare_values(
+    a: Option<&ftd::evalexpr::Value>,
+    b: Option<&ftd::evalexpr::Value>,
+) -> std::cmp::Ordering {
+    use ftd::evalexpr::Value;
+
+    match (a, b) {
+        (Some(Value::String(a)), Some(Value::String(b))) => a.cmp(b),
+        (Some(Value::Boolean(a)), Some(Value::Boolean(b))) => a.cmp(b),
+        (Some(Value::Int(a)), Some(Value::Int(b))) => a.cmp(b),
+        (Some(a), Some(b)) if a.is_number() && b.is_number() => a
+            .as_number()
+            .unwrap()
+            .partial_cmp(&b.as_number().unwrap())
+            .unwrap_or(std::cmp::Ordering::Equal),
+        _ => std::cmp::Ordering::Equal,
+    }
+}
+
 pub fn default_context() -> ftd::interpreter2::Result<ftd::evalexpr::HashMapContext> {
     let mut context = ftd::evalexpr::HashMapContext::new();
     for (key, function) in default_functions() {
@@ -121,6 +281,10 @@ pub fn default_bag() -> ftd::Map<ftd::interpreter2::Thing> {
             "ftd#column".to_string(),
             ftd::interpreter2::Thing::Component(column_function()),
         ),
+        (
+            "ftd#grid".to_string(),
+            ftd::interpreter2::Thing::Component(grid_function()),
+        ),
         (
             "ftd#text".to_string(),
             ftd::interpreter2::Thing::Component(markup_function()),
@@ -500,19 +664,601 @@ pub fn default_bag() -> ftd::Map<ftd::interpreter2::Thing> {
             }),
         ),
         (
-            ftd::interpreter2::FTD_BACKGROUND.to_string(),
+            ftd::interpreter2::FTD_BACKGROUND.to_string(),
+            ftd::interpreter2::Thing::OrType(ftd::interpreter2::OrType {
+                name: ftd::interpreter2::FTD_BACKGROUND.to_string(),
+                variants: vec![
+                    ftd::interpreter2::OrTypeVariant::Regular(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_SOLID,
+                        ftd::interpreter2::Kind::record(ftd::interpreter2::FTD_COLOR)
+                            .into_kind_data(),
+                        false,
+                        None,
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::Regular(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_LINEAR_GRADIENT,
+                        ftd::interpreter2::Kind::record(ftd::interpreter2::FTD_LINEAR_GRADIENT)
+                            .into_kind_data(),
+                        false,
+                        None,
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::Regular(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_RADIAL_GRADIENT,
+                        ftd::interpreter2::Kind::record(ftd::interpreter2::FTD_RADIAL_GRADIENT)
+                            .into_kind_data(),
+                        false,
+                        None,
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::Regular(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_IMAGE,
+                        ftd::interpreter2::Kind::record(ftd::interpreter2::FTD_IMAGE_BACKGROUND)
+                            .into_kind_data(),
+                        false,
+                        None,
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::Regular(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_LAYERS,
+                        ftd::interpreter2::Kind::or_type(
+                            ftd::interpreter2::FTD_BACKGROUND,
+                        ).into_list()
+                            .into_kind_data(),
+                        false,
+                        None,
+                        0,
+                    )),
+                ],
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter2::FTD_COLOR_STOP.to_string(),
+            ftd::interpreter2::Thing::Record(ftd::interpreter2::Record {
+                name: ftd::interpreter2::FTD_COLOR_STOP.to_string(),
+                fields: std::iter::IntoIterator::into_iter([
+                    ftd::interpreter2::Field {
+                        name: "color".to_string(),
+                        kind: ftd::interpreter2::Kind::record(ftd::interpreter2::FTD_COLOR)
+                            .into_kind_data()
+                            .caption(),
+                        mutable: false,
+                        value: None,
+                        line_number: 0,
+                    },
+                    ftd::interpreter2::Field {
+                        name: "stop".to_string(),
+                        kind: ftd::interpreter2::Kind::or_type(ftd::interpreter2::FTD_LENGTH)
+                            .into_optional()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        line_number: 0,
+                    },
+                ])
+                .collect(),
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter2::FTD_LINEAR_GRADIENT.to_string(),
+            ftd::interpreter2::Thing::Record(ftd::interpreter2::Record {
+                name: ftd::interpreter2::FTD_LINEAR_GRADIENT.to_string(),
+                fields: std::iter::IntoIterator::into_iter([
+                    ftd::interpreter2::Field {
+                        name: "direction".to_string(),
+                        kind: ftd::interpreter2::Kind::or_type(
+                            ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS,
+                        )
+                            .into_optional()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        line_number: 0,
+                    },
+                    ftd::interpreter2::Field {
+                        name: "colors".to_string(),
+                        kind: ftd::interpreter2::Kind::record(ftd::interpreter2::FTD_COLOR_STOP)
+                            .into_list()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        line_number: 0,
+                    },
+                ])
+                .collect(),
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter2::FTD_RADIAL_GRADIENT.to_string(),
+            ftd::interpreter2::Thing::Record(ftd::interpreter2::Record {
+                name: ftd::interpreter2::FTD_RADIAL_GRADIENT.to_string(),
+                fields: std::iter::IntoIterator::into_iter([
+                    ftd::interpreter2::Field {
+                        name: "shape".to_string(),
+                        kind: ftd::interpreter2::Kind::or_type(
+                            ftd::interpreter2::FTD_RADIAL_GRADIENT_SHAPE,
+                        )
+                            .into_optional()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        line_number: 0,
+                    },
+                    ftd::interpreter2::Field {
+                        name: "position".to_string(),
+                        kind: ftd::interpreter2::Kind::or_type(
+                            ftd::interpreter2::FTD_BACKGROUND_POSITION,
+                        )
+                            .into_optional()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        line_number: 0,
+                    },
+                    ftd::interpreter2::Field {
+                        name: "colors".to_string(),
+                        kind: ftd::interpreter2::Kind::record(ftd::interpreter2::FTD_COLOR_STOP)
+                            .into_list()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        line_number: 0,
+                    },
+                ])
+                .collect(),
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter2::FTD_IMAGE_BACKGROUND.to_string(),
+            ftd::interpreter2::Thing::Record(ftd::interpreter2::Record {
+                name: ftd::interpreter2::FTD_IMAGE_BACKGROUND.to_string(),
+                fields: std::iter::IntoIterator::into_iter([
+                    ftd::interpreter2::Field {
+                        name: "src".to_string(),
+                        kind: ftd::interpreter2::Kind::record(
+                            ftd::interpreter2::FTD_IMAGE_SRC,
+                        ).into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        line_number: 0,
+                    },
+                    ftd::interpreter2::Field {
+                        name: "repeat".to_string(),
+                        kind: ftd::interpreter2::Kind::or_type(
+                            ftd::interpreter2::FTD_BACKGROUND_REPEAT,
+                        )
+                            .into_optional()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        line_number: 0,
+                    },
+                    ftd::interpreter2::Field {
+                        name: "size".to_string(),
+                        kind: ftd::interpreter2::Kind::or_type(
+                            ftd::interpreter2::FTD_BACKGROUND_SIZE,
+                        )
+                            .into_optional()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        line_number: 0,
+                    },
+                    ftd::interpreter2::Field {
+                        name: "position".to_string(),
+                        kind: ftd::interpreter2::Kind::or_type(
+                            ftd::interpreter2::FTD_BACKGROUND_POSITION,
+                        )
+                            .into_optional()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        line_number: 0,
+                    },
+                ])
+                .collect(),
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS.to_string(),
+            ftd::interpreter2::Thing::OrType(ftd::interpreter2::OrType {
+                name: ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS.to_string(),
+                variants: vec![
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_TOP,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_TOP,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_LEFT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_LEFT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_RIGHT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_RIGHT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_TOP_LEFT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_TOP_LEFT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_TOP_RIGHT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_TOP_RIGHT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM_LEFT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM_LEFT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM_RIGHT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_BOTTOM_RIGHT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::Regular(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_LINEAR_GRADIENT_DIRECTIONS_ANGLE,
+                        ftd::interpreter2::Kind::decimal()
+                            .into_kind_data(),
+                        false,
+                        None,
+                        0,
+                    )),
+                ],
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter2::FTD_RADIAL_GRADIENT_SHAPE.to_string(),
+            ftd::interpreter2::Thing::OrType(ftd::interpreter2::OrType {
+                name: ftd::interpreter2::FTD_RADIAL_GRADIENT_SHAPE.to_string(),
+                variants: vec![
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_RADIAL_GRADIENT_SHAPE_CIRCLE,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_RADIAL_GRADIENT_SHAPE_CIRCLE,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_RADIAL_GRADIENT_SHAPE_ELLIPSE,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_RADIAL_GRADIENT_SHAPE_ELLIPSE,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                ],
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter2::FTD_BACKGROUND_REPEAT.to_string(),
+            ftd::interpreter2::Thing::OrType(ftd::interpreter2::OrType {
+                name: ftd::interpreter2::FTD_BACKGROUND_REPEAT.to_string(),
+                variants: vec![
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_REPEAT_REPEAT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_REPEAT_REPEAT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_REPEAT_REPEAT_X,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_REPEAT_REPEAT_X,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_REPEAT_REPEAT_Y,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_REPEAT_REPEAT_Y,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_REPEAT_NO_REPEAT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_REPEAT_NO_REPEAT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_REPEAT_SPACE,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_REPEAT_SPACE,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_REPEAT_ROUND,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_REPEAT_ROUND,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                ],
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter2::FTD_BACKGROUND_SIZE.to_string(),
+            ftd::interpreter2::Thing::OrType(ftd::interpreter2::OrType {
+                name: ftd::interpreter2::FTD_BACKGROUND_SIZE.to_string(),
+                variants: vec![
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_SIZE_AUTO,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_SIZE_AUTO,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_SIZE_COVER,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_SIZE_COVER,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_SIZE_CONTAIN,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_SIZE_CONTAIN,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::Regular(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_SIZE_FIXED,
+                        ftd::interpreter2::Kind::or_type(ftd::interpreter2::FTD_LENGTH)
+                            .into_kind_data(),
+                        false,
+                        None,
+                        0,
+                    )),
+                ],
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter2::FTD_BACKGROUND_POSITION.to_string(),
             ftd::interpreter2::Thing::OrType(ftd::interpreter2::OrType {
-                name: ftd::interpreter2::FTD_BACKGROUND.to_string(),
-                variants: vec![ftd::interpreter2::OrTypeVariant::Regular(
-                    ftd::interpreter2::Field::new(
-                        ftd::interpreter2::FTD_BACKGROUND_SOLID,
-                        ftd::interpreter2::Kind::record(ftd::interpreter2::FTD_COLOR)
-                            .into_kind_data(),
+                name: ftd::interpreter2::FTD_BACKGROUND_POSITION.to_string(),
+                variants: vec![
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_POSITION_LEFT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
                         false,
-                        None,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_POSITION_LEFT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_POSITION_CENTER,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_POSITION_CENTER,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_POSITION_RIGHT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_POSITION_RIGHT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_POSITION_TOP,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_POSITION_TOP,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_POSITION_BOTTOM,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_POSITION_BOTTOM,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_POSITION_LEFT_TOP,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_POSITION_LEFT_TOP,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_POSITION_LEFT_BOTTOM,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_POSITION_LEFT_BOTTOM,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_POSITION_RIGHT_TOP,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_POSITION_RIGHT_TOP,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_BACKGROUND_POSITION_RIGHT_BOTTOM,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_BACKGROUND_POSITION_RIGHT_BOTTOM,
+                            )
+                            .into_property_value(false, 0),
+                        ),
                         0,
-                    ),
-                )],
+                    )),
+                ],
                 line_number: 0,
             }),
         ),
@@ -1287,6 +2033,67 @@ pub fn default_bag() -> ftd::Map<ftd::interpreter2::Thing> {
                 line_number: 0,
             }),
         ),
+        (
+            ftd::interpreter2::FTD_GRID_TRACK.to_string(),
+            ftd::interpreter2::Thing::OrType(ftd::interpreter2::OrType {
+                name: ftd::interpreter2::FTD_GRID_TRACK.to_string(),
+                variants: vec![
+                    ftd::interpreter2::OrTypeVariant::Regular(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_GRID_TRACK_FIXED,
+                        ftd::interpreter2::Kind::or_type(ftd::interpreter2::FTD_LENGTH)
+                            .into_kind_data(),
+                        false,
+                        None,
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::Regular(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_GRID_TRACK_FRACTION,
+                        ftd::interpreter2::Kind::decimal()
+                            .into_kind_data(),
+                        false,
+                        None,
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_GRID_TRACK_AUTO,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_GRID_TRACK_AUTO,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_GRID_TRACK_MIN_CONTENT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_GRID_TRACK_MIN_CONTENT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                    ftd::interpreter2::OrTypeVariant::new_constant(ftd::interpreter2::Field::new(
+                        ftd::interpreter2::FTD_GRID_TRACK_MAX_CONTENT,
+                        ftd::interpreter2::Kind::string().into_kind_data(),
+                        false,
+                        Some(
+                            ftd::interpreter2::Value::new_string(
+                                ftd::interpreter2::FTD_GRID_TRACK_MAX_CONTENT,
+                            )
+                            .into_property_value(false, 0),
+                        ),
+                        0,
+                    )),
+                ],
+                line_number: 0,
+            }),
+        ),
         (
             ftd::interpreter2::FTD_WHITESPACE.to_string(),
             ftd::interpreter2::Thing::OrType(ftd::interpreter2::OrType {
@@ -6603,6 +7410,36 @@ pub fn code_function() -> ftd::interpreter2::ComponentDefinition {
                         .into_optional()
                         .into_kind_data(),
                 ),
+                ftd::interpreter2::Argument::default(
+                    "dark-theme",
+                    ftd::interpreter2::Kind::string()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter2::Argument::default(
+                    "show-line-numbers",
+                    ftd::interpreter2::Kind::boolean()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter2::Argument::default(
+                    "highlight-lines",
+                    ftd::interpreter2::Kind::string()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter2::Argument::default(
+                    "start-line",
+                    ftd::interpreter2::Kind::integer()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter2::Argument::default(
+                    "diff",
+                    ftd::interpreter2::Kind::boolean()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
             ],
         ]
         .concat()
@@ -6667,6 +7504,53 @@ pub fn column_function() -> ftd::interpreter2::ComponentDefinition {
     }
 }
 
+pub fn grid_function() -> ftd::interpreter2::ComponentDefinition {
+    ftd::interpreter2::ComponentDefinition {
+        name: "ftd#grid".to_string(),
+        arguments: [
+            container_arguments(),
+            common_arguments(),
+            vec![
+                ftd::interpreter2::Argument::default(
+                    "template-columns",
+                    ftd::interpreter2::Kind::or_type(ftd::interpreter2::FTD_GRID_TRACK)
+                        .into_list()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter2::Argument::default(
+                    "template-rows",
+                    ftd::interpreter2::Kind::or_type(ftd::interpreter2::FTD_GRID_TRACK)
+                        .into_list()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter2::Argument::default(
+                    "template-areas",
+                    ftd::interpreter2::Kind::string()
+                        .into_list()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter2::Argument::default(
+                    "column-gap",
+                    ftd::interpreter2::Kind::or_type(ftd::interpreter2::FTD_LENGTH)
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter2::Argument::default(
+                    "row-gap",
+                    ftd::interpreter2::Kind::or_type(ftd::interpreter2::FTD_LENGTH)
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+            ],
+        ]
+        .concat()
+        .into_iter()
+        .collect(),
+        definition: ftd::interpreter2::Component::from_name("ftd.kernel"),
+        line_number: 0,
+    }
+}
+
 fn container_arguments() -> Vec<ftd::interpreter2::Argument> {
     vec![
         ftd::interpreter2::Argument::default(
@@ -6704,6 +7588,24 @@ fn container_arguments() -> Vec<ftd::interpreter2::Argument> {
 
 fn common_arguments() -> Vec<ftd::interpreter2::Argument> {
     vec![
+        ftd::interpreter2::Argument::default(
+            "grid-area",
+            ftd::interpreter2::Kind::string()
+                .into_optional()
+                .into_kind_data(),
+        ),
+        ftd::interpreter2::Argument::default(
+            "grid-column",
+            ftd::interpreter2::Kind::string()
+                .into_optional()
+                .into_kind_data(),
+        ),
+        ftd::interpreter2::Argument::default(
+            "grid-row",
+            ftd::interpreter2::Kind::string()
+                .into_optional()
+                .into_kind_data(),
+        ),
         ftd::interpreter2::Argument::default(
             "sticky",
             ftd::interpreter2::Kind::boolean()
@@ -103,6 +103,7 @@ impl Function {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number,
+                        span: None,
                     })?;
             if !argument.mutable.eq(&function_value.is_mutable()) {
                 return ftd::interpreter2::utils::e2(
@@ -154,7 +155,7 @@ impl Function {
                     let value = ftd::interpreter2::Value::from_evalexpr_value(
                         evalexpr_context.get_value(key.as_str()).unwrap().clone(),
                         &context.kind,
-                        doc.name,
+                        doc,
                         line_number,
                     )?;
                     // TODO: insert new value in doc.bag
@@ -176,7 +177,7 @@ impl Function {
             return Ok(Some(ftd::interpreter2::Value::from_evalexpr_value(
                 eval,
                 &self.return_kind.kind,
-                doc.name,
+                doc,
                 line_number,
             )?));
         }
@@ -45,6 +45,27 @@ impl Kind {
         }
     }
 
+    /// The kind as it is written in a document, e.g. `string list`.
+    pub fn name(&self) -> String {
+        match self {
+            Kind::String => "string".to_string(),
+            Kind::Object => "object".to_string(),
+            Kind::Integer => "integer".to_string(),
+            Kind::Decimal => "decimal".to_string(),
+            Kind::Boolean => "boolean".to_string(),
+            Kind::Void => "void".to_string(),
+            Kind::Record { name } => name.to_string(),
+            Kind::OrType { name, variant, .. } => match variant {
+                Some(variant) => format!("{}.{}", name, variant),
+                None => name.to_string(),
+            },
+            Kind::List { kind } => format!("{} list", kind.name()),
+            Kind::Optional { kind } => format!("optional {}", kind.name()),
+            Kind::Constant { kind } => format!("constant {}", kind.name()),
+            Kind::UI { name, .. } => name.as_deref().unwrap_or("ftd.ui").to_string(),
+        }
+    }
+
     pub fn string() -> Kind {
         Kind::String
     }
@@ -316,6 +337,11 @@ impl KindData {
         line_number: usize,
     ) -> ftd::interpreter2::Result<()> {
         let ast_kind = var_kind.kind;
+        if ftd::ast::VariableModifier::get_modifier(ast_kind.as_str()).is_some() {
+            // the inner kind of a list of list, e.g. `integer list` of `integer list list`
+            let inner = ftd::ast::VariableKind::get_kind(ast_kind.as_str(), doc.name, line_number)?;
+            return KindData::scan_ast_kind(inner, known_kinds, doc, line_number);
+        }
         match ast_kind.as_ref() {
             "string" | "object" | "integer" | "decimal" | "boolean" | "void" | "ftd.ui"
             | "children" => Ok(()),
@@ -353,6 +379,24 @@ impl KindData {
 
             return Ok(ftd::interpreter2::StateWithThing::new_thing(kind_data));
         }
+        if ftd::ast::VariableModifier::get_modifier(ast_kind.as_str()).is_some() {
+            // the inner kind of a list of list, e.g. `integer list` of `integer list list`
+            let inner = ftd::ast::VariableKind::get_kind(ast_kind.as_str(), doc.name, line_number)?;
+            let mut kind_data = try_ok_state!(KindData::from_ast_kind(
+                inner,
+                known_kinds,
+                doc,
+                line_number
+            )?);
+            kind_data.caption = caption;
+            kind_data.body = body;
+
+            if let Some(ref modifier) = var_kind.modifier {
+                kind_data = kind_data.into_by_ast_modifier(modifier);
+            }
+
+            return Ok(ftd::interpreter2::StateWithThing::new_thing(kind_data));
+        }
         let kind = match ast_kind.as_ref() {
             "string" => Kind::string(),
             "object" => Kind::object(),
@@ -292,6 +292,7 @@ impl Field {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number,
+                        span: None,
                     })?;
 
                 check_variant_if_constant(or_variant, remaining, doc)?;
@@ -96,6 +96,7 @@ impl PropertyValue {
                         ),
                         doc_id: doc.name.to_string(),
                         line_number,
+                        span: None,
                     },
                 )
             }
@@ -115,6 +116,7 @@ impl PropertyValue {
             source,
             condition: None,
             line_number: self.line_number(),
+            span: Default::default(),
         }
     }
 
@@ -492,6 +494,7 @@ impl PropertyValue {
                                     ),
                                     doc_id: doc.name.to_string(),
                                     line_number,
+                                    span: None,
                                 })?
                                 .clone();
                             result_field.insert(field.name.to_string(), property_value);
@@ -657,6 +660,7 @@ impl PropertyValue {
             message: "Need expected kind".to_string(),
             doc_id: doc.name.to_string(),
             line_number: value.line_number(),
+            span: None,
         })?;
         return get_property_value(
             value,
@@ -753,6 +757,24 @@ impl PropertyValue {
                                 value.line_number(),
                             );
                         }
+                        // the key of an or-type item can name its variant, e.g.
+                        // `-- ftd.grid-track.fraction: 1`
+                        let item_kind = if kind.is_or_type() {
+                            let var_kind = ftd::ast::VariableKind::get_kind(
+                                key.as_str(),
+                                doc.name,
+                                value.line_number(),
+                            )?;
+                            try_ok_state!(ftd::interpreter2::KindData::from_ast_kind(
+                                var_kind,
+                                &Default::default(),
+                                doc,
+                                value.line_number(),
+                            )?)
+                            .kind
+                        } else {
+                            kind.as_ref().clone()
+                        };
                         values.push(if kind.is_ui() {
                             try_ok_state!(PropertyValue::to_ui_value(
                                 &key,
@@ -766,7 +788,7 @@ impl PropertyValue {
                                 doc,
                                 is_mutable,
                                 Some(&ftd::interpreter2::KindData {
-                                    kind: kind.as_ref().clone(),
+                                    kind: item_kind,
                                     caption: expected_kind.caption,
                                     body: expected_kind.body,
                                 }),
@@ -814,6 +836,7 @@ impl PropertyValue {
                                 ),
                                 doc_id: doc.name.to_string(),
                                 line_number: value.line_number(),
+                                span: None,
                             })?;
                         let value = match &variant {
                         ftd::interpreter2::OrTypeVariant::Constant(c) => return ftd::interpreter2::utils::e2(format!("Cannot pass constant variant as property, variant: `{}`. Help: Pass variant as value instead", c.name), doc.name, c.line_number),
@@ -899,6 +922,7 @@ impl PropertyValue {
                                     ),
                                     doc_id: doc.name.to_string(),
                                     line_number: constant.line_number,
+                                    span: None,
                                 })?;
 
                         ftd::interpreter2::StateWithThing::new_thing(
@@ -1002,12 +1026,7 @@ impl PropertyValue {
                         .map(Some));
                     }
                     Some(ekind) if !ekind.kind.is_same_as(&found_kind.kind) => {
-                        return ftd::interpreter2::utils::e2(
-                            format!("Expected kind `{:?}`, found: `{:?}`", ekind, found_kind)
-                                .as_str(),
-                            doc.name,
-                            value.line_number(),
-                        )
+                        doc.kind_mismatch(&ekind.kind, &found_kind.kind, value.line_number())?
                     }
                     _ => {}
                 }
@@ -1047,12 +1066,7 @@ impl PropertyValue {
                         .map(Some));
                     }
                     Some(ekind) if !ekind.kind.is_same_as(&found_kind.kind) => {
-                        return ftd::interpreter2::utils::e2(
-                            format!("Expected kind `{:?}`, found: `{:?}`", ekind, found_kind)
-                                .as_str(),
-                            doc.name,
-                            value.line_number(),
-                        )
+                        doc.kind_mismatch(&ekind.kind, &found_kind.kind, value.line_number())?
                     }
                     _ => {}
                 }
@@ -1098,12 +1112,7 @@ impl PropertyValue {
                         .map(Some));
                     }
                     Some(ekind) if !ekind.kind.is_same_as(&found_kind.kind) => {
-                        return ftd::interpreter2::utils::e2(
-                            format!("3 Expected kind `{:?}`, found: `{:?}`", ekind, found_kind)
-                                .as_str(),
-                            doc.name,
-                            value.line_number(),
-                        )
+                        doc.kind_mismatch(&ekind.kind, &found_kind.kind, value.line_number())?
                     }
                     _ => {}
                 }
@@ -1446,6 +1455,22 @@ impl Value {
                     ftd::evalexpr::Value::Empty
                 }
             }
+            // a record is a tuple of `(field-name, value)` pairs, so list functions like
+            // `ftd.sort_by` can get to its fields
+            Value::Record { fields, .. } => {
+                let mut values = vec![];
+                for (name, value) in fields {
+                    let v = value
+                        .clone()
+                        .resolve(doc, line_number)?
+                        .to_evalexpr_value(doc, value.line_number())?;
+                    values.push(ftd::evalexpr::Value::Tuple(vec![
+                        ftd::evalexpr::Value::String(name.to_string()),
+                        v,
+                    ]));
+                }
+                ftd::evalexpr::Value::Tuple(values)
+            }
             t => unimplemented!("{:?}", t),
         })
     }
@@ -1453,7 +1478,7 @@ impl Value {
     pub(crate) fn from_evalexpr_value(
         value: ftd::evalexpr::Value,
         expected_kind: &ftd::interpreter2::Kind,
-        doc_name: &str,
+        doc: &ftd::interpreter2::TDoc,
         line_number: usize,
     ) -> ftd::interpreter2::Result<Value> {
         Ok(match value {
@@ -1471,10 +1496,10 @@ impl Value {
             }
             ftd::evalexpr::Value::Tuple(data) if expected_kind.is_list() => {
                 let mut values = vec![];
-                let val_kind = expected_kind.list_type(doc_name, line_number)?;
+                let val_kind = expected_kind.list_type(doc.name, line_number)?;
                 for val in data {
                     values.push(ftd::interpreter2::PropertyValue::Value {
-                        value: Value::from_evalexpr_value(val, &val_kind, doc_name, line_number)?,
+                        value: Value::from_evalexpr_value(val, &val_kind, doc, line_number)?,
                         is_mutable: false,
                         line_number,
                     });
@@ -1484,14 +1509,70 @@ impl Value {
                     kind: ftd::interpreter2::KindData::new(val_kind),
                 }
             }
+            ftd::evalexpr::Value::Tuple(data) if expected_kind.is_record() => {
+                let name = expected_kind.get_record_name().unwrap();
+                let record = doc.get_record(name, line_number)?;
+                let mut fields: ftd::Map<ftd::interpreter2::PropertyValue> = Default::default();
+                for field in record.fields {
+                    let value = data.iter().find_map(|pair| match pair {
+                        ftd::evalexpr::Value::Tuple(pair) => match pair.as_slice() {
+                            [ftd::evalexpr::Value::String(key), value] if field.name.eq(key) => {
+                                Some(value.to_owned())
+                            }
+                            _ => None,
+                        },
+                        _ => None,
+                    });
+                    let value = match value {
+                        Some(value) => value,
+                        None if field.kind.is_optional() => ftd::evalexpr::Value::Empty,
+                        None => {
+                            return ftd::interpreter2::utils::e2(
+                                format!("Field `{}` not found in record `{}`", field.name, name),
+                                doc.name,
+                                line_number,
+                            )
+                        }
+                    };
+                    fields.insert(
+                        field.name.to_string(),
+                        ftd::interpreter2::PropertyValue::Value {
+                            value: Value::from_evalexpr_value(
+                                value,
+                                &field.kind.kind,
+                                doc,
+                                line_number,
+                            )?,
+                            is_mutable: false,
+                            line_number,
+                        },
+                    );
+                }
+                Value::Record {
+                    name: name.to_string(),
+                    fields,
+                }
+            }
             ftd::evalexpr::Value::Empty if expected_kind.is_optional() => Value::Optional {
                 data: Box::new(None),
                 kind: ftd::interpreter2::KindData::new(expected_kind.clone()),
             },
+            value if expected_kind.is_optional() => {
+                let kind = expected_kind.clone().inner();
+                Value::Optional {
+                    data: Box::new(Some(Value::from_evalexpr_value(
+                        value,
+                        &kind,
+                        doc,
+                        line_number,
+                    )?)),
+                    kind: ftd::interpreter2::KindData::new(kind),
+                }
+            }
             t => {
                 return ftd::interpreter2::utils::e2(
                     format!("Expected kind: `{:?}`, found: `{:?}`", expected_kind, t),
-                    doc_name,
+                    doc.name,
                     line_number,
                 )
             }
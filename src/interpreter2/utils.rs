@@ -41,6 +41,7 @@ where
         message: m.into(),
         doc_id: doc_id.to_string(),
         line_number,
+        span: None,
     })
 }
 
@@ -56,6 +57,7 @@ where
         message: message.into(),
         doc_id: doc_id.to_string(),
         line_number,
+        span: None,
     }
 }
 
@@ -176,6 +178,58 @@ pub fn split_at(text: &str, at: &str) -> (String, Option<String>) {
     }
 }
 
+/// Names of the variables `{$<variable>}` and of the components `{<component>: <caption>}` the
+/// markup in `text` refers to.
+pub(crate) fn markup_names(text: &str) -> (Vec<String>, Vec<String>) {
+    let mut references = vec![];
+    let mut components = vec![];
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '{' => {
+                let mut content = String::new();
+                let mut depth = 1;
+                for c in chars.by_ref() {
+                    match c {
+                        '{' => depth += 1,
+                        '}' if depth == 1 => break,
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    content.push(c);
+                }
+                let content = content.trim();
+                if let Some(reference) = content.strip_prefix('$') {
+                    references.push(reference.trim().to_string());
+                } else if let Some((name, _)) = content.split_once(':') {
+                    components.push(name.trim().to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    (references, components)
+}
+
+/// If `name` of `{<name>: <caption>}` in markup can be a component of `doc`: a plain name, or
+/// one of `ftd` or of a module `doc` imports. Anything else, like `{e.g.: ...}`, is text.
+pub(crate) fn is_markup_component_name(name: &str, doc: &ftd::interpreter2::TDoc) -> bool {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+    {
+        return false;
+    }
+    match name.split_once('.') {
+        Some((module, _)) => module.eq("ftd") || doc.aliases.contains_key(module),
+        None => true,
+    }
+}
+
 pub(crate) fn get_special_variable() -> Vec<&'static str> {
     vec![
         "MOUSE-IN",
@@ -415,6 +469,16 @@ pub(crate) fn get_value(
 
     Ok(match value {
         None => Some(serde_json::Value::Null),
+        Some(value) if ftd::executor::Background::is_shorthand_value(&value) => {
+            let (light, dark) = ftd::executor::Background::css_strings_from_value(&value, doc, 0)
+                .map_err(|e| ftd::interpreter2::Error::ParseError {
+                message: e.to_string(),
+                doc_id: doc.name.to_string(),
+                line_number: 0,
+                span: None,
+            })?;
+            Some(serde_json::json!({ "light": light, "dark": dark }))
+        }
         Some(ftd::interpreter2::Value::Boolean { value }) => serde_json::to_value(value).ok(),
         Some(ftd::interpreter2::Value::Integer { value }) => serde_json::to_value(value).ok(),
         Some(ftd::interpreter2::Value::String { text: value, .. }) => {
@@ -464,7 +528,7 @@ pub(crate) fn get_value(
                     }
                 }
                 Some(value)
-                    if name.eq(ftd::interpreter2::FTD_RESIZING_FIXED)
+                    if name.eq(ftd::interpreter2::FTD_RESIZING)
                         && variant.ne(ftd::interpreter2::FTD_RESIZING_FIXED) =>
                 {
                     if let Ok(pattern) = ftd::executor::Resizing::set_value_from_variant(
@@ -12,6 +12,7 @@ mod component;
 mod condition;
 mod constants;
 mod di;
+pub mod diagnostic;
 mod dnode;
 pub mod evalexpr;
 mod event;
@@ -21,6 +22,7 @@ mod html;
 pub mod html1;
 pub mod interpreter;
 pub mod interpreter2;
+pub mod lsp;
 pub mod markup;
 pub mod node;
 mod or_type;
@@ -73,6 +75,11 @@ pub fn build_js() -> &'static str {
     include_str!("../build.js")
 }
 
+/// Same as `build`, for `ftd::html1::StaticHtmlUI`, without `ftd.js` and the page data
+pub fn build_static() -> &'static str {
+    include_str!("../build-static.html")
+}
+
 // #[cfg(test)]
 pub type Map<T> = std::collections::BTreeMap<String, T>;
 
@@ -0,0 +1,320 @@
+/**
+ * Everything the language server knows about one open document.
+ *
+ * The document is parsed with [`ftd::p11::parse_with_recovery`], so all syntax errors are
+ * reported at once, and the sections that could be parsed are interpreted with
+ * [`ftd::interpreter2::InterpreterState`]. Unlike [`ftd::interpreter2::interpret`], every
+ * definition of the document is interpreted, not just the ones reachable from a component
+ * invocation, so unused records, components and variables are type checked and can be hovered.
+ *
+ * # Fields
+ *
+ * - `doc_id`: module name of the document, as it would appear in an `import`
+ * - `module`: uri and content of the document
+ * - `diagnostics`: errors found in the document, all of them located in `module.source`
+ * - `bag`: things resolved by the interpreter before it finished or stopped
+ * - `aliases`: aliases of the document, `ftd` and the imports
+ * - `modules`: imported modules, keyed by module name
+ *
+ * Interpretation stops at processors and foreign variables, as only the host of the document
+ * can resolve them; whatever was resolved before is kept in `bag`.
+ */
+#[derive(Debug, Default, Clone)]
+pub struct Analysis {
+    pub doc_id: String,
+    pub module: ftd::lsp::Module,
+    pub diagnostics: Vec<ftd::diagnostic::Diagnostic>,
+    pub bag: ftd::Map<ftd::interpreter2::Thing>,
+    pub aliases: ftd::Map<String>,
+    pub modules: ftd::Map<ftd::lsp::Module>,
+}
+
+impl Analysis {
+    /// `resolve` is called with the module name of every import, see [`ftd::lsp::directory`].
+    pub fn new<F>(doc_id: &str, module: ftd::lsp::Module, resolve: &F) -> Analysis
+    where
+        F: Fn(&str) -> Option<ftd::lsp::Module>,
+    {
+        let (sections, errors) = ftd::p11::parse_with_recovery(module.source.as_str(), doc_id);
+        let mut analysis = Analysis {
+            doc_id: doc_id.to_string(),
+            module,
+            ..Default::default()
+        };
+        for e in errors.iter() {
+            analysis.report(e.into());
+        }
+
+        match ftd::interpreter2::ParsedDocument::from_sections(doc_id, sections.as_slice()) {
+            Ok(document) => {
+                analysis.aliases = document.get_doc_aliases();
+                analysis.interpret(document, resolve);
+            }
+            Err(e) => analysis.report((&e).into()),
+        }
+        analysis
+    }
+
+    fn interpret<F>(&mut self, document: ftd::interpreter2::ParsedDocument, resolve: &F)
+    where
+        F: Fn(&str) -> Option<ftd::lsp::Module>,
+    {
+        use itertools::Itertools;
+
+        let mut state = ftd::interpreter2::InterpreterState::new(self.doc_id.to_string());
+        let asts = document
+            .ast
+            .iter()
+            .filter(|v| !v.is_import())
+            .map(|v| (0, v.to_owned()))
+            .collect_vec();
+        state.parsed_libs.insert(self.doc_id.to_string(), document);
+        state.to_process.stack.push((self.doc_id.to_string(), asts));
+
+        // `continue_*` consume the state, so a copy is kept to know what was resolved before
+        // an error.
+        let mut last_bag = state.bag.clone();
+        let mut result = state.continue_processing();
+        loop {
+            let interpreter = match result {
+                Ok(interpreter) => interpreter,
+                Err(e) => {
+                    self.report((&e).into());
+                    self.bag = last_bag;
+                    return;
+                }
+            };
+            match interpreter {
+                ftd::interpreter2::Interpreter::Done { document } => {
+                    self.bag = document.data;
+                    return;
+                }
+                ftd::interpreter2::Interpreter::StuckOnImport { module, state, .. } => {
+                    let source = match resolve(module.as_str()) {
+                        Some(m) => {
+                            let source = m.source.to_string();
+                            self.modules.insert(module.to_string(), m);
+                            source
+                        }
+                        None => {
+                            self.report_import(
+                                module.as_str(),
+                                format!("Cannot find module `{}`", module).as_str(),
+                            );
+                            self.bag = state.bag;
+                            return;
+                        }
+                    };
+                    match ftd::interpreter2::ParsedDocument::parse(module.as_str(), source.as_str())
+                    {
+                        Ok(document) => {
+                            last_bag = state.bag.clone();
+                            result = state.continue_after_import(
+                                module.as_str(),
+                                document,
+                                vec![],
+                                vec![],
+                                0,
+                            );
+                        }
+                        Err(e) => {
+                            self.report((&e).into());
+                            self.bag = state.bag;
+                            return;
+                        }
+                    }
+                }
+                ftd::interpreter2::Interpreter::StuckOnProcessor { state, .. }
+                | ftd::interpreter2::Interpreter::StuckOnForeignVariable { state, .. } => {
+                    self.bag = state.bag;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Adds `diagnostic`. Errors in imported modules are reported on the `import` line of the
+    /// module.
+    fn report(&mut self, diagnostic: ftd::diagnostic::Diagnostic) {
+        let doc_id = diagnostic
+            .doc_id
+            .as_deref()
+            .map(|v| v.split('#').next().unwrap_or(v))
+            .unwrap_or_default()
+            .to_string();
+        if doc_id.is_empty() || doc_id.eq(&self.doc_id) || !self.modules.contains_key(&doc_id) {
+            self.diagnostics.push(diagnostic);
+            return;
+        }
+        self.report_import(
+            doc_id.as_str(),
+            format!(
+                "{}:{}: {}",
+                doc_id,
+                diagnostic.line_number.unwrap_or_default(),
+                diagnostic.message
+            )
+            .as_str(),
+        );
+    }
+
+    fn report_import(&mut self, module: &str, message: &str) {
+        let line_number = self
+            .module
+            .source
+            .split('\n')
+            .position(|v| v.trim_start().starts_with("-- import:") && v.contains(module))
+            .map(|v| v + 1)
+            .unwrap_or(1);
+        self.diagnostics.push(ftd::diagnostic::Diagnostic::new(
+            message,
+            self.doc_id.as_str(),
+            line_number,
+            Some(ftd::p11::Span::locate_after(
+                self.module.source.as_str(),
+                line_number,
+                ":",
+                module,
+            )),
+        ));
+    }
+
+    /// The thing referred to by the word at `line` and `column` (both 1-based), if the
+    /// interpreter resolved it. For `$foo.bar` this is `foo`, unless `foo.bar` is a thing too.
+    pub fn thing_at(&self, line: usize, column: usize) -> Option<&ftd::interpreter2::Thing> {
+        let mut name = ftd::lsp::word_at(self.module.source.as_str(), line, column)?
+            .trim_start_matches(ftd::interpreter2::utils::CLONE)
+            .trim_start_matches(ftd::interpreter2::utils::REFERENCE);
+        loop {
+            if name.is_empty() {
+                return None;
+            }
+            let full_name =
+                ftd::interpreter2::utils::resolve_name(name, self.doc_id.as_str(), &self.aliases);
+            if let Some(thing) = self.bag.get(full_name.as_str()) {
+                return Some(thing);
+            }
+            name = name.rsplit_once('.')?.0;
+        }
+    }
+
+    /// Uri of the module defining the thing at `line` and `column`, and the span of its name
+    /// there. Things of the `ftd` module are not defined in any file.
+    pub fn definition(&self, line: usize, column: usize) -> Option<(&str, ftd::p11::Span)> {
+        let thing = self.thing_at(line, column)?;
+        let line_number = thing.line_number();
+        let full_name = thing.name();
+        let (doc_id, name) = full_name.split_once('#')?;
+        let module = if doc_id.eq(&self.doc_id) {
+            &self.module
+        } else {
+            self.modules.get(doc_id)?
+        };
+        if line_number == 0 {
+            return None;
+        }
+
+        let name = name.rsplit('.').next().unwrap_or(name);
+        let mut span = ftd::p11::Span::locate(
+            module.source.as_str(),
+            line_number,
+            format!("{}:", name).as_str(),
+        );
+        if span.length == name.chars().count() + 1 {
+            span.length -= 1;
+        }
+        Some((module.uri.as_str(), span))
+    }
+
+    /// Markdown describing the thing at `line` and `column`: its kind and name, and the
+    /// arguments or fields of components, functions and records.
+    pub fn hover(&self, line: usize, column: usize) -> Option<String> {
+        Some(format!(
+            "```ftd\n{}\n```",
+            describe(self.thing_at(line, column)?)
+        ))
+    }
+
+    /// Arguments of the component invoked by the section `line` (1-based) belongs to. Nothing
+    /// is returned on the section line itself, or if the section is not a component
+    /// invocation.
+    pub fn completion(&self, line: usize) -> Vec<&ftd::interpreter2::Argument> {
+        let lines: Vec<&str> = self.module.source.split('\n').collect();
+        if line == 0 || lines.get(line - 1).map_or(true, |v| is_section_line(v)) {
+            return vec![];
+        }
+        let section = match lines[..line - 1].iter().rev().find(|v| is_section_line(v)) {
+            Some(section) => section,
+            None => return vec![],
+        };
+        let name = match section
+            .trim()
+            .trim_start_matches("-- ")
+            .split_once(':')
+            .map(|v| v.0.trim())
+        {
+            Some(name) if !name.is_empty() && !name.contains(char::is_whitespace) => name,
+            _ => return vec![],
+        };
+
+        let full_name =
+            ftd::interpreter2::utils::resolve_name(name, self.doc_id.as_str(), &self.aliases);
+        match self.bag.get(full_name.as_str()) {
+            Some(ftd::interpreter2::Thing::Component(c)) => c.arguments.iter().collect(),
+            _ => vec![],
+        }
+    }
+}
+
+fn is_section_line(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with("-- ") && !line.starts_with("-- end:")
+}
+
+/// `kind name` of `thing`, followed by its arguments or fields.
+pub(crate) fn describe(thing: &ftd::interpreter2::Thing) -> String {
+    let fields = |fields: &[ftd::interpreter2::Field]| {
+        fields
+            .iter()
+            .map(|v| format!("\n{} {}", kind_data_name(&v.kind), v.name))
+            .collect::<String>()
+    };
+    match thing {
+        ftd::interpreter2::Thing::Record(r) => format!("record {}:{}", r.name, fields(&r.fields)),
+        ftd::interpreter2::Thing::OrType(o) => format!("or-type {}", o.name),
+        ftd::interpreter2::Thing::OrTypeWithVariant { or_type, variant } => {
+            format!("or-type {}.{}", or_type, variant.name())
+        }
+        ftd::interpreter2::Thing::Variable(v) => format!(
+            "{} {}{}",
+            kind_data_name(&v.kind),
+            if v.mutable { "$" } else { "" },
+            v.name
+        ),
+        ftd::interpreter2::Thing::Component(c) => {
+            format!("component {}:{}", c.name, fields(&c.arguments))
+        }
+        ftd::interpreter2::Thing::Function(f) => format!(
+            "{} {}:{}",
+            kind_data_name(&f.return_kind),
+            f.name,
+            fields(&f.arguments)
+        ),
+    }
+}
+
+/// The kind as it is written in a document, e.g. `optional caption string`.
+pub(crate) fn kind_data_name(kind: &ftd::interpreter2::KindData) -> String {
+    let modifier = match (kind.caption, kind.body) {
+        (true, true) => "caption or body ",
+        (true, false) => "caption ",
+        (false, true) => "body ",
+        (false, false) => "",
+    };
+    match kind.kind {
+        ftd::interpreter2::Kind::Optional { ref kind } => {
+            format!("optional {}{}", modifier, kind.name())
+        }
+        ref kind => format!("{}{}", modifier, kind.name()),
+    }
+}
@@ -0,0 +1,403 @@
+//! Language server for `.ftd` files, see `src/bin/ftd-lsp.rs`.
+//!
+//! [`Server`] speaks JSON-RPC over whatever transport the caller provides, and supports
+//! diagnostics, go-to-definition, hover and completion of component arguments. Imports are
+//! read through a callback, [`directory`] being the one used by the binary.
+//!
+//! Positions are exchanged in characters, not in UTF-16 code units as the protocol asks for;
+//! the two only differ for characters outside the basic multilingual plane.
+
+#[cfg(test)]
+#[macro_use]
+mod test;
+
+mod analysis;
+
+pub use analysis::Analysis;
+
+/// A document the server knows about: the uri it is reported with, and its content.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Module {
+    pub uri: String,
+    pub source: String,
+}
+
+/// Resolves module `foo/bar` to `{root}/foo/bar.ftd`, or `{root}/foo/bar/index.ftd`.
+pub fn directory(root: std::path::PathBuf) -> impl Fn(&str) -> Option<Module> {
+    move |module| {
+        [format!("{}.ftd", module), format!("{}/index.ftd", module)]
+            .iter()
+            .find_map(|name| {
+                let path = root.join(name);
+                std::fs::read_to_string(&path).ok().map(|source| Module {
+                    uri: path_to_uri(&path),
+                    source,
+                })
+            })
+    }
+}
+
+/// `file://` uri of `path`, the bytes other than the unreserved characters and `/` are
+/// percent-encoded, as editors do.
+pub fn path_to_uri(path: &std::path::Path) -> String {
+    let mut uri = "file://".to_string();
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(format!("%{:02X}", byte).as_str()),
+        }
+    }
+    uri
+}
+
+/// Path of a `file://` uri, percent-decoded. `None` for other schemes, or if the decoded path
+/// is not UTF-8.
+pub fn uri_to_path(uri: &str) -> Option<std::path::PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut path = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let byte = match encoded[i] {
+            b'%' => {
+                let hex = encoded.get(i + 1..i + 3)?;
+                if !hex.iter().all(u8::is_ascii_hexdigit) {
+                    return None;
+                }
+                i += 2;
+                u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?
+            }
+            byte => byte,
+        };
+        path.push(byte);
+        i += 1;
+    }
+    String::from_utf8(path).ok().map(std::path::PathBuf::from)
+}
+
+/// The identifier (`$foo.bar`, `ftd.text`, ...) at `line` and `column` (both 1-based) of
+/// `source`. The cursor may also be right after the identifier.
+pub fn word_at(source: &str, line: usize, column: usize) -> Option<&str> {
+    let is_word = |c: char| c.is_alphanumeric() || "-_.$*".contains(c);
+
+    let content = source.split('\n').nth(line.checked_sub(1)?)?;
+    let chars: Vec<(usize, char)> = content.char_indices().collect();
+    let mut idx = column.checked_sub(1)?.min(chars.len());
+    if chars.get(idx).map_or(true, |v| !is_word(v.1)) {
+        idx = idx.checked_sub(1)?;
+        if !is_word(chars.get(idx)?.1) {
+            return None;
+        }
+    }
+    let start = chars[..idx]
+        .iter()
+        .rposition(|v| !is_word(v.1))
+        .map(|v| v + 1)
+        .unwrap_or_default();
+    let end = chars[idx..]
+        .iter()
+        .position(|v| !is_word(v.1))
+        .map(|v| v + idx)
+        .unwrap_or(chars.len());
+
+    let start = chars[start].0;
+    let end = chars.get(end).map(|v| v.0).unwrap_or(content.len());
+    match content[start..end].trim_end_matches('.') {
+        "" => None,
+        word => Some(word),
+    }
+}
+
+/**
+ * State of the language server: the open documents, keyed by uri.
+ *
+ * Every message is passed to [`Server::handle`], which returns the messages to send back, the
+ * response to the request if any and the `textDocument/publishDiagnostics` notifications.
+ * Documents are synchronised in full on every change and are re-interpreted right away.
+ *
+ * # Fields
+ *
+ * - `root`: directory module names are relative to, `foo/bar.ftd` in it is module `foo/bar`
+ * - `resolve`: returns the module an import refers to, see [`directory`]
+ */
+pub struct Server<F> {
+    root: std::path::PathBuf,
+    resolve: F,
+    documents: ftd::Map<Analysis>,
+    shutdown: bool,
+    exit_code: Option<i32>,
+}
+
+impl<F> Server<F>
+where
+    F: Fn(&str) -> Option<Module>,
+{
+    pub fn new(root: std::path::PathBuf, resolve: F) -> Server<F> {
+        Server {
+            root,
+            resolve,
+            documents: Default::default(),
+            shutdown: false,
+            exit_code: None,
+        }
+    }
+
+    /// Set once the `exit` notification is received: `0` if it was preceded by `shutdown`,
+    /// `1` otherwise.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    pub fn analysis(&self, uri: &str) -> Option<&Analysis> {
+        self.documents.get(uri)
+    }
+
+    pub fn handle(&mut self, message: serde_json::Value) -> Vec<serde_json::Value> {
+        let id = message.get("id").cloned();
+        let params = message
+            .get("params")
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+        let method = match message.get("method").and_then(|v| v.as_str()) {
+            Some(method) => method,
+            // a response to a request of ours, we do not send any
+            None => return vec![],
+        };
+
+        match method {
+            "initialize" => vec![response(
+                id,
+                serde_json::json!({
+                    "capabilities": {
+                        "textDocumentSync": 1,
+                        "definitionProvider": true,
+                        "hoverProvider": true,
+                        "completionProvider": {},
+                    },
+                    "serverInfo": { "name": "ftd-lsp" },
+                }),
+            )],
+            "shutdown" => {
+                self.shutdown = true;
+                vec![response(id, serde_json::Value::Null)]
+            }
+            "exit" => {
+                self.exit_code = Some(if self.shutdown { 0 } else { 1 });
+                vec![]
+            }
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                match (document["uri"].as_str(), document["text"].as_str()) {
+                    (Some(uri), Some(text)) => vec![self.update(uri, text)],
+                    _ => vec![],
+                }
+            }
+            "textDocument/didChange" => {
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|v| v.last())
+                    .and_then(|v| v["text"].as_str());
+                match (params["textDocument"]["uri"].as_str(), text) {
+                    (Some(uri), Some(text)) => vec![self.update(uri, text)],
+                    _ => vec![],
+                }
+            }
+            "textDocument/didClose" => match params["textDocument"]["uri"].as_str() {
+                Some(uri) => {
+                    self.documents.remove(uri);
+                    vec![publish_diagnostics(uri, vec![])]
+                }
+                None => vec![],
+            },
+            "textDocument/definition" => {
+                let result = self
+                    .position(&params)
+                    .and_then(|(a, line, column)| a.definition(line, column))
+                    .map(|(uri, span)| serde_json::json!({ "uri": uri, "range": range(span) }));
+                vec![response(id, result.unwrap_or(serde_json::Value::Null))]
+            }
+            "textDocument/hover" => {
+                let result = self
+                    .position(&params)
+                    .and_then(|(a, line, column)| a.hover(line, column))
+                    .map(|value| {
+                        serde_json::json!({
+                            "contents": { "kind": "markdown", "value": value }
+                        })
+                    });
+                vec![response(id, result.unwrap_or(serde_json::Value::Null))]
+            }
+            "textDocument/completion" => {
+                let items: Vec<serde_json::Value> = self
+                    .position(&params)
+                    .map(|(a, line, _)| a.completion(line))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|v| {
+                        serde_json::json!({
+                            "label": v.name,
+                            // CompletionItemKind.Field
+                            "kind": 5,
+                            "detail": analysis::kind_data_name(&v.kind),
+                        })
+                    })
+                    .collect();
+                vec![response(id, serde_json::Value::Array(items))]
+            }
+            _ => match id {
+                Some(id) => vec![serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        // MethodNotFound
+                        "code": -32601,
+                        "message": format!("unsupported method: {}", method),
+                    },
+                })],
+                None => vec![],
+            },
+        }
+    }
+
+    /// Module name of the document at `uri`: its path relative to `root`, without the `.ftd`
+    /// extension.
+    pub fn doc_id(&self, uri: &str) -> String {
+        let path = uri_to_path(uri).unwrap_or_else(|| std::path::PathBuf::from(uri));
+        let path = path.strip_prefix(&self.root).unwrap_or(&path);
+        path.with_extension("")
+            .to_string_lossy()
+            .trim_start_matches('/')
+            .to_string()
+    }
+
+    fn update(&mut self, uri: &str, text: &str) -> serde_json::Value {
+        let doc_id = self.doc_id(uri);
+        let analysis = Analysis::new(
+            doc_id.as_str(),
+            Module {
+                uri: uri.to_string(),
+                source: text.to_string(),
+            },
+            &self.resolve,
+        );
+        let diagnostics = analysis
+            .diagnostics
+            .iter()
+            .map(|v| diagnostic(v, text))
+            .collect();
+        self.documents.insert(uri.to_string(), analysis);
+        publish_diagnostics(uri, diagnostics)
+    }
+
+    /// The document and 1-based line and column of `TextDocumentPositionParams`.
+    fn position(&self, params: &serde_json::Value) -> Option<(&Analysis, usize, usize)> {
+        let analysis = self
+            .documents
+            .get(params["textDocument"]["uri"].as_str()?)?;
+        let line = params["position"]["line"].as_u64()? as usize + 1;
+        let column = params["position"]["character"].as_u64()? as usize + 1;
+        Some((analysis, line, column))
+    }
+}
+
+fn response(id: Option<serde_json::Value>, result: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id.unwrap_or(serde_json::Value::Null),
+        "result": result,
+    })
+}
+
+/// The response to a message that could not be read, the server keeps reading after it.
+pub fn parse_error(error: &std::io::Error) -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": serde_json::Value::Null,
+        "error": {
+            // ParseError
+            "code": -32700,
+            "message": error.to_string(),
+        },
+    })
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<serde_json::Value>) -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn range(span: ftd::p11::Span) -> serde_json::Value {
+    let line = span.line.saturating_sub(1);
+    let character = span.column.saturating_sub(1);
+    serde_json::json!({
+        "start": { "line": line, "character": character },
+        "end": { "line": line, "character": character + span.length },
+    })
+}
+
+fn diagnostic(d: &ftd::diagnostic::Diagnostic, source: &str) -> serde_json::Value {
+    let span = match (d.span, d.line_number) {
+        (Some(span), _) if !span.is_empty() => span,
+        (_, Some(line_number)) if line_number > 0 => ftd::p11::Span::line(source, line_number),
+        _ => ftd::p11::Span::new(0, 1, 1, 0),
+    };
+    serde_json::json!({
+        "range": range(span),
+        // DiagnosticSeverity.Error or DiagnosticSeverity.Warning
+        "severity": match d.level {
+            ftd::diagnostic::Level::Error => 1,
+            ftd::diagnostic::Level::Warning => 2,
+        },
+        "source": "ftd",
+        "message": d.message,
+    })
+}
+
+/// Reads one `Content-Length` framed message. Returns `None` at the end of `reader`.
+pub fn read_message<R: std::io::BufRead>(
+    reader: &mut R,
+) -> std::io::Result<Option<serde_json::Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let content_length = content_length.ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, "missing Content-Length")
+    })?;
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+pub fn write_message<W: std::io::Write>(
+    writer: &mut W,
+    message: &serde_json::Value,
+) -> std::io::Result<()> {
+    let content = message.to_string();
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    writer.flush()
+}
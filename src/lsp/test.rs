@@ -0,0 +1,261 @@
+use pretty_assertions::assert_eq; // macro
+
+/// Fresh directory with `files` in it, removed by the caller once done.
+fn temp_dir(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("ftd-lsp-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    for (file, content) in files {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn analyse(dir: &std::path::Path, source: &str) -> super::Analysis {
+    super::Analysis::new(
+        "index",
+        super::Module {
+            uri: super::path_to_uri(&dir.join("index.ftd")),
+            source: source.to_string(),
+        },
+        &super::directory(dir.to_path_buf()),
+    )
+}
+
+const LIB: &str = indoc::indoc!(
+    "
+    -- component card:
+    caption title:
+
+    -- ftd.text: $card.title
+
+    -- end: card
+    "
+);
+
+const INDEX: &str = indoc::indoc!(
+    "
+    -- import: lib
+
+    -- integer x: 10
+
+    -- lib.card: Hello
+
+    -- ftd.text: World
+    "
+);
+
+#[test]
+fn word_at() {
+    let source = "-- lib.card: Hello\npadding.px: $x\n";
+    assert_eq!(super::word_at(source, 1, 4), Some("lib.card"));
+    assert_eq!(super::word_at(source, 1, 12), Some("lib.card"));
+    assert_eq!(super::word_at(source, 1, 13), None);
+    assert_eq!(super::word_at(source, 2, 15), Some("$x"));
+    assert_eq!(super::word_at(source, 3, 1), None);
+}
+
+#[test]
+fn uri() {
+    let path = std::path::Path::new("/my docs/café/index.ftd");
+    let uri = super::path_to_uri(path);
+    assert_eq!(uri, "file:///my%20docs/caf%C3%A9/index.ftd");
+    assert_eq!(super::uri_to_path(&uri).as_deref(), Some(path));
+    assert_eq!(super::uri_to_path("file:///a%2"), None);
+    assert_eq!(super::uri_to_path("untitled:Untitled-1"), None);
+}
+
+#[test]
+fn diagnostics() {
+    let dir = temp_dir("diagnostics", &[]);
+
+    let analysis = analyse(
+        &dir,
+        indoc::indoc!(
+            "
+            -- import: missing
+
+            -- missing.card: Hello
+            "
+        ),
+    );
+    assert_eq!(
+        analysis.diagnostics,
+        vec![ftd::diagnostic::Diagnostic::new(
+            "Cannot find module `missing`",
+            "index",
+            1,
+            Some(ftd::p11::Span::new(11, 1, 12, 7)),
+        )]
+    );
+
+    let analysis = analyse(
+        &dir,
+        indoc::indoc!(
+            "
+            -- ftd.text: Hello
+
+            -- ftd.column
+            padding: 10
+
+            -- ftd.text: $missing
+            "
+        ),
+    );
+    assert_eq!(
+        analysis
+            .diagnostics
+            .iter()
+            .map(|v| v.line_number)
+            .collect::<Vec<_>>(),
+        vec![Some(3), Some(6)]
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn definition() {
+    let dir = temp_dir("definition", &[("lib.ftd", LIB)]);
+    let analysis = analyse(&dir, INDEX);
+    assert_eq!(analysis.diagnostics, vec![]);
+
+    let lib = super::path_to_uri(&dir.join("lib.ftd"));
+    assert_eq!(
+        analysis.definition(5, 8),
+        Some((lib.as_str(), ftd::p11::Span::new(13, 1, 14, 4)))
+    );
+    assert_eq!(
+        analysis.definition(3, 12),
+        Some((
+            analysis.module.uri.as_str(),
+            ftd::p11::Span::new(27, 3, 12, 1)
+        ))
+    );
+    // `ftd.text` is not defined in any file
+    assert_eq!(analysis.definition(7, 8), None);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn hover() {
+    let dir = temp_dir("hover", &[("lib.ftd", LIB)]);
+    let analysis = analyse(&dir, INDEX);
+
+    assert_eq!(
+        analysis.hover(3, 12),
+        Some("```ftd\ninteger index#x\n```".to_string())
+    );
+    assert_eq!(
+        analysis.hover(5, 8),
+        Some("```ftd\ncomponent lib#card:\ncaption string title\n```".to_string())
+    );
+    assert_eq!(analysis.hover(5, 14), None);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn completion() {
+    let dir = temp_dir("completion", &[("lib.ftd", LIB)]);
+    let analysis = analyse(&dir, INDEX);
+
+    let names = |line| {
+        analysis
+            .completion(line)
+            .iter()
+            .map(|v| v.name.to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(6), vec!["title".to_string()]);
+    assert_eq!(names(5), Vec::<String>::new());
+    assert_eq!(names(4), Vec::<String>::new());
+    assert!(names(8).contains(&"text".to_string()));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn server() {
+    // a space, percent-encoded in the uris
+    let dir = temp_dir("my server", &[("lib.ftd", LIB)]);
+    let uri = super::path_to_uri(&dir.join("index.ftd"));
+    let mut server = super::Server::new(dir.clone(), super::directory(dir.clone()));
+
+    let mut input = vec![];
+    for message in [
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": {"uri": uri, "languageId": "ftd", "version": 1, "text": INDEX}
+            }
+        }),
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "textDocument/definition",
+            "params": {"textDocument": {"uri": uri}, "position": {"line": 4, "character": 7}}
+        }),
+    ] {
+        super::write_message(&mut input, &message).unwrap();
+    }
+
+    let mut reader = std::io::BufReader::new(input.as_slice());
+    let mut output = vec![];
+    while let Some(message) = super::read_message(&mut reader).unwrap() {
+        output.extend(server.handle(message));
+    }
+
+    assert_eq!(output.len(), 3);
+    assert_eq!(output[0]["id"], 1);
+    assert_eq!(output[0]["result"]["capabilities"]["hoverProvider"], true);
+    assert_eq!(
+        output[1],
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {"uri": uri, "diagnostics": []},
+        })
+    );
+    assert_eq!(
+        output[2]["result"],
+        serde_json::json!({
+            "uri": super::path_to_uri(&dir.join("lib.ftd")),
+            "range": {
+                "start": {"line": 0, "character": 13},
+                "end": {"line": 0, "character": 17},
+            },
+        })
+    );
+    assert_eq!(server.doc_id(uri.as_str()), "index");
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn malformed_message() {
+    let mut input = b"Content-Length: 5\r\n\r\n{oops".to_vec();
+    super::write_message(
+        &mut input,
+        &serde_json::json!({"jsonrpc": "2.0", "method": "exit"}),
+    )
+    .unwrap();
+
+    let mut reader = std::io::BufReader::new(input.as_slice());
+    let error = super::read_message(&mut reader).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    let response = super::parse_error(&error);
+    assert_eq!(response["id"], serde_json::Value::Null);
+    assert_eq!(response["error"]["code"], -32700);
+    // the malformed message is skipped
+    assert_eq!(
+        super::read_message(&mut reader).unwrap().unwrap()["method"],
+        "exit"
+    );
+    assert!(super::read_message(&mut reader).unwrap().is_none());
+}
@@ -1,75 +1,592 @@
-#[allow(unreachable_code)]
-#[allow(dead_code)]
-fn t() {
-    // The returned nodes are created in the supplied Arena, and are bound by its lifetime.
-    let arena = comrak::Arena::new();
-
-    let root = comrak::parse_document(
-        &arena,
-        "This is my input.\n\n1. Also my input.\n2. Certainly my input.\n",
-        &comrak::ComrakOptions::default(),
-    );
+const USAGE: &str = indoc::indoc!(
+    "
+    usage: ftd <command> [options]
+
+    commands:
+        render <file> [-o <file>]               render a document to html, on stdout unless
+               [--data-report] [--static]       `-o` is given, `--data-report` prints the
+                                                size of the page data of each module, and
+                                                of what was left out of it, on stderr,
+                                                `--static` renders it without javascript and
+                                                warns about the interactions that are lost
+        check <file or directory>...            interpret, type check, lint and render
+                                                documents, report all errors and warnings
+        ast <file> [--json]                     print the ast of a document
+        bench <file or directory>...            time the interpretation and rendering of
+                                                documents
+        fmt [--check] <file or directory>...    format documents in place, see `ftd::p11::format`
+        examples [<name>]                       render ./examples and ./t/html to ./docs
+
+    options:
+        -I, --include <directory>               also look for imported modules in this
+                                                directory
+
+    Module `foo/bar` is read from `foo/bar.ftd` or `foo/bar/index.ftd`, looked up in the
+    directory of the document (or the directory given to `check` and `bench`), then in the
+    `--include` directories, then in the directories listed in $FTD_PATH.
+
+    Exits with 1 if a document has errors, 2 if the command line is invalid.
+    "
+);
+
+pub fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let rest = args.get(1..).unwrap_or_default();
+    let code = match args.first().map(|v| v.as_str()) {
+        Some("render") => render(rest),
+        Some("check") => check(rest),
+        Some("ast") => ast(rest),
+        Some("bench") => bench(rest),
+        Some("fmt") => fmt(rest),
+        Some("examples") => examples(rest.first()),
+        Some("help") | Some("--help") | Some("-h") => {
+            print!("{}", USAGE);
+            0
+        }
+        Some(command) => usage(format!("unknown command `{}`", command).as_str()),
+        None => usage("no command given"),
+    };
+    std::process::exit(code);
+}
+
+fn usage(message: &str) -> i32 {
+    eprint!("error: {}\n\n{}", message, USAGE);
+    2
+}
+
+/// Command line options, the ones a command accepts are listed by it, see [`Options::parse`].
+#[derive(Debug, Default)]
+struct Options {
+    /// arguments that are not options: files and directories
+    paths: Vec<std::path::PathBuf>,
+    output: Option<std::path::PathBuf>,
+    include: Vec<std::path::PathBuf>,
+    json: bool,
+    check: bool,
+    data_report: bool,
+    static_html: bool,
+}
 
-    dbg!(root);
-    return;
+impl Options {
+    /// `flags` are the long names of the options the command accepts.
+    fn parse(args: &[String], flags: &[&str]) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let flag = match arg.as_str() {
+                "-o" => "--output",
+                "-I" => "--include",
+                v if v.starts_with('-') => v,
+                _ => {
+                    options.paths.push(arg.into());
+                    continue;
+                }
+            };
+            if !flags.contains(&flag) {
+                return Err(format!("unexpected option `{}`", arg));
+            }
+            match flag {
+                "--json" => options.json = true,
+                "--check" => options.check = true,
+                "--data-report" => options.data_report = true,
+                "--static" => options.static_html = true,
+                _ => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("`{}` needs a value", arg))?;
+                    if flag.eq("--output") {
+                        options.output = Some(value.into());
+                    } else {
+                        options.include.push(value.into());
+                    }
+                }
+            }
+        }
+        Ok(options)
+    }
 
-    fn iter_nodes<'a, F>(node: &'a comrak::nodes::AstNode<'a>, f: &F)
-    where
-        F: Fn(&'a comrak::nodes::AstNode<'a>),
-    {
-        f(node);
-        for c in node.children() {
-            iter_nodes(c, f);
+    /// Directories imported modules are looked up in, for documents in `root`.
+    fn search_path(&self, root: &std::path::Path) -> Vec<std::path::PathBuf> {
+        let mut search_path = vec![root.to_path_buf()];
+        search_path.extend(self.include.iter().cloned());
+        if let Some(ftd_path) = std::env::var_os("FTD_PATH") {
+            search_path.extend(std::env::split_paths(&ftd_path));
         }
+        search_path
     }
+}
 
-    iter_nodes(root, &|node| {
-        dbg!(root);
-        dbg!(node);
-        // match &mut node.data.borrow_mut().value {
-        //     &mut NodeValue::Text(ref mut text) => {
-        //         let orig = std::mem::replace(text, vec![]);
-        //         *text = String::from_utf8(orig).unwrap().replace("my", "your").as_bytes().to_vec();
-        //     }
-        //     _ => (),
-        // }
-    });
+/// `.ftd` files found in `path`, recursively. The error is the one of a directory that cannot
+/// be read, to be reported with [`usage`].
+fn ftd_files(path: &std::path::Path, files: &mut Vec<std::path::PathBuf>) -> Result<(), String> {
+    if path.is_dir() {
+        let mut entries: Vec<_> = std::fs::read_dir(path)
+            .map_err(|e| format!("cannot read directory `{}`: {}", path.display(), e))?
+            .filter_map(|v| v.ok().map(|v| v.path()))
+            .collect();
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() || entry.extension().map_or(false, |v| v == "ftd") {
+                ftd_files(&entry, files)?;
+            }
+        }
+    } else {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
 }
 
-pub fn main() {
-    // t();
-    // return;
+/// The documents of `paths`, each with the directory its module name is relative to: the
+/// directory given, or the directory of the file given.
+fn documents(
+    paths: &[std::path::PathBuf],
+) -> Result<Vec<(std::path::PathBuf, std::path::PathBuf)>, String> {
+    let mut documents = vec![];
+    for path in paths {
+        let root = if path.is_dir() {
+            path.to_path_buf()
+        } else {
+            path.parent().map(|v| v.to_path_buf()).unwrap_or_default()
+        };
+        let mut files = vec![];
+        ftd_files(path, &mut files)?;
+        documents.extend(files.into_iter().map(|v| (root.clone(), v)));
+    }
+    Ok(documents)
+}
+
+/**
+ * Interprets documents, reading the modules they import from a search path, see
+ * [`ftd::interpreter2::Directory`].
+ *
+ * Processors and foreign variables can only be resolved by a host that knows about them, the
+ * command line tool does not: documents using them are reported as errors.
+ *
+ * # Fields
+ *
+ * - `directory`: the modules imported so far and where they were read from
+ * - `documents`: path and content of the documents given on the command line, by module name
+ */
+struct Loader {
+    directory: ftd::interpreter2::Directory,
+    documents: ftd::Map<(std::path::PathBuf, String)>,
+}
+
+impl Loader {
+    fn new(search_path: Vec<std::path::PathBuf>) -> Loader {
+        Loader {
+            directory: ftd::interpreter2::Directory::new(search_path),
+            documents: Default::default(),
+        }
+    }
+
+    /// Reads the document at `path`, its module name is its path relative to `root`, without
+    /// the extension.
+    fn read(
+        &mut self,
+        root: &std::path::Path,
+        path: &std::path::Path,
+    ) -> Result<String, ftd::diagnostic::Diagnostic> {
+        let doc_id = path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .with_extension("")
+            .to_string_lossy()
+            .to_string();
+        let source = std::fs::read_to_string(path).map_err(|e| ftd::diagnostic::Diagnostic {
+            message: format!("{}: {}", path.display(), e),
+            ..Default::default()
+        })?;
+        self.documents
+            .insert(doc_id.to_string(), (path.to_path_buf(), source));
+        Ok(doc_id)
+    }
+
+    fn source(&self, doc_id: &str) -> &str {
+        self.documents
+            .get(doc_id)
+            .map(|v| v.1.as_str())
+            .unwrap_or_default()
+    }
+
+    /// Interprets document `doc_id`, which must have been [`Loader::read`] already.
+    fn interpret(
+        &mut self,
+        doc_id: &str,
+    ) -> Result<ftd::interpreter2::Document, ftd::diagnostic::Diagnostic> {
+        let source = self
+            .documents
+            .get(doc_id)
+            .map(|v| v.1.as_str())
+            .unwrap_or_default();
+        ftd::interpreter2::interpret_with_host(doc_id, source, &mut self.directory)
+            .map_err(|e| (&e).into())
+    }
 
-    let id = std::env::args().nth(1);
+    /// Same as [`Loader::interpret`], keeping the kind errors in the document for
+    /// `ftd::interpreter2::check` instead of stopping at the first one.
+    fn interpret_to_check(
+        &mut self,
+        doc_id: &str,
+    ) -> Result<ftd::interpreter2::Document, ftd::diagnostic::Diagnostic> {
+        let source = self.source(doc_id);
+        ftd::interpreter2::interpret_to_check(doc_id, source)
+            .and_then(|interpreter| {
+                ftd::interpreter2::continue_with_host(interpreter, &mut self.directory)
+            })
+            .map_err(|e| (&e).into())
+    }
 
-    if id.is_some() && id.as_ref().unwrap().eq("bm") {
-        use std::io::Write;
+    /// Renders `diagnostic` with the line of the document it refers to, located by path.
+    fn report(&self, diagnostic: &ftd::diagnostic::Diagnostic) -> String {
+        let doc_id = diagnostic
+            .doc_id
+            .as_deref()
+            .map(|v| v.split('#').next().unwrap_or(v))
+            .unwrap_or_default();
+        let document = self.documents.get(doc_id).cloned().or_else(|| {
+            let path = self.directory.loaded.get(doc_id)?;
+            let source = std::fs::read_to_string(path).ok()?;
+            Some((path.to_path_buf(), source))
+        });
+        match document {
+            Some((path, source)) => ftd::diagnostic::Diagnostic {
+                doc_id: Some(path.display().to_string()),
+                ..diagnostic.clone()
+            }
+            .render(source.as_str()),
+            None => diagnostic.render(""),
+        }
+    }
+}
+
+/// The html page of `document`: `build.html` with the rendered document, its data and the
+/// javascript it needs filled in.
+fn html(
+    document: ftd::interpreter2::Document,
+    extra_css: &str,
+) -> Result<String, ftd::diagnostic::Diagnostic> {
+    html_with_report(document, extra_css).map(|v| v.0)
+}
 
-        let mut log = "".to_string();
-        let benchmark_dir = std::path::Path::new("./benchmark-2022/");
-        for entry in std::fs::read_dir(benchmark_dir)
-            .unwrap_or_else(|_| panic!("{:?} is not a directory", benchmark_dir.to_str()))
+/// Same as `html`, with the size of the page data of each module, the data only has the
+/// variables the page can read or mutate, see `ftd::html1::HtmlUI::from_node_data_pruned`.
+fn html_with_report(
+    document: ftd::interpreter2::Document,
+    extra_css: &str,
+) -> Result<(String, ftd::html1::DataReport), ftd::diagnostic::Diagnostic> {
+    let executor = ftd::executor::ExecuteDoc::from_interpreter(document)
+        .map_err(|e| ftd::diagnostic::Diagnostic::from(&e))?;
+    let node = ftd::node::NodeData::from_rt(executor);
+    let html_ui = ftd::html1::HtmlUI::from_node_data_pruned(node, "main")
+        .map_err(|e| ftd::diagnostic::Diagnostic::from(&e))?;
+    let html = ftd::html1::utils::trim_all_lines(
+        ftd::build()
+            .replace("__ftd_doc_title__", "")
+            .replace("__ftd_data__", html_ui.variables.as_str())
+            .replace("__ftd_external_children__", "{}")
+            .replace("__ftd__", html_ui.html.as_str())
+            .replace("__ftd_js__", ftd::build_js())
+            .replace("__extra_js__", "")
+            .replace("__base_url__", "/")
+            .replace("__extra_css__", extra_css)
+            .replace(
+                "__ftd_functions__",
+                format!(
+                    "{}\n{}\n{}",
+                    html_ui.functions.as_str(),
+                    html_ui.dependencies.as_str(),
+                    html_ui.variable_dependencies.as_str()
+                )
+                .as_str(),
+            )
+            .replace("__ftd_body_events__", html_ui.outer_events.as_str())
+            .replace("__ftd_css__", "")
+            .replace("__ftd_element_css__", "")
+            .as_str(),
+    );
+    Ok((html, html_ui.data_report))
+}
+
+/// Same as `html`, but without `ftd.js`, see `ftd::html1::StaticHtmlUI`, with the warnings
+/// about the interactions of the document that are lost.
+fn static_html(
+    document: ftd::interpreter2::Document,
+    extra_css: &str,
+) -> Result<(String, Vec<ftd::diagnostic::Diagnostic>), ftd::diagnostic::Diagnostic> {
+    let executor = ftd::executor::ExecuteDoc::from_interpreter(document)
+        .map_err(|e| ftd::diagnostic::Diagnostic::from(&e))?;
+    let node = ftd::node::NodeData::from_rt(executor);
+    let static_ui = ftd::html1::StaticHtmlUI::from_node_data(node, "main")
+        .map_err(|e| ftd::diagnostic::Diagnostic::from(&e))?;
+    let html = ftd::html1::utils::trim_all_lines(
+        ftd::build_static()
+            .replace("__ftd_doc_title__", "")
+            .replace("__ftd__", static_ui.html.as_str())
+            .replace("__base_url__", "/")
+            .replace("__extra_css__", extra_css)
+            .replace("__ftd_css__", static_ui.css.as_str())
+            .as_str(),
+    );
+    Ok((html, static_ui.warnings))
+}
+
+/// `ftd render <file> [-o <file>] [-I <directory>]... [--data-report] [--static]`
+fn render(args: &[String]) -> i32 {
+    let flags = ["--output", "--include", "--data-report", "--static"];
+    let options = match Options::parse(args, &flags) {
+        Ok(options) if options.data_report && options.static_html => {
+            return usage("a page rendered with `--static` has no data for `--data-report`")
+        }
+        Ok(options) if options.paths.len() == 1 => options,
+        Ok(_) => return usage("`render` takes exactly one file"),
+        Err(e) => return usage(e.as_str()),
+    };
+    let path = options.paths[0].as_path();
+    let root = path.parent().unwrap_or_else(|| std::path::Path::new(""));
+    let mut loader = Loader::new(options.search_path(root));
+
+    let html = match loader
+        .read(root, path)
+        .and_then(|doc_id| loader.interpret(doc_id.as_str()))
+        .and_then(|document| {
+            if options.static_html {
+                return static_html(document, "");
+            }
+            let (html, data_report) = html_with_report(document, "")?;
+            if options.data_report {
+                eprint!("{}", data_report);
+            }
+            Ok((html, vec![]))
+        }) {
+        Ok((html, warnings)) => {
+            for warning in warnings.iter() {
+                eprintln!("{}\n", loader.report(warning));
+            }
+            html
+        }
+        Err(e) => {
+            eprintln!("{}", loader.report(&e));
+            return 1;
+        }
+    };
+    let written = match options.output {
+        Some(ref output) => std::fs::write(output, html),
+        None => {
+            use std::io::Write;
+            std::io::stdout().write_all(html.as_bytes())
+        }
+    };
+    if let Err(e) = written {
+        eprintln!("error: {}", e);
+        return 1;
+    }
+    0
+}
+
+/// `ftd check <file or directory>... [-I <directory>]...`: type checks the documents with
+/// `ftd::interpreter2::check`, lints them with `ftd::interpreter2::lint` and renders them
+/// without writing them, and reports the errors and warnings of all of them.
+fn check(args: &[String]) -> i32 {
+    let options = match Options::parse(args, &["--include"]) {
+        Ok(options) if !options.paths.is_empty() => options,
+        Ok(_) => return usage("`check` needs a file or directory"),
+        Err(e) => return usage(e.as_str()),
+    };
+
+    let documents = match documents(options.paths.as_slice()) {
+        Ok(documents) => documents,
+        Err(e) => return usage(e.as_str()),
+    };
+    let mut failed = 0;
+    let mut warnings = 0;
+    for (root, path) in documents.iter() {
+        let mut loader = Loader::new(options.search_path(root));
+        let diagnostics = match loader.read(root, path).and_then(|doc_id| {
+            let document = loader.interpret_to_check(doc_id.as_str())?;
+            Ok((doc_id, document))
+        }) {
+            Ok((doc_id, document)) => diagnostics(document, loader.source(doc_id.as_str())),
+            Err(e) => vec![e],
+        };
+        for d in diagnostics.iter() {
+            eprintln!("{}\n", loader.report(d));
+        }
+        if diagnostics
+            .iter()
+            .any(|v| v.level == ftd::diagnostic::Level::Error)
         {
-            let path = entry.expect("no files inside ./benchmark-2022").path();
-            let source = path
-                .to_str()
-                .unwrap_or_else(|| panic!("Path {:?} cannot be convert to string", path));
-            let split: Vec<_> = source.split('/').collect();
-            let id = split.last().expect("Filename should be present");
-            if id.contains(".ftd") {
-                let start = std::time::Instant::now();
-                log = format!("{}Processing: {} ... ", log, id);
-                let doc = std::fs::read_to_string(source).expect("cant read file");
-                ftd_v2_write(id, doc.as_str());
-                log = format!("{}Done {:?}\n", log, start.elapsed());
+            failed += 1;
+        }
+        warnings += diagnostics
+            .iter()
+            .filter(|v| v.level == ftd::diagnostic::Level::Warning)
+            .count();
+    }
+    eprintln!(
+        "checked {} documents, {} with errors, {} warnings",
+        documents.len(),
+        failed,
+        warnings
+    );
+    if failed > 0 {
+        return 1;
+    }
+    0
+}
+
+/// The type errors of `document`, the interpreted `source`, or else its lints and the errors of
+/// rendering it.
+fn diagnostics(
+    document: ftd::interpreter2::Document,
+    source: &str,
+) -> Vec<ftd::diagnostic::Diagnostic> {
+    let mut diagnostics: Vec<ftd::diagnostic::Diagnostic> = ftd::interpreter2::check(&document)
+        .iter()
+        .map(ftd::diagnostic::Diagnostic::from)
+        .collect();
+    if !diagnostics.is_empty() {
+        return diagnostics;
+    }
+    match ftd::interpreter2::lint(&document, source) {
+        Ok(lints) => diagnostics.extend(lints.iter().map(ftd::diagnostic::Diagnostic::from)),
+        Err(e) => diagnostics.push((&e).into()),
+    }
+    if let Err(e) = html(document, "") {
+        diagnostics.push(e);
+    }
+    diagnostics
+}
+
+/// `ftd ast <file> [--json]`: prints the ast of the document, as json or in the debug format.
+fn ast(args: &[String]) -> i32 {
+    let options = match Options::parse(args, &["--json"]) {
+        Ok(options) if options.paths.len() == 1 => options,
+        Ok(_) => return usage("`ast` takes exactly one file"),
+        Err(e) => return usage(e.as_str()),
+    };
+    let path = options.paths[0].as_path();
+    let root = path.parent().unwrap_or_else(|| std::path::Path::new(""));
+    let mut loader = Loader::new(vec![]);
+
+    let document = match loader.read(root, path).and_then(|doc_id| {
+        ftd::interpreter2::ParsedDocument::parse(doc_id.as_str(), loader.source(doc_id.as_str()))
+            .map_err(|e| (&e).into())
+    }) {
+        Ok(document) => document,
+        Err(e) => {
+            eprintln!("{}", loader.report(&e));
+            return 1;
+        }
+    };
+    if options.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document.ast).expect("failed to convert ast to json")
+        );
+    } else {
+        println!("{:#?}", document.ast);
+    }
+    0
+}
+
+/// `ftd bench <file or directory>... [-I <directory>]...`: times the interpretation, imports
+/// included, and the rendering of each document.
+fn bench(args: &[String]) -> i32 {
+    let options = match Options::parse(args, &["--include"]) {
+        Ok(options) if !options.paths.is_empty() => options,
+        Ok(_) => return usage("`bench` needs a file or directory"),
+        Err(e) => return usage(e.as_str()),
+    };
+
+    let documents = match documents(options.paths.as_slice()) {
+        Ok(documents) => documents,
+        Err(e) => return usage(e.as_str()),
+    };
+    let mut code = 0;
+    let total = std::time::Instant::now();
+    for (root, path) in documents {
+        let mut loader = Loader::new(options.search_path(&root));
+        let start = std::time::Instant::now();
+        let result = loader
+            .read(&root, &path)
+            .and_then(|doc_id| loader.interpret(doc_id.as_str()))
+            .and_then(|document| {
+                let interpreted = start.elapsed();
+                html(document, "").map(|_| interpreted)
+            });
+        match result {
+            Ok(interpreted) => println!(
+                "{}: interpret {:?}, render {:?}",
+                path.display(),
+                interpreted,
+                start.elapsed() - interpreted
+            ),
+            Err(e) => {
+                eprintln!("{}\n", loader.report(&e));
+                code = 1;
             }
         }
-        let mut f =
-            std::fs::File::create("./benchmark-2022/.log").expect("failed to create .html file");
-        f.write_all(log.as_bytes())
-            .expect("failed to write to .log file");
-        return;
     }
+    println!("total {:?}", total.elapsed());
+    code
+}
+
+/// `ftd fmt [--check] <file or directory>...`: formats the given `.ftd` files in place, see
+/// `ftd::p11::format`. With `--check` the files are not written, and the ones that are not
+/// formatted are listed instead. Returns the exit code.
+fn fmt(args: &[String]) -> i32 {
+    let options = match Options::parse(args, &["--check"]) {
+        Ok(options) if !options.paths.is_empty() => options,
+        Ok(_) => return usage("`fmt` needs a file or directory"),
+        Err(e) => return usage(e.as_str()),
+    };
+    let mut files = vec![];
+    for path in options.paths.iter() {
+        if let Err(e) = ftd_files(path, &mut files) {
+            return usage(e.as_str());
+        }
+    }
+
+    let mut code = 0;
+    for file in files {
+        let source = match std::fs::read_to_string(&file) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{}: {}", file.display(), e);
+                code = 1;
+                continue;
+            }
+        };
+        let id = file.to_string_lossy().to_string();
+        let formatted = match ftd::p11::format(source.as_str(), id.as_str()) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    ftd::diagnostic::Diagnostic::from(&e).render(source.as_str())
+                );
+                code = 1;
+                continue;
+            }
+        };
+        if formatted.eq(&source) {
+            continue;
+        }
+        if options.check {
+            println!("{}", file.display());
+            code = 1;
+        } else if let Err(e) = std::fs::write(&file, formatted) {
+            eprintln!("{}: {}", file.display(), e);
+            code = 1;
+        }
+    }
+    code
+}
+
+/// `ftd examples [<name>]`: renders `./examples/<name>.ftd`, or all of `./examples` and
+/// `./t/html`, to `./docs`, along with an index page. This is how the site is built.
+fn examples(id: Option<&String>) -> i32 {
     let dir = std::path::Path::new("./examples/");
     let new_ftd_dir = std::path::Path::new("./t/html/");
 
@@ -176,84 +693,68 @@ padding-bottom: 20
         }
     }
     write("index.ftd", write_doc);
+    0
+}
+
+/// Host of the examples in `./t/html`: modules are read from there, and module `test` has the
+/// foreign variable `var` and the foreign function `fn`. Processors return their caption, or
+/// their name, in uppercase.
+struct ExampleHost;
+
+impl ftd::interpreter2::Host for ExampleHost {
+    fn load_module(
+        &mut self,
+        module: &str,
+        _caller_module: &str,
+    ) -> Option<ftd::interpreter2::Module> {
+        if module.eq("test") {
+            let mut loaded = ftd::interpreter2::Module::default();
+            loaded.foreign_variables.push("var".to_string());
+            loaded.foreign_functions.push("fn".to_string());
+            return Some(loaded);
+        }
+        std::fs::read_to_string(format!("./t/html/{}.ftd", module))
+            .ok()
+            .map(ftd::interpreter2::Module::from)
+    }
+
+    fn process(
+        &mut self,
+        processor: &str,
+        ast: &ftd::ast::AST,
+        module: &str,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+        let variable_definition = ast.clone().get_variable_definition(module)?;
+        Ok(ftd::interpreter2::Value::String {
+            text: variable_definition
+                .value
+                .caption()
+                .unwrap_or_else(|| processor.to_string())
+                .to_uppercase(),
+        })
+    }
+
+    fn foreign_variable(
+        &mut self,
+        module: &str,
+        variable: &str,
+        _caller_module: &str,
+    ) -> ftd::interpreter2::Result<ftd::interpreter2::Value> {
+        if module.eq("test") {
+            Ok(ftd::interpreter2::Value::String {
+                text: variable.to_uppercase(),
+            })
+        } else {
+            ftd::interpreter2::utils::e2(format!("Unknown module {}", module), module, 0)
+        }
+    }
 }
 
 pub fn ftd_v2_interpret_helper(
     name: &str,
     source: &str,
 ) -> ftd::interpreter2::Result<ftd::interpreter2::Document> {
-    let mut s = ftd::interpreter2::interpret(name, source)?;
-    let document;
-    loop {
-        match s {
-            ftd::interpreter2::Interpreter::Done { document: doc } => {
-                document = doc;
-                break;
-            }
-            ftd::interpreter2::Interpreter::StuckOnImport {
-                module, state: st, ..
-            } => {
-                let mut source = "".to_string();
-                let mut foreign_variable = vec![];
-                let mut foreign_function = vec![];
-                if module.eq("test") {
-                    foreign_variable.push("var".to_string());
-                    foreign_function.push("fn".to_string());
-                }
-                if let Ok(value) = std::fs::read_to_string(format!("./t/html/{}.ftd", module)) {
-                    source = value;
-                }
-                let document = ftd::interpreter2::ParsedDocument::parse_with_line_number(
-                    module.as_str(),
-                    source.as_str(),
-                    0,
-                )?;
-
-                s = st.continue_after_import(
-                    module.as_str(),
-                    document,
-                    foreign_variable,
-                    foreign_function,
-                    0,
-                )?;
-            }
-            ftd::interpreter2::Interpreter::StuckOnProcessor {
-                state, ast, module, ..
-            } => {
-                let variable_definition = ast.get_variable_definition(module.as_str())?;
-                let processor = variable_definition.processor.unwrap();
-                let value = ftd::interpreter2::Value::String {
-                    text: variable_definition
-                        .value
-                        .caption()
-                        .unwrap_or(processor)
-                        .to_uppercase()
-                        .to_string(),
-                };
-                s = state.continue_after_processor(value)?;
-            }
-            ftd::interpreter2::Interpreter::StuckOnForeignVariable {
-                state,
-                module,
-                variable,
-                ..
-            } => {
-                if module.eq("test") {
-                    let value = ftd::interpreter2::Value::String {
-                        text: variable.to_uppercase().to_string(),
-                    };
-                    s = state.continue_after_variable(module.as_str(), variable.as_str(), value)?;
-                } else {
-                    return ftd::interpreter2::utils::e2(
-                        format!("Unknown module {}", module),
-                        module.as_str(),
-                        0,
-                    );
-                }
-            }
-        }
-    }
-    Ok(document)
+    ftd::interpreter2::interpret_with_host(name, source, &mut ExampleHost)
 }
 
 fn ftd_v2_write(id: &str, s: &str) {
@@ -261,42 +762,9 @@ fn ftd_v2_write(id: &str, s: &str) {
     let start = std::time::Instant::now();
     print!("Processing: {} ... ", id);
     let doc = ftd_v2_interpret_helper("foo", s).unwrap_or_else(|e| panic!("{:?}", e));
-    let executor =
-        ftd::executor::ExecuteDoc::from_interpreter(doc).unwrap_or_else(|e| panic!("{:?}", e));
-    let node = ftd::node::NodeData::from_rt(executor);
-    let html_ui =
-        ftd::html1::HtmlUI::from_node_data(node, "main").unwrap_or_else(|e| panic!("{:?}", e));
-    let ftd_js = std::fs::read_to_string("build.js").expect("build.js not found");
     let test_css = std::fs::read_to_string("t/test.css").expect("t/test.css not found");
-    let html_str = ftd::html1::utils::trim_all_lines(
-        std::fs::read_to_string("build.html")
-            .expect("cant read ftd.html")
-            .replace("__ftd_doc_title__", "")
-            .replace("__ftd_data__", html_ui.variables.as_str())
-            .replace("__ftd_external_children__", "{}")
-            .replace("__ftd__", html_ui.html.as_str())
-            .replace("__ftd_js__", ftd_js.as_str())
-            .replace("__extra_js__", "")
-            .replace("__base_url__", "/")
-            .replace(
-                "__extra_css__",
-                format!("<style>{}</style>", test_css).as_str(),
-            )
-            .replace(
-                "__ftd_functions__",
-                format!(
-                    "{}\n{}\n{}",
-                    html_ui.functions.as_str(),
-                    html_ui.dependencies.as_str(),
-                    html_ui.variable_dependencies.as_str()
-                )
-                .as_str(),
-            )
-            .replace("__ftd_body_events__", html_ui.outer_events.as_str())
-            .replace("__ftd_css__", "")
-            .replace("__ftd_element_css__", "")
-            .as_str(),
-    );
+    let html_str = html(doc, format!("<style>{}</style>", test_css).as_str())
+        .unwrap_or_else(|e| panic!("{:?}", e));
     std::fs::create_dir_all("./docs").expect("failed to create docs folder");
     let mut f = std::fs::File::create(format!("./docs/{}", id.replace(".ftd", ".html")))
         .expect("failed to create .html file");
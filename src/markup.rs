@@ -1,5 +1,3 @@
-const MAGIC: &str = "MMMMMMMMMAMMAMSMASMDASMDAMSDMASMDASDMASMDASDMAASD";
-
 pub static MD: once_cell::sync::Lazy<comrak::ComrakOptions> = once_cell::sync::Lazy::new(|| {
     let mut m = comrak::ComrakOptions::default();
     m.extension.strikethrough = true;
@@ -11,15 +9,231 @@ pub static MD: once_cell::sync::Lazy<comrak::ComrakOptions> = once_cell::sync::L
     m
 });
 
+/// Marks the place of an html snippet, like a heading anchor or a highlighted code fence, in the
+/// html generated by comrak, which escapes raw html.
+const PLACEHOLDER: &str = "PPPPPPPPPLPPLPLSPLSPDLSPDLPSDPLSPDLSPD";
+
+/// Per-document markup options, set by the host.
+#[derive(Debug, Clone, Default)]
+pub struct MarkupOptions {
+    /// Adds an anchor to each heading, with an id created from the heading text using `slug`.
+    pub heading_anchors: bool,
+    pub footnotes: bool,
+    pub description_lists: bool,
+    /// Renders `![alt](src)` as an image. By default it is kept as text.
+    pub images: bool,
+    /// Highlights code fences, like `ftd.code`, with this theme.
+    pub code_theme: Option<String>,
+    pub hook: Option<std::sync::Arc<dyn MarkupHook>>,
+}
+
+impl PartialEq for MarkupOptions {
+    fn eq(&self, other: &Self) -> bool {
+        self.heading_anchors == other.heading_anchors
+            && self.footnotes == other.footnotes
+            && self.description_lists == other.description_lists
+            && self.images == other.images
+            && self.code_theme == other.code_theme
+            && match (&self.hook, &other.hook) {
+                (Some(a), Some(b)) => std::sync::Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+impl MarkupOptions {
+    fn comrak_options(&self) -> comrak::ComrakOptions {
+        let mut m = MD.clone();
+        m.extension.footnotes = self.footnotes;
+        m.extension.description_lists = self.description_lists;
+        m
+    }
+}
+
+/// Lets a host rewrite the urls of links and images in markup, e.g. to turn relative links into
+/// package urls. Returning `None` keeps the url as it is.
+pub trait MarkupHook: std::fmt::Debug + Send + Sync {
+    fn link(&self, _url: &str) -> Option<String> {
+        None
+    }
+
+    fn image(&self, _url: &str) -> Option<String> {
+        None
+    }
+}
+
 pub fn markup(i: &str) -> String {
-    comrak::markdown_to_html(i.replace("![", MAGIC).trim(), &MD)
-        .trim()
-        .replace(MAGIC, "![")
-        .replace('\n', " ")
+    markup_with_options(i, &Default::default(), &Default::default())
+}
+
+/// Renders `i`, highlighting its code fences, if `options.code_theme` is set, using `code`.
+pub fn markup_with_options(
+    i: &str,
+    options: &MarkupOptions,
+    code: &ftd::executor::CodeOptions,
+) -> String {
+    let comrak_options = options.comrak_options();
+    let arena = comrak::Arena::new();
+    let root = comrak::parse_document(&arena, i.trim(), &comrak_options);
+    if !options.images {
+        images_to_text(&arena, root);
+    }
+    let snippets = update_nodes(root, options, code);
+
+    let mut html = vec![];
+    // writing to a `Vec` does not fail
+    comrak::format_html(root, &comrak_options, &mut html).unwrap();
+    let mut o = String::from_utf8_lossy(&html).trim().replace('\n', " ");
+    for (placeholder, snippet) in snippets {
+        o = o.replace(placeholder.as_str(), snippet.as_str());
+    }
+    o
+}
+
+/// Turns each image back into the text it is written as, `![alt](src "title")`.
+fn images_to_text<'a>(
+    arena: &'a comrak::Arena<comrak::nodes::AstNode<'a>>,
+    root: &'a comrak::nodes::AstNode<'a>,
+) {
+    let images = root
+        .descendants()
+        .filter(|v| matches!(v.data.borrow().value, comrak::nodes::NodeValue::Image(_)))
+        .collect::<Vec<_>>();
+    for image in images {
+        let mut data = image.data.borrow_mut();
+        let link = match &data.value {
+            comrak::nodes::NodeValue::Image(link) => link,
+            _ => continue,
+        };
+        let mut end = format!("]({}", String::from_utf8_lossy(&link.url));
+        if !link.title.is_empty() {
+            end.push_str(format!(" \"{}\"", String::from_utf8_lossy(&link.title)).as_str());
+        }
+        end.push(')');
+        // the alt text, the children of the image, goes between `![` and `](src)`
+        data.value = comrak::nodes::NodeValue::Text(b"![".to_vec());
+        image.append(
+            arena.alloc(comrak::arena_tree::Node::new(std::cell::RefCell::new(
+                comrak::nodes::Ast::new(comrak::nodes::NodeValue::Text(end.into_bytes())),
+            ))),
+        );
+    }
+}
+
+/// Rewrites links and images using the hook, and replaces heading anchors and code fences with
+/// placeholders. Returns the placeholders along with the html they stand for.
+fn update_nodes<'a>(
+    root: &'a comrak::nodes::AstNode<'a>,
+    options: &MarkupOptions,
+    code_options: &ftd::executor::CodeOptions,
+) -> Vec<(String, String)> {
+    let mut snippets = vec![];
+    let mut slugs: ftd::Map<usize> = Default::default();
+    for node in root.descendants() {
+        let mut snippet = None;
+        match &mut node.data.borrow_mut().value {
+            comrak::nodes::NodeValue::Link(link) => {
+                if let Some(hook) = options.hook.as_ref() {
+                    if let Some(url) = hook.link(String::from_utf8_lossy(&link.url).as_ref()) {
+                        link.url = url.into_bytes();
+                    }
+                }
+            }
+            comrak::nodes::NodeValue::Image(image) => {
+                if let Some(hook) = options.hook.as_ref() {
+                    if let Some(url) = hook.image(String::from_utf8_lossy(&image.url).as_ref()) {
+                        image.url = url.into_bytes();
+                    }
+                }
+            }
+            comrak::nodes::NodeValue::CodeBlock(code) => {
+                if let Some(theme) = options.code_theme.as_ref() {
+                    let info = String::from_utf8_lossy(&code.info);
+                    let ext = info.split_whitespace().next().unwrap_or("txt");
+                    let literal = String::from_utf8_lossy(&code.literal);
+                    // an unknown theme leaves the code fence as comrak renders it
+                    if let Ok(html) = ftd::executor::code::code(
+                        literal.as_ref(),
+                        ext,
+                        theme,
+                        &Default::default(),
+                        code_options,
+                        "",
+                    ) {
+                        snippet = Some(html);
+                    }
+                }
+            }
+            comrak::nodes::NodeValue::Heading(_) if options.heading_anchors => {
+                add_heading_anchor(node, &mut slugs, &mut snippets);
+            }
+            _ => {}
+        }
+        if let Some(html) = snippet {
+            let placeholder = format!("{}{}Z", PLACEHOLDER, snippets.len());
+            node.data.borrow_mut().value =
+                comrak::nodes::NodeValue::Text(placeholder.clone().into_bytes());
+            snippets.push((placeholder, html));
+        }
+    }
+    snippets
+}
+
+fn add_heading_anchor<'a>(
+    heading: &'a comrak::nodes::AstNode<'a>,
+    slugs: &mut ftd::Map<usize>,
+    snippets: &mut Vec<(String, String)>,
+) {
+    let texts = heading
+        .descendants()
+        .skip(1)
+        .filter(|v| matches!(v.data.borrow().value, comrak::nodes::NodeValue::Text(_)))
+        .collect::<Vec<_>>();
+    let first = match texts.first() {
+        Some(first) => *first,
+        None => return,
+    };
+    let text = texts
+        .iter()
+        .filter_map(|v| match &v.data.borrow().value {
+            comrak::nodes::NodeValue::Text(text) => Some(String::from_utf8_lossy(text).to_string()),
+            _ => None,
+        })
+        .collect::<String>();
+
+    let mut slug = slug::slugify(text);
+    let count = slugs.entry(slug.clone()).or_insert(0);
+    if *count > 0 {
+        slug = format!("{}-{}", slug, count);
+    }
+    *count += 1;
+
+    let placeholder = format!("{}{}Z", PLACEHOLDER, snippets.len());
+    if let comrak::nodes::NodeValue::Text(text) = &mut first.data.borrow_mut().value {
+        let mut literal = placeholder.clone().into_bytes();
+        literal.append(text);
+        *text = literal;
+    }
+    snippets.push((
+        placeholder,
+        format!(
+            "<a href=\"#{slug}\" aria-hidden=\"true\" class=\"anchor\" id=\"{slug}\"></a>",
+            slug = slug
+        ),
+    ));
 }
 
 pub fn markup_inline(i: &str) -> String {
-    let mut o = markup(i);
+    markup_inline_with_options(i, &Default::default(), &Default::default())
+}
+
+pub fn markup_inline_with_options(
+    i: &str,
+    options: &MarkupOptions,
+    code: &ftd::executor::CodeOptions,
+) -> String {
+    let mut o = markup_with_options(i, options, code);
     let (space_before, space_after) = spaces(i);
 
     // if output is wrapped in `<p>`, we are trying to remove it, because this is a single text
@@ -65,3 +279,75 @@ fn spaces(s: &str) -> (usize, usize) {
     }
     (space_before, space_after)
 }
+
+#[cfg(test)]
+mod test {
+    #[derive(Debug)]
+    struct PackageLinks;
+
+    impl super::MarkupHook for PackageLinks {
+        fn link(&self, url: &str) -> Option<String> {
+            url.strip_prefix('/')
+                .map(|v| format!("https://example.com/{}", v))
+        }
+    }
+
+    #[test]
+    fn default_options() {
+        assert_eq!(
+            super::markup("hello ![world](w.png)"),
+            "<p>hello ![world](w.png)</p>"
+        );
+    }
+
+    #[test]
+    fn heading_anchors() {
+        let options = super::MarkupOptions {
+            heading_anchors: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            super::markup_with_options(
+                "# Hello World\n\n# Hello World",
+                &options,
+                &Default::default()
+            ),
+            "<h1><a href=\"#hello-world\" aria-hidden=\"true\" class=\"anchor\" \
+            id=\"hello-world\"></a>Hello World</h1> <h1><a href=\"#hello-world-1\" \
+            aria-hidden=\"true\" class=\"anchor\" id=\"hello-world-1\"></a>Hello World</h1>"
+        );
+    }
+
+    #[test]
+    fn hook() {
+        let options = super::MarkupOptions {
+            images: true,
+            hook: Some(std::sync::Arc::new(PackageLinks)),
+            ..Default::default()
+        };
+        assert_eq!(
+            super::markup_with_options(
+                "[a](/a/) [b](https://b.com) ![c](/c.png)",
+                &options,
+                &Default::default()
+            ),
+            "<p><a href=\"https://example.com/a/\">a</a> <a href=\"https://b.com\">b</a> \
+            <img src=\"/c.png\" alt=\"c\" /></p>"
+        );
+    }
+
+    #[test]
+    fn code_fence() {
+        let options = super::MarkupOptions {
+            code_theme: Some(ftd::executor::code::DEFAULT_THEME.to_string()),
+            ..Default::default()
+        };
+        let o = super::markup_with_options(
+            "hello\n\n```rs\nfn main() {}\n```",
+            &options,
+            &Default::default(),
+        );
+        assert!(o.starts_with("<p>hello</p> <pre style="), "{}", o);
+        assert!(!o.contains(super::PLACEHOLDER), "{}", o);
+    }
+}
@@ -9,9 +9,14 @@ pub struct Node {
     pub style: ftd::Map<ftd::node::Value>,
     pub children: Vec<Node>,
     pub text: ftd::node::Value,
+    /// `text` to show in dark mode, if it differs from `text`, like the highlighted code of an
+    /// `ftd.code` with a `dark-theme`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dark_text: Option<ftd::node::Value>,
     pub null: bool,
     pub data_id: String,
     pub line_number: usize,
+    pub loop_template: Option<ftd::executor::LoopTemplate>,
 }
 
 pub type Event = ftd::executor::Event;
@@ -31,11 +36,13 @@ impl Node {
             style: common.style(doc_id, &mut []),
             children: vec![],
             text: Default::default(),
+            dark_text: None,
             classes: common.classes(),
             null: common.is_dummy,
             events: common.event.clone(),
             data_id: common.data_id.clone(),
             line_number: common.line_number,
+            loop_template: common.loop_template.to_owned(),
         }
     }
 
@@ -45,8 +52,6 @@ impl Node {
         doc_id: &str,
         display: &str,
     ) -> Node {
-        use itertools::Itertools;
-
         let mut attrs = common.attrs(doc_id);
         attrs.extend(container.attrs());
         let mut classes = container.add_class();
@@ -63,16 +68,14 @@ impl Node {
             classes,
             condition: common.condition.to_owned(),
             text: Default::default(),
-            children: container
-                .children
-                .iter()
-                .map(|v| v.to_node(doc_id))
-                .collect_vec(),
+            dark_text: None,
+            children: children_to_nodes(container.children.as_slice(), doc_id),
             null: common.is_dummy,
             events: common.event.clone(),
             data_id: common.data_id.to_string(),
             line_number: common.line_number,
             display: s(display),
+            loop_template: common.loop_template.to_owned(),
         }
     }
 
@@ -86,6 +89,7 @@ impl ftd::executor::Element {
         match self {
             ftd::executor::Element::Row(r) => r.to_node(doc_id),
             ftd::executor::Element::Column(c) => c.to_node(doc_id),
+            ftd::executor::Element::Grid(g) => g.to_node(doc_id),
             ftd::executor::Element::Text(t) => t.to_node(doc_id),
             ftd::executor::Element::Integer(t) => t.to_node(doc_id),
             ftd::executor::Element::Decimal(t) => t.to_node(doc_id),
@@ -104,14 +108,35 @@ impl ftd::executor::Element {
                 style: Default::default(),
                 children: vec![],
                 text: Default::default(),
+                dark_text: None,
                 null: true,
                 data_id: "".to_string(),
                 line_number: 0,
+                loop_template: None,
             },
         }
     }
 }
 
+/// The nodes of `children`, with the templates of the loops on mutable lists moved before the
+/// sibling that follows their instances, see `ftd::executor::LoopTemplate`.
+fn children_to_nodes(children: &[ftd::executor::Element], doc_id: &str) -> Vec<Node> {
+    let mut nodes = vec![];
+    let mut templates = vec![];
+    for child in children {
+        let node = child.to_node(doc_id);
+        match node.loop_template.as_ref().map(|t| t.position) {
+            Some(position) => templates.push((position, node)),
+            None => nodes.push(node),
+        }
+    }
+    for (count, (position, node)) in templates.into_iter().enumerate() {
+        let index = std::cmp::min(position + count, nodes.len());
+        nodes.insert(index, node);
+    }
+    nodes
+}
+
 impl ftd::executor::Row {
     pub fn to_node(&self, doc_id: &str) -> Node {
         use ftd::node::utils::CheckMap;
@@ -255,6 +280,115 @@ impl ftd::executor::Column {
     }
 }
 
+impl ftd::executor::Grid {
+    pub fn to_node(&self, doc_id: &str) -> Node {
+        use ftd::node::utils::CheckMap;
+
+        let mut n = Node::from_container(&self.common, &self.container, doc_id, "grid");
+        if !self.common.is_not_visible {
+            n.style
+                .insert(s("display"), ftd::node::Value::from_string("grid"));
+        }
+
+        n.style.check_and_insert(
+            "justify-content",
+            ftd::node::Value::from_executor_value(
+                Some(
+                    self.container
+                        .align_content
+                        .to_owned()
+                        .map(|v| v.to_css_justify_content(true))
+                        .value,
+                ),
+                self.container.align_content.to_owned(),
+                Some(ftd::executor::Alignment::justify_content_pattern(true)),
+                doc_id,
+            ),
+        );
+
+        n.style.upsert(
+            "justify-content",
+            ftd::node::Value::from_executor_value(
+                self.container
+                    .spacing_mode
+                    .to_owned()
+                    .map(|v| v.map(|v| v.to_css_string()))
+                    .value,
+                self.container.spacing_mode.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        n.style.check_and_insert(
+            "align-items",
+            ftd::node::Value::from_executor_value(
+                Some(
+                    self.container
+                        .align_content
+                        .to_owned()
+                        .map(|v| v.to_css_align_items(true))
+                        .value,
+                ),
+                self.container.align_content.to_owned(),
+                Some(ftd::executor::Alignment::align_item_pattern(true)),
+                doc_id,
+            ),
+        );
+
+        n.style.check_and_insert(
+            "grid-template-columns",
+            ftd::node::Value::from_executor_value(
+                ftd::executor::GridTrack::list_to_css_string(&self.template_columns.value),
+                self.template_columns.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        n.style.check_and_insert(
+            "grid-template-rows",
+            ftd::node::Value::from_executor_value(
+                ftd::executor::GridTrack::list_to_css_string(&self.template_rows.value),
+                self.template_rows.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        n.style.check_and_insert(
+            "grid-template-areas",
+            ftd::node::Value::from_executor_value(
+                ftd::node::utils::grid_template_areas_to_css(&self.template_areas.value),
+                self.template_areas.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        n.style.check_and_insert(
+            "column-gap",
+            ftd::node::Value::from_executor_value(
+                self.column_gap.value.as_ref().map(|v| v.to_css_string()),
+                self.column_gap.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        n.style.check_and_insert(
+            "row-gap",
+            ftd::node::Value::from_executor_value(
+                self.row_gap.value.as_ref().map(|v| v.to_css_string()),
+                self.row_gap.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+        n
+    }
+}
+
 impl ftd::executor::Text {
     pub fn to_node(&self, doc_id: &str) -> Node {
         use ftd::node::utils::CheckMap;
@@ -337,16 +471,64 @@ impl ftd::executor::Text {
 
         n.classes.extend(self.common.add_class());
         n.classes.push("ft_md".to_string());
-        n.text = ftd::node::Value::from_executor_value(
-            Some(self.text.value.rendered.to_string()),
-            self.text.clone(),
-            None,
-            doc_id,
-        );
+        if self.markup.is_empty() {
+            n.text = ftd::node::Value::from_executor_value(
+                Some(self.text.value.rendered.to_string()),
+                self.text.clone(),
+                None,
+                doc_id,
+            );
+        } else {
+            // each run of the markup gets its own node, so that a change in a referenced
+            // variable only updates that run
+            n.children = self.markup.iter().map(|v| v.to_node(doc_id)).collect();
+        }
         n
     }
 }
 
+impl ftd::executor::Markup {
+    pub fn to_node(&self, doc_id: &str) -> Node {
+        match self {
+            ftd::executor::Markup::Text(t) => t.to_node(doc_id),
+            ftd::executor::Markup::Element(e) => {
+                let mut n = e.to_node(doc_id);
+                if n.node.eq("div") {
+                    n.node = s("span");
+                }
+                if !n.is_null() {
+                    n.display = s("inline");
+                }
+                n
+            }
+        }
+    }
+}
+
+impl ftd::executor::MarkupText {
+    pub fn to_node(&self, doc_id: &str) -> Node {
+        let mut attrs: ftd::Map<ftd::node::Value> = Default::default();
+        attrs.insert(
+            s("data-id"),
+            ftd::node::Value::from_string(self.data_id.as_str()),
+        );
+        Node {
+            node: s("span"),
+            display: s("inline"),
+            attrs,
+            text: ftd::node::Value::from_executor_value(
+                Some(self.text.value.rendered.to_string()),
+                self.text.clone(),
+                None,
+                doc_id,
+            ),
+            data_id: self.data_id.to_string(),
+            line_number: self.line_number,
+            ..Default::default()
+        }
+    }
+}
+
 impl ftd::executor::Code {
     pub fn to_node(&self, doc_id: &str) -> Node {
         use ftd::node::utils::CheckMap;
@@ -428,6 +610,13 @@ impl ftd::executor::Code {
             None,
             doc_id,
         );
+        n.dark_text = Some(ftd::node::Value::from_executor_value(
+            Some(self.dark_text.value.rendered.to_string()),
+            self.dark_text.clone(),
+            None,
+            doc_id,
+        ))
+        .filter(|v| v.value.ne(&n.text.value));
         n
     }
 }
@@ -662,6 +851,36 @@ impl ftd::executor::Common {
 
         d.check_and_insert("box-sizing", ftd::node::Value::from_string("border-box"));
 
+        d.check_and_insert(
+            "grid-area",
+            ftd::node::Value::from_executor_value(
+                self.grid_area.value.to_owned(),
+                self.grid_area.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        d.check_and_insert(
+            "grid-column",
+            ftd::node::Value::from_executor_value(
+                self.grid_column.value.to_owned(),
+                self.grid_column.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        d.check_and_insert(
+            "grid-row",
+            ftd::node::Value::from_executor_value(
+                self.grid_row.value.to_owned(),
+                self.grid_row.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
         d.check_and_insert(
             "z-index",
             ftd::node::Value::from_executor_value(
@@ -842,14 +1061,30 @@ impl ftd::executor::Common {
             ),
         );
 
+        let (solid_background, background) =
+            ftd::node::utils::split_background(self.background.to_owned());
+
         d.check_and_insert(
             "background-color",
             ftd::node::Value::from_executor_value(
-                self.background
+                solid_background
+                    .to_owned()
+                    .map(|v| v.and_then(|v| v.color_css_string()))
+                    .value,
+                solid_background,
+                None,
+                doc_id,
+            ),
+        );
+
+        d.check_and_insert(
+            "background",
+            ftd::node::Value::from_executor_value(
+                background
                     .to_owned()
                     .map(|v| v.map(|v| v.to_css_string()))
                     .value,
-                self.background.to_owned(),
+                background,
                 None,
                 doc_id,
             ),
@@ -4,70 +4,7 @@ pub fn interpret_helper(
     name: &str,
     source: &str,
 ) -> ftd::interpreter2::Result<ftd::interpreter2::Document> {
-    let mut s = ftd::interpreter2::interpret(name, source)?;
-    let document;
-    loop {
-        match s {
-            ftd::interpreter2::Interpreter::Done { document: doc } => {
-                document = doc;
-                break;
-            }
-            ftd::interpreter2::Interpreter::StuckOnImport {
-                module, state: st, ..
-            } => {
-                let source = "";
-                let mut foreign_variable = vec![];
-                let mut foreign_function = vec![];
-                if module.eq("test") {
-                    foreign_variable.push("var".to_string());
-                    foreign_function.push("fn".to_string());
-                }
-                let document = ftd::interpreter2::ParsedDocument::parse(module.as_str(), source)?;
-                s = st.continue_after_import(
-                    module.as_str(),
-                    document,
-                    foreign_variable,
-                    foreign_function,
-                    0,
-                )?;
-            }
-            ftd::interpreter2::Interpreter::StuckOnProcessor {
-                state, ast, module, ..
-            } => {
-                let variable_definition = ast.get_variable_definition(module.as_str())?;
-                let processor = variable_definition.processor.unwrap();
-                let value = ftd::interpreter2::Value::String {
-                    text: variable_definition
-                        .value
-                        .caption()
-                        .unwrap_or(processor)
-                        .to_uppercase()
-                        .to_string(),
-                };
-                s = state.continue_after_processor(value)?;
-            }
-            ftd::interpreter2::Interpreter::StuckOnForeignVariable {
-                state,
-                module,
-                variable,
-                ..
-            } => {
-                if module.eq("test") {
-                    let value = ftd::interpreter2::Value::String {
-                        text: variable.to_uppercase().to_string(),
-                    };
-                    s = state.continue_after_variable(module.as_str(), variable.as_str(), value)?;
-                } else {
-                    return ftd::interpreter2::utils::e2(
-                        format!("Unknown module {}", module),
-                        module.as_str(),
-                        0,
-                    );
-                }
-            }
-        }
-    }
-    Ok(document)
+    ftd::interpreter2::interpret_with_host(name, source, &mut ftd::interpreter2::test::TestHost)
 }
 
 #[track_caller]
@@ -110,6 +47,57 @@ fn node_test_all() {
     }
 }
 
+#[test]
+fn code_classes() {
+    let mut registry = ftd::code::Registry::default();
+    registry.add_theme(
+        "registered-theme",
+        ftd::code::TS.themes[ftd::code::DEFAULT_THEME].clone(),
+    );
+    let registry = std::sync::Arc::new(registry);
+
+    let doc = interpret_helper(
+        "foo",
+        indoc::indoc!(
+            "
+            -- ftd.code:
+            lang: rs
+            theme: registered-theme
+
+            fn main() {}
+            "
+        ),
+    )
+    .unwrap_or_else(|e| panic!("{:?}", e));
+    let executor = ftd::executor::ExecuteDoc::from_interpreter_with_options(
+        doc,
+        &Default::default(),
+        &ftd::executor::CodeOptions {
+            classes: true,
+            registry: registry.clone(),
+        },
+    )
+    .unwrap_or_else(|e| panic!("{:?}", e));
+    let node = ftd::node::NodeData::from_rt(executor);
+    let html = node.node.children[0].text.value.to_owned().unwrap();
+
+    assert!(
+        html.starts_with(
+            "<pre class=\"ft_syntax_code\"><span class=\"ft_syntax_source ft_syntax_rust\">"
+        ),
+        "{}",
+        html
+    );
+    assert!(!html.contains("style="), "{}", html);
+    assert!(html.ends_with("</span>\n</pre>\n"), "{}", html);
+    assert!(registry
+        .theme_css("registered-theme")
+        .unwrap()
+        .contains(".ft_syntax_code {"));
+    assert!(registry.theme_css("unknown-theme").is_none());
+    assert!(ftd::code::TS.themes.get("registered-theme").is_none());
+}
+
 fn find_all_files_matching_extension_recursively(
     dir: impl AsRef<std::path::Path>,
     extension: &str,
@@ -52,6 +52,44 @@ pub(crate) fn wrap_to_css(wrap: bool) -> String {
     }
 }
 
+/// `grid-template-areas` of `areas`, a row of area names each. Single quotes are used, as
+/// the style ends up in a double quoted `style` attribute.
+pub(crate) fn grid_template_areas_to_css(areas: &[String]) -> Option<String> {
+    if areas.is_empty() {
+        return None;
+    }
+    Some(
+        areas
+            .iter()
+            .map(|v| format!("'{}'", v.trim()))
+            .collect::<Vec<String>>()
+            .join(" "),
+    )
+}
+
+/// Splits `background` into the solid colors, set as `background-color`, and gradients,
+/// images and layers, set using the `background` shorthand. Properties whose kind does not
+/// tell the variant apart go to the shorthand, which also accepts a plain color.
+pub(crate) fn split_background(
+    background: ftd::executor::Value<Option<ftd::executor::Background>>,
+) -> (
+    ftd::executor::Value<Option<ftd::executor::Background>>,
+    ftd::executor::Value<Option<ftd::executor::Background>>,
+) {
+    let (shorthand_properties, solid_properties): (Vec<_>, Vec<_>) = background
+        .properties
+        .into_iter()
+        .partition(|v| ftd::executor::Background::is_shorthand_kind(&v.value.kind()));
+    let (solid, shorthand) = match background.value {
+        Some(v @ ftd::executor::Background::Solid(_)) => (Some(v), None),
+        v => (None, v),
+    };
+    (
+        ftd::executor::Value::new(solid, background.line_number, solid_properties),
+        ftd::executor::Value::new(shorthand, background.line_number, shorthand_properties),
+    )
+}
+
 pub(crate) fn escape(s: &str) -> String {
     let s = s.replace('>', "\\u003E");
     let s = s.replace('<', "\\u003C");
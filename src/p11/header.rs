@@ -13,6 +13,10 @@ pub struct KV {
     pub kind: Option<String>,
     pub value: Option<String>,
     pub condition: Option<String>,
+    #[serde(skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
+    #[serde(skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub value_span: ftd::p11::Span,
 }
 
 impl KV {
@@ -29,6 +33,8 @@ impl KV {
             kind,
             value,
             condition,
+            span: Default::default(),
+            value_span: Default::default(),
         }
     }
 }
@@ -41,6 +47,8 @@ pub struct Section {
     pub kind: Option<String>,
     pub section: Vec<ftd::p11::Section>,
     pub condition: Option<String>,
+    #[serde(skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
 }
 
 impl Header {
@@ -77,9 +85,23 @@ impl Header {
             kind,
             section,
             condition,
+            span: Default::default(),
         })
     }
 
+    /// Sets the span of the header key (for caption, the span of caption value) and, for
+    /// `KV` headers, the span of the value.
+    pub(crate) fn with_span(mut self, span: ftd::p11::Span, value_span: ftd::p11::Span) -> Header {
+        match &mut self {
+            Header::KV(kv) => {
+                kv.span = span;
+                kv.value_span = value_span;
+            }
+            Header::Section(s) => s.span = span,
+        }
+        self
+    }
+
     pub fn without_line_number(&self) -> Self {
         use itertools::Itertools;
 
@@ -87,11 +109,14 @@ impl Header {
             Header::KV(kv) => {
                 let mut kv = (*kv).clone();
                 kv.line_number = 0;
+                kv.span = Default::default();
+                kv.value_span = Default::default();
                 Header::KV(kv)
             }
             Header::Section(s) => {
                 let mut s = (*s).clone();
                 s.line_number = 0;
+                s.span = Default::default();
                 s.section = s
                     .section
                     .iter()
@@ -142,6 +167,7 @@ impl Header {
                 ),
                 doc_id: doc_id.to_string(),
                 line_number: self.get_line_number(),
+                span: Some(self.get_span()),
             }),
         }
     }
@@ -155,6 +181,7 @@ impl Header {
                 ),
                 doc_id: doc_id.to_string(),
                 line_number: self.get_line_number(),
+                span: Some(self.get_span()),
             }),
             Header::Section(ftd::p11::header::Section { section, .. }) => Ok(section),
         }
@@ -167,6 +194,26 @@ impl Header {
         }
     }
 
+    /// Span of the header key. For caption this is the span of the caption value.
+    pub fn get_span(&self) -> ftd::p11::Span {
+        match self {
+            Header::KV(ftd::p11::header::KV { span, .. })
+            | Header::Section(ftd::p11::header::Section { span, .. }) => *span,
+        }
+    }
+
+    /// Span of the header value, falls back to the span of the key when the value span is not
+    /// known (e.g. for header sections).
+    pub fn get_value_span(&self) -> ftd::p11::Span {
+        match self {
+            Header::KV(ftd::p11::header::KV {
+                value_span, span, ..
+            }) if value_span.is_empty() => *span,
+            Header::KV(ftd::p11::header::KV { value_span, .. }) => *value_span,
+            Header::Section(ftd::p11::header::Section { span, .. }) => *span,
+        }
+    }
+
     pub(crate) fn get_kind(&self) -> Option<String> {
         match self {
             Header::KV(ftd::p11::header::KV { kind, .. })
@@ -242,12 +289,14 @@ impl Headers {
             key: key.to_string(),
             doc_id: doc_id.to_string(),
             line_number,
+            span: None,
         })?;
         if headers.len() > 1 {
             return Err(ftd::p11::Error::MoreThanOneHeader {
                 key: key.to_string(),
                 doc_id: doc_id.to_string(),
                 line_number: header.get_line_number(),
+                span: Some(headers[1].get_span()),
             });
         }
         Ok(header)
@@ -5,12 +5,16 @@ mod test;
 pub(crate) mod header;
 mod parser;
 mod section;
+mod span;
+mod to_string;
 pub mod utils;
 
 pub use header::{Header, Headers, Section as HSection, KV};
-pub use parser::{parse, parse_with_line_number};
+pub use parser::{parse, parse_with_line_number, parse_with_recovery};
 pub use section::Body;
 pub use section::Section;
+pub use span::Span;
+pub use to_string::{format, to_string};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -18,13 +22,18 @@ pub enum Error {
     SectionNotFound { doc_id: String, line_number: usize },
 
     #[error("{doc_id}:{line_number} -> MoreThanOneCaption")]
-    MoreThanOneCaption { doc_id: String, line_number: usize },
+    MoreThanOneCaption {
+        doc_id: String,
+        line_number: usize,
+        span: Option<ftd::p11::Span>,
+    },
 
     #[error("{doc_id}:{line_number} -> {message}")]
     ParseError {
         message: String,
         doc_id: String,
         line_number: usize,
+        span: Option<ftd::p11::Span>,
     },
 
     #[error("{doc_id}:{line_number} -> MoreThanOneHeader for key {key}")]
@@ -32,6 +41,7 @@ pub enum Error {
         key: String,
         doc_id: String,
         line_number: usize,
+        span: Option<ftd::p11::Span>,
     },
 
     #[error("{doc_id}:{line_number} -> HeaderNotFound for key {key}")]
@@ -39,6 +49,7 @@ pub enum Error {
         key: String,
         doc_id: String,
         line_number: usize,
+        span: Option<ftd::p11::Span>,
     },
 
     #[error("forbidden usage: {message}, line_number: {line_number}, doc: {doc_id}")]
@@ -5,6 +5,7 @@ enum ParsingStateReading {
         key: String,
         kind: Option<String>,
         condition: Option<String>,
+        span: ftd::p11::Span,
     },
     Caption,
     Body,
@@ -18,13 +19,38 @@ pub struct State {
     content: String,
     doc_id: String,
     state: Vec<(ftd::p11::Section, Vec<ParsingStateReading>)>,
+    source: String,
+    /// Byte offset at which each line of `source` starts.
+    line_starts: Vec<usize>,
+    line_offset: i32,
 }
 
 impl State {
-    fn next(&mut self) -> ftd::p11::Result<()> {
-        use itertools::Itertools;
+    /// Span of `needle` in the line with given `line_number`, as reported in sections and
+    /// headers.
+    fn span(&self, line_number: i32, needle: &str) -> ftd::p11::Span {
+        self.span_after(line_number, "", needle)
+    }
 
+    fn span_after(&self, line_number: i32, after: &str, needle: &str) -> ftd::p11::Span {
+        let line = ftd::p11::utils::i32_to_usize(line_number + self.line_offset);
+        let span = match self.line_starts.get(line.wrapping_sub(1)) {
+            Some(start) => {
+                let content = self.source[*start..].split('\n').next().unwrap_or_default();
+                ftd::p11::Span::in_line(content, *start, line, after, needle)
+            }
+            None => ftd::p11::Span::new(self.source.len(), line, 1, 0),
+        };
+        span.at_line(ftd::p11::utils::i32_to_usize(line_number))
+    }
+
+    fn next(&mut self) -> ftd::p11::Result<()> {
         self.reading_section()?;
+        self.continue_reading()
+    }
+
+    fn continue_reading(&mut self) -> ftd::p11::Result<()> {
+        use itertools::Itertools;
 
         while let Some((_, mut state)) = self.get_latest_state() {
             let mut change_state = None;
@@ -50,8 +76,9 @@ impl State {
                     key,
                     kind,
                     condition,
+                    span,
                 } => {
-                    self.reading_header_value(key.as_str(), kind, condition)?;
+                    self.reading_header_value(key.as_str(), kind, condition, span)?;
                 }
                 ParsingStateReading::Caption => {
                     self.reading_caption_value()?;
@@ -75,8 +102,9 @@ impl State {
             return Ok(());
         }
         let start_line = &start_line[2..];
+        let end_line_number = self.line_number + (scan_line_number as i32) + 1;
         let (name, caption) = colon_separated_values(
-            ftd::p11::utils::i32_to_usize(self.line_number + 1),
+            ftd::p11::utils::i32_to_usize(end_line_number),
             start_line,
             self.doc_id.as_str(),
         )?;
@@ -85,7 +113,18 @@ impl State {
                 message: "section name not provided for `end`".to_string(),
                 doc_id: self.doc_id.to_string(),
                 line_number: ftd::p11::utils::i32_to_usize(self.line_number),
+                span: Some(self.span(end_line_number, start_line)),
             })?;
+            // check before removing anything from the state, so that a stray `end` leaves the
+            // state intact for the recovering parser
+            if !self.is_open(caption.as_str()) {
+                return Err(ftd::p11::Error::ParseError {
+                    message: format!("No section found to end: {}", caption),
+                    doc_id: self.doc_id.to_string(),
+                    line_number: ftd::p11::utils::i32_to_usize(self.line_number),
+                    span: Some(self.span_after(end_line_number, ":", caption.as_str())),
+                });
+            }
             let mut sections = vec![];
             loop {
                 let line_number = self.line_number;
@@ -97,6 +136,7 @@ impl State {
                             message: format!("No section found to end: {}", caption),
                             doc_id: self.doc_id.to_string(),
                             line_number: ftd::p11::utils::i32_to_usize(self.line_number),
+                            span: None,
                         }
                     })?;
                     sections.push(section);
@@ -113,15 +153,19 @@ impl State {
                         key,
                         kind,
                         condition,
+                        span,
                     } if caption.eq(format!("{}.{}", section.name, key).as_str()) => {
                         sections.reverse();
-                        section.headers.push(ftd::p11::Header::section(
-                            ftd::p11::utils::i32_to_usize(line_number),
-                            key.as_str(),
-                            kind,
-                            sections,
-                            condition,
-                        ));
+                        section.headers.push(
+                            ftd::p11::Header::section(
+                                ftd::p11::utils::i32_to_usize(line_number),
+                                key.as_str(),
+                                kind,
+                                sections,
+                                condition,
+                            )
+                            .with_span(span, Default::default()),
+                        );
                         *change_state = Some(ParsingStateReading::Section);
                         break;
                     }
@@ -136,6 +180,101 @@ impl State {
         Ok(())
     }
 
+    /// Checks if there is a section or a header section named `name` that is yet to be ended.
+    fn is_open(&self, name: &str) -> bool {
+        self.state.iter().any(|(section, states)| {
+            states.iter().any(|state| match state {
+                ParsingStateReading::Section => name.eq(section.name.as_str()),
+                ParsingStateReading::Header { key, .. } => {
+                    name.eq(format!("{}.{}", section.name, key).as_str())
+                }
+                _ => false,
+            })
+        })
+    }
+
+    /// Marks the section the error `e` occurred in as broken, and moves the parser to the next
+    /// section line after the error. Returns `false` if there is nothing left to parse.
+    fn recover(&mut self, e: &ftd::p11::Error, resume_after: &mut usize) -> bool {
+        use itertools::Itertools;
+
+        let lines = self.source.split('\n').collect_vec();
+        let error_line = ftd::diagnostic::Diagnostic::from(e)
+            .line_number
+            .map(|v| v + ftd::p11::utils::i32_to_usize(self.line_offset))
+            .unwrap_or_default()
+            .max(ftd::p11::utils::i32_to_usize(
+                self.line_number + self.line_offset,
+            ));
+
+        // the section line the error belongs to, if the error is in an `end` line, there is no
+        // section to mark as broken
+        let owner = lines
+            .iter()
+            .take(error_line)
+            .rposition(|line| is_section_line(line))
+            .filter(|owner| !is_end_line(lines[*owner]));
+        let latest = self.state.last().map(|(section, _)| {
+            (
+                section.name.to_string(),
+                section.line_number + ftd::p11::utils::i32_to_usize(self.line_offset),
+            )
+        });
+        match (owner, latest) {
+            (Some(owner), Some((name, line_number)))
+                if owner + 1 == line_number || is_header_line(lines[owner], name.as_str()) =>
+            {
+                if let Some((section, states)) = self.state.last_mut() {
+                    section.is_broken = true;
+                    *states = vec![
+                        ParsingStateReading::Section,
+                        ParsingStateReading::Subsection,
+                    ];
+                }
+            }
+            (Some(owner), _) if owner >= *resume_after => {
+                let line = lines[owner].trim_start_matches('/');
+                let name = line[2..].split(':').next().unwrap_or_default().trim();
+                let (name, kind) = get_name_and_kind(name);
+                let line_number = owner as i32 + 1 - self.line_offset;
+                let mut section = ftd::p11::Section::with_name(name.as_str());
+                section.kind = kind;
+                section.is_commented = lines[owner].starts_with('/');
+                section.is_broken = true;
+                section.line_number = ftd::p11::utils::i32_to_usize(line_number);
+                section.span = self.span(line_number, name.as_str());
+                self.state.push((
+                    section,
+                    vec![
+                        ParsingStateReading::Section,
+                        ParsingStateReading::Subsection,
+                    ],
+                ));
+            }
+            _ => {}
+        }
+
+        let resume = lines
+            .iter()
+            .enumerate()
+            .skip(error_line.max(*resume_after + 1))
+            .find_map(|(idx, line)| is_section_line(line).then_some(idx));
+        match resume {
+            Some(resume) => {
+                *resume_after = resume;
+                self.content = lines[resume..].join("\n");
+                self.line_number = resume as i32 - self.line_offset;
+                true
+            }
+            None => {
+                let sections = self.state.drain(..).map(|(v, _)| v).collect_vec();
+                self.sections.extend(sections);
+                self.content = "".to_string();
+                false
+            }
+        }
+    }
+
     fn clean_content(&mut self) -> (usize, String) {
         let mut valid_line_number = None;
         let new_line_content = self.content.split('\n');
@@ -183,7 +322,7 @@ impl State {
 
         let (name_with_kind, caption) =
         //  section-kind section-name: caption
-            colon_separated_values(ftd::p11::utils::i32_to_usize(self.line_number), line, self
+            colon_separated_values(ftd::p11::utils::i32_to_usize(self.line_number + (scan_line_number as i32) + 1), line, self
                 .doc_id.as_str())?;
         let (section_name, kind) = get_name_and_kind(name_with_kind.as_str());
         let last_section = self.get_latest_state().map(|v| v.0);
@@ -200,14 +339,19 @@ impl State {
         }
 
         self.line_number += (scan_line_number as i32) + 1;
+        let span = self
+            .span(self.line_number, name_with_kind.as_str())
+            .suffix(name_with_kind.as_str(), section_name.as_str());
         let section = ftd::p11::Section {
             name: section_name,
             kind,
             caption: caption.map(|v| {
+                let caption_span = self.span_after(self.line_number, ":", v.as_str());
                 ftd::p11::Header::from_caption(
                     v.as_str(),
                     ftd::p11::utils::i32_to_usize(self.line_number),
                 )
+                .with_span(caption_span, caption_span)
             }),
             headers: Default::default(),
             body: None,
@@ -215,6 +359,8 @@ impl State {
             is_commented,
             line_number: ftd::p11::utils::i32_to_usize(self.line_number),
             block_body: false,
+            span,
+            is_broken: false,
         };
 
         self.state
@@ -227,6 +373,24 @@ impl State {
     fn reading_block_headers(&mut self) -> ftd::p11::Result<()> {
         self.end(&mut None)?;
         let (scan_line_number, content) = self.clean_content();
+        let header_line_number = self.line_number + (scan_line_number as i32) + 1;
+        let (key_span, value_span) = {
+            let (start_line, _) = new_line_split(content.as_str());
+            let start_line = start_line.trim();
+            let start_line = start_line
+                .strip_prefix("/-- ")
+                .or_else(|| start_line.strip_prefix("-- "))
+                .unwrap_or(start_line);
+            match colon_separated_values(0, start_line, self.doc_id.as_str()) {
+                Ok((name_with_kind, value)) => (
+                    self.span(header_line_number, name_with_kind.as_str()),
+                    value
+                        .map(|v| self.span_after(header_line_number, ":", v.as_str()))
+                        .unwrap_or_default(),
+                ),
+                Err(_) => Default::default(),
+            }
+        };
         let (section, parsing_states) =
             self.state
                 .last_mut()
@@ -258,7 +422,7 @@ impl State {
         };
 
         let (name_with_kind, value) = colon_separated_values(
-            ftd::p11::utils::i32_to_usize(self.line_number),
+            ftd::p11::utils::i32_to_usize(header_line_number),
             line,
             self.doc_id.as_str(),
         )?;
@@ -270,6 +434,7 @@ impl State {
             parsing_states.push(header_not_found_next_state);
             return Ok(());
         };
+        let key_span = key_span.suffix(name_with_kind.as_str(), key);
 
         self.line_number += (scan_line_number as i32) + 1;
         self.content = rest_lines;
@@ -285,16 +450,20 @@ impl State {
             return Err(ftd::p11::Error::MoreThanOneCaption {
                 doc_id: self.doc_id.to_string(),
                 line_number: section.line_number,
+                span: Some(key_span),
             });
         }
         if let Some(value) = value {
-            section.headers.push(ftd::p11::Header::kv(
-                ftd::p11::utils::i32_to_usize(self.line_number),
-                key,
-                kind,
-                Some(value),
-                condition,
-            ))
+            section.headers.push(
+                ftd::p11::Header::kv(
+                    ftd::p11::utils::i32_to_usize(self.line_number),
+                    key,
+                    kind,
+                    Some(value),
+                    condition,
+                )
+                .with_span(key_span, value_span),
+            )
         } else {
             parsing_states.push(if is_caption(key) {
                 ParsingStateReading::Caption
@@ -305,6 +474,7 @@ impl State {
                     key: key.to_string(),
                     kind,
                     condition,
+                    span: key_span,
                 }
             });
         }
@@ -316,9 +486,11 @@ impl State {
         header_key: &str,
         header_kind: Option<String>,
         header_condition: Option<String>,
+        header_span: ftd::p11::Span,
     ) -> ftd::p11::Result<()> {
         if let Err(ftd::p11::Error::SectionNotFound { .. }) = self.reading_section() {
             let mut value = vec![];
+            let mut value_span = None;
             let mut new_line_number = None;
             let mut first_line = true;
             let split_content = self.content.as_str().split('\n');
@@ -337,10 +509,14 @@ impl State {
                             message: format!("start section header '{}' after a newline!!", line),
                             doc_id: self.doc_id.to_string(),
                             line_number: ftd::p11::utils::i32_to_usize(self.line_number),
+                            span: Some(self.span(self.line_number, line)),
                         });
                     }
                     first_line = false;
                 }
+                if value_span.is_none() && !line.trim().is_empty() {
+                    value_span = Some(self.span(self.line_number, line));
+                }
                 value.push(clean_line(line));
             }
             self.content = content_index(self.content.as_str(), new_line_number);
@@ -354,19 +530,23 @@ impl State {
                 })?
                 .0;
             let value = value.join("\n").trim().to_string();
-            section.headers.push(ftd::p11::Header::kv(
-                ftd::p11::utils::i32_to_usize(line_number),
-                header_key,
-                header_kind,
-                if value.is_empty() { None } else { Some(value) },
-                header_condition,
-            ));
+            section.headers.push(
+                ftd::p11::Header::kv(
+                    ftd::p11::utils::i32_to_usize(line_number),
+                    header_key,
+                    header_kind,
+                    if value.is_empty() { None } else { Some(value) },
+                    header_condition,
+                )
+                .with_span(header_span, value_span.unwrap_or_default()),
+            );
         }
         Ok(())
     }
 
     fn reading_caption_value(&mut self) -> ftd::p11::Result<()> {
         let mut value = vec![];
+        let mut value_span = None;
         let mut new_line_number = None;
         let mut first_line = true;
         let split_content = self.content.as_str().split('\n');
@@ -385,10 +565,14 @@ impl State {
                         message: format!("start section caption '{}' after a newline!!", line),
                         doc_id: self.doc_id.to_string(),
                         line_number: ftd::p11::utils::i32_to_usize(self.line_number),
+                        span: Some(self.span(self.line_number, line)),
                     });
                 }
                 first_line = false;
             }
+            if value_span.is_none() && !line.trim().is_empty() {
+                value_span = Some(self.span(self.line_number, line));
+            }
             value.push(clean_line(line));
         }
         self.content = content_index(self.content.as_str(), new_line_number);
@@ -403,15 +587,20 @@ impl State {
             .0;
 
         let value = value.join("\n").trim().to_string();
-        section.caption = Some(ftd::p11::Header::from_caption(
-            value.as_str(),
-            ftd::p11::utils::i32_to_usize(line_number),
-        ));
+        let value_span = value_span.unwrap_or_default();
+        section.caption = Some(
+            ftd::p11::Header::from_caption(
+                value.as_str(),
+                ftd::p11::utils::i32_to_usize(line_number),
+            )
+            .with_span(value_span, value_span),
+        );
         Ok(())
     }
 
     fn reading_body_value(&mut self) -> ftd::p11::Result<()> {
         let mut value = vec![];
+        let mut value_span = None;
         let mut new_line_number = None;
         let mut first_line = true;
         let split_content = self.content.as_str().split('\n');
@@ -430,11 +619,15 @@ impl State {
                         message: format!("start section body '{}' after a newline!!", line),
                         doc_id: self.doc_id.to_string(),
                         line_number: ftd::p11::utils::i32_to_usize(self.line_number),
+                        span: Some(self.span(self.line_number, line)),
                     });
                 }
                 first_line = false;
             }
 
+            if value_span.is_none() && !line.trim().is_empty() {
+                value_span = Some(self.span(self.line_number, line));
+            }
             value.push(clean_line(line));
         }
         self.content = content_index(self.content.as_str(), new_line_number);
@@ -449,10 +642,10 @@ impl State {
             .0;
         let value = value.join("\n").trim().to_string();
         if !value.is_empty() {
-            section.body = Some(ftd::p11::Body::new(
-                ftd::p11::utils::i32_to_usize(line_number),
-                value.as_str(),
-            ));
+            section.body = Some(
+                ftd::p11::Body::new(ftd::p11::utils::i32_to_usize(line_number), value.as_str())
+                    .with_span(value_span.unwrap_or_default()),
+            );
         }
         let (section, parsing_state) = self.state.last_mut().unwrap();
         if !section.block_body {
@@ -483,13 +676,27 @@ impl State {
                 let (header_key, kind, condition) =
                     get_name_kind_and_condition(name_with_kind.as_str());
                 self.line_number += 1;
-                headers.push(ftd::p11::Header::kv(
-                    ftd::p11::utils::i32_to_usize(self.line_number),
-                    header_key.as_str(),
-                    kind,
-                    caption,
-                    condition,
-                ));
+                let name_with_kind = name_with_kind
+                    .split(ftd::p11::utils::INLINE_IF)
+                    .next()
+                    .unwrap_or_default();
+                let key_span = self
+                    .span(self.line_number, name_with_kind)
+                    .suffix(name_with_kind, header_key.as_str());
+                let value_span = caption
+                    .as_ref()
+                    .map(|v| self.span_after(self.line_number, ":", v.as_str()))
+                    .unwrap_or_default();
+                headers.push(
+                    ftd::p11::Header::kv(
+                        ftd::p11::utils::i32_to_usize(self.line_number),
+                        header_key.as_str(),
+                        kind,
+                        caption,
+                        condition,
+                    )
+                    .with_span(key_span, value_span),
+                );
             } else {
                 new_line_number = Some(line_number);
                 break;
@@ -535,6 +742,7 @@ impl State {
                     message: format!("`{}` section state is not yet empty", section.name),
                     doc_id: self.doc_id.to_string(),
                     line_number: ftd::p11::utils::i32_to_usize(self.line_number),
+                    span: None,
                 });
             }
         }
@@ -555,6 +763,44 @@ pub fn parse(content: &str, doc_id: &str) -> ftd::p11::Result<Vec<ftd::p11::Sect
     parse_with_line_number(content, doc_id, 0)
 }
 
+/// Same as [`parse`], but does not stop at the first error. After an error the parser skips
+/// to the next section line (`-- ` or `/-- `) and carries on, so all the syntax errors of a
+/// document are reported at once.
+///
+/// The section an error occurred in is returned with `is_broken` set and whatever could be
+/// parsed of it; [`ftd::ast::AST::from_sections`] skips such sections.
+pub fn parse_with_recovery(
+    content: &str,
+    doc_id: &str,
+) -> (Vec<ftd::p11::Section>, Vec<ftd::p11::Error>) {
+    let mut state = State {
+        content: content.to_string(),
+        doc_id: doc_id.to_string(),
+        line_number: 0,
+        sections: Default::default(),
+        state: Default::default(),
+        source: content.to_string(),
+        line_starts: line_starts(content),
+        line_offset: 0,
+    };
+    let mut errors = vec![];
+    let mut resume_after = 0;
+    let mut result = state.next();
+    while let Err(e) = result {
+        let can_continue = state.recover(&e, &mut resume_after);
+        errors.push(e);
+        if !can_continue {
+            break;
+        }
+        result = if state.state.is_empty() {
+            state.next()
+        } else {
+            state.continue_reading()
+        };
+    }
+    (state.sections, errors)
+}
+
 pub fn parse_with_line_number(
     content: &str,
     doc_id: &str,
@@ -570,11 +816,20 @@ pub fn parse_with_line_number(
         },
         sections: Default::default(),
         state: Default::default(),
+        source: content.to_string(),
+        line_starts: line_starts(content),
+        line_offset: line_number as i32,
     };
     state.next()?;
     Ok(state.sections)
 }
 
+fn line_starts(source: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(idx, _)| idx + 1))
+        .collect()
+}
+
 fn colon_separated_values(
     line_number: usize,
     line: &str,
@@ -586,6 +841,7 @@ fn colon_separated_values(
             // TODO: context should be a few lines before and after the input
             doc_id: doc_id.to_string(),
             line_number,
+            span: None,
         });
     }
 
@@ -635,6 +891,29 @@ fn valid_line(line: &str) -> bool {
     !line.starts_with(";;")
 }
 
+fn is_section_line(line: &str) -> bool {
+    line.starts_with("-- ") || line.starts_with("/-- ")
+}
+
+fn is_end_line(line: &str) -> bool {
+    line.trim_start_matches('/')
+        .strip_prefix("-- ")
+        .and_then(|v| v.split(':').next())
+        .map_or(false, |v| is_end(v.trim()))
+}
+
+/// Checks if `line` is a block header line, `-- <section-name>.<key>:`, of the section
+/// `section_name`.
+fn is_header_line(line: &str, section_name: &str) -> bool {
+    let line = line.trim_start_matches('/');
+    line.strip_prefix("-- ")
+        .and_then(|v| v.split(':').next())
+        .map(|v| get_name_and_kind(v.trim()).0)
+        .map_or(false, |v| {
+            v.starts_with(format!("{}.", section_name).as_str())
+        })
+}
+
 fn is_caption(s: &str) -> bool {
     s.eq("caption")
 }
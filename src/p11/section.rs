@@ -14,6 +14,9 @@ use itertools::Itertools;
  * - `is_commented`: A boolean representing whether the section is commented or not
  * - `line_number`: A usize representing the line number where the section starts in the document
  * - `block_body`: A boolean representing whether the section body is present as a block
+ * - `span`: `ftd::p11::Span` of the section name in the section line
+ * - `is_broken`: A boolean representing whether the section could not be parsed completely, set
+ *    only by `ftd::p11::parse_with_recovery`
  *
  */
 #[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize, Default)]
@@ -28,6 +31,10 @@ pub struct Section {
     pub is_commented: bool,
     pub line_number: usize,
     pub block_body: bool,
+    #[serde(skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_broken: bool,
 }
 
 impl Section {
@@ -42,6 +49,8 @@ impl Section {
             line_number: 0,
             headers: ftd::p11::Headers(vec![]),
             block_body: false,
+            span: Default::default(),
+            is_broken: false,
         }
     }
 
@@ -71,6 +80,8 @@ impl Section {
             is_commented: self.is_commented.to_owned(),
             line_number: 0,
             block_body: false,
+            span: Default::default(),
+            is_broken: self.is_broken,
         }
     }
 
@@ -121,13 +132,14 @@ impl Section {
         self
     }
 
-    /// returns a copy of Section after processing comments
+    /// returns a copy of Section after processing comments, broken sections (see
+    /// [`ftd::p11::parse_with_recovery()`]) are removed along with the commented ones
     ///
     /// ## NOTE: This function is only called by [`ParsedDocument::ignore_comments()`]
     ///
     /// [`ParsedDocument::ignore_comments()`]: ftd::p2::interpreter::ParsedDocument::ignore_comments
     pub fn remove_comments(&self) -> Option<Section> {
-        if self.is_commented {
+        if self.is_commented || self.is_broken {
             return None;
         }
         Some(Section {
@@ -144,6 +156,8 @@ impl Section {
             is_commented: false,
             line_number: self.line_number,
             block_body: self.block_body,
+            span: self.span,
+            is_broken: false,
         })
     }
 }
@@ -152,6 +166,8 @@ impl Section {
 pub struct Body {
     pub line_number: usize,
     pub value: String,
+    #[serde(default, skip_serializing_if = "ftd::p11::Span::is_empty")]
+    pub span: ftd::p11::Span,
 }
 
 impl Body {
@@ -159,12 +175,20 @@ impl Body {
         Body {
             line_number,
             value: value.trim().to_string(),
+            span: Default::default(),
         }
     }
+
+    pub(crate) fn with_span(mut self, span: ftd::p11::Span) -> Body {
+        self.span = span;
+        self
+    }
+
     pub fn without_line_number(&self) -> Self {
         Body {
             line_number: 0,
             value: self.value.to_string(),
+            span: Default::default(),
         }
     }
 
@@ -174,6 +198,7 @@ impl Body {
         value.map(|value| Body {
             line_number: self.line_number,
             value,
+            span: self.span,
         })
     }
 
@@ -0,0 +1,113 @@
+/**
+ * Location of a token in the source of a document.
+ *
+ * # Fields
+ *
+ * - `offset`: byte offset of the first character of the token in the document
+ * - `line`: line number of the token, same as the `line_number` reported everywhere else
+ * - `column`: 1-based column (in characters) of the first character of the token
+ * - `length`: length of the token in characters, `0` if the span is unknown
+ *
+ */
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Span {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+impl Span {
+    pub fn new(offset: usize, line: usize, column: usize, length: usize) -> Span {
+        Span {
+            offset,
+            line,
+            column,
+            length,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Span covering the whole (trimmed) `line` of `source`. `line` is 1-based.
+    pub fn line(source: &str, line: usize) -> Span {
+        Span::locate(source, line, "")
+    }
+
+    /// Span of the first occurrence of `needle` in `line` (1-based) of `source`.
+    ///
+    /// If `needle` is empty or can not be found in the line, the span covers the trimmed line,
+    /// so the diagnostic still points somewhere useful.
+    pub fn locate(source: &str, line: usize, needle: &str) -> Span {
+        Span::locate_after(source, line, "", needle)
+    }
+
+    /// Same as [`Span::locate`], but only looks for `needle` after the first occurrence of
+    /// `after` in the line, e.g. the caption after the `:` of a section line.
+    pub fn locate_after(source: &str, line: usize, after: &str, needle: &str) -> Span {
+        let mut offset = 0;
+        for (idx, content) in source.split('\n').enumerate() {
+            if idx + 1 == line {
+                return Span::in_line(content, offset, line, after, needle);
+            }
+            offset += content.len() + 1;
+        }
+        Span {
+            offset,
+            line,
+            column: 1,
+            length: 0,
+        }
+    }
+
+    /// Same as [`Span::locate_after`], for callers that already know the `content` of `line`
+    /// and the byte `offset` it starts at, so the source does not have to be scanned again.
+    pub(crate) fn in_line(
+        content: &str,
+        offset: usize,
+        line: usize,
+        after: &str,
+        needle: &str,
+    ) -> Span {
+        let needle = needle.trim();
+        let skip = content
+            .find(after)
+            .map(|v| v + after.len())
+            .unwrap_or_default();
+        let (start, len) = match content[skip..].find(needle) {
+            Some(start) if !needle.is_empty() => (skip + start, needle.len()),
+            _ => {
+                let trimmed = content.trim();
+                (content.len() - content.trim_start().len(), trimmed.len())
+            }
+        };
+        Span {
+            offset: offset + start,
+            line,
+            column: content[..start].chars().count() + 1,
+            length: content[start..start + len].chars().count(),
+        }
+    }
+
+    /// Narrows a span covering `text` down to `suffix`, if `text` ends with it. Used to point
+    /// at the name in `kind name` pairs.
+    pub(crate) fn suffix(mut self, text: &str, suffix: &str) -> Span {
+        if let Some(prefix) = text.trim().strip_suffix(suffix) {
+            if self.length == text.trim().chars().count() {
+                self.offset += prefix.len();
+                self.column += prefix.chars().count();
+                self.length = suffix.chars().count();
+            }
+        }
+        self
+    }
+
+    /// Same span, but reported at `line_number` instead of the line it was located in.
+    pub(crate) fn at_line(mut self, line_number: usize) -> Span {
+        self.line = line_number;
+        self
+    }
+}
@@ -922,3 +922,233 @@ fn kind() {
             .list(),
     );
 }
+
+#[test]
+fn span() {
+    let source = indoc!(
+        "
+        -- ftd.text: Hello
+        color: red
+        color: blue
+
+        -- ftd.text.padding: 10
+        "
+    );
+    let sections = super::parse(source, "foo").unwrap_or_else(|e| panic!("{:?}", e));
+    let section = sections.first().unwrap();
+    assert_eq!(section.span, ftd::p11::Span::new(3, 1, 4, 8));
+    assert_eq!(
+        section.caption.as_ref().unwrap().get_span(),
+        ftd::p11::Span::new(13, 1, 14, 5)
+    );
+
+    let color = section.headers.find("color");
+    assert_eq!(color[0].get_span(), ftd::p11::Span::new(19, 2, 1, 5));
+    assert_eq!(color[0].get_value_span(), ftd::p11::Span::new(26, 2, 8, 3));
+
+    let padding = section.headers.find("padding");
+    assert_eq!(padding[0].get_span(), ftd::p11::Span::new(55, 5, 13, 7));
+
+    let error = section
+        .headers
+        .find_once("color", "foo", section.line_number)
+        .unwrap_err();
+    assert_eq!(
+        ftd::diagnostic::Diagnostic::from(&error).render(source),
+        indoc!(
+            "
+            error: more than one header for key `color`
+             --> foo:3:1
+              |
+            3 | color: blue
+              | ^^^^^"
+        )
+    );
+}
+
+#[test]
+fn recovery() {
+    use itertools::Itertools;
+
+    let source = indoc!(
+        "
+        -- ftd.text: Hello
+
+        -- ftd.column
+        padding: 10
+
+        -- ftd.text: World
+        color: red
+        This is body
+
+        -- ftd.text: Bye
+        "
+    );
+    let (sections, errors) = super::parse_with_recovery(source, "foo");
+    assert_eq!(
+        errors
+            .iter()
+            .map(|e| ftd::diagnostic::Diagnostic::from(e).line_number)
+            .collect_vec(),
+        vec![Some(3), Some(8)]
+    );
+    assert_eq!(
+        sections
+            .iter()
+            .map(|s| (s.name.as_str(), s.line_number, s.is_broken))
+            .collect_vec(),
+        vec![
+            ("ftd.text", 1, false),
+            ("ftd.column", 3, true),
+            ("ftd.text", 6, true),
+            ("ftd.text", 10, false),
+        ]
+    );
+    assert_eq!(
+        ftd::ast::AST::from_sections(sections.as_slice(), "foo")
+            .unwrap_or_else(|e| panic!("{:?}", e))
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn format() {
+    let source = indoc!(
+        "
+        ;; the heading
+        --   ftd.text:   Hello   
+        color:   red
+        padding.px if {flag}:10
+
+
+        This is   body
+        ;; in body
+
+        \\-- not a section
+
+
+        -- ftd.column:
+        -- ftd.column.id: main
+
+        ;; before text
+        -- ftd.column.text:
+
+        multi
+        line
+        -- ftd.text: child
+        -- end: ftd.column
+        "
+    );
+    let formatted = indoc!(
+        "
+        ;; the heading
+        -- ftd.text: Hello
+        color: red
+        padding.px if {flag}: 10
+
+        This is   body
+        ;; in body
+
+        \\-- not a section
+
+        -- ftd.column:
+        id: main
+
+        ;; before text
+        -- ftd.column.text:
+
+        multi
+        line
+
+        -- ftd.text: child
+
+        -- end: ftd.column
+        "
+    );
+    assert_eq!(super::format(source, "foo").unwrap(), formatted);
+    assert_eq!(super::format(formatted, "foo").unwrap(), formatted);
+}
+
+#[test]
+fn format_block_headers() {
+    assert_eq!(
+        super::to_string(&[ftd::p11::Section::with_name("foo")
+            .and_body("-- yo:\n;; not a comment")
+            .add_header_str("key", "multi\nline")
+            .add_header_section(
+                "bar",
+                Some("ftd.ui".to_string()),
+                ftd::p11::Section::with_name("ftd.text")
+                    .and_caption("hello")
+                    .list(),
+                Some("{flag}".to_string()),
+            )]),
+        indoc!(
+            "
+            -- foo:
+
+            -- foo.key:
+
+            multi
+            line
+
+            -- ftd.ui foo.bar:
+            if: {flag}
+
+            -- ftd.text: hello
+
+            -- end: foo.bar
+
+            -- foo.body:
+
+            \\-- yo:
+            \\;; not a comment
+            "
+        )
+    );
+}
+
+#[test]
+fn format_end_of_empty_section() {
+    let formatted = indoc!(
+        "
+        -- ftd.row:
+
+        -- ftd.row:
+        id: inner
+
+        -- end: ftd.row
+
+        -- ftd.text: child
+
+        -- ftd.column:
+
+        -- end: ftd.row
+        "
+    );
+    assert_eq!(super::format(formatted, "foo").unwrap(), formatted);
+}
+
+#[test]
+fn format_round_trip() {
+    let mut files = find_all_files_matching_extension_recursively("t/p1", "ftd");
+    files.extend(find_all_files_matching_extension_recursively(
+        "t/html", "ftd",
+    ));
+    for file in files {
+        let source = std::fs::read_to_string(&file).unwrap();
+        if super::parse(source.as_str(), "foo").is_err() {
+            continue;
+        }
+        // `format` checks the formatted document parses to the same sections
+        let formatted = super::format(source.as_str(), "foo")
+            .unwrap_or_else(|e| panic!("{}: {:?}", file.display(), e));
+        assert_eq!(
+            super::format(formatted.as_str(), "foo").unwrap(),
+            formatted,
+            "{} is not formatted the same way twice",
+            file.display()
+        );
+    }
+}
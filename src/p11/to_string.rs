@@ -0,0 +1,278 @@
+/// Prints `sections` in the canonical form, see [`format`].
+pub fn to_string(sections: &[ftd::p11::Section]) -> String {
+    let mut printer = Printer::default();
+    printer.sections(sections, None);
+    printer.finish()
+}
+
+/**
+ * Formats the source of a document, the way `ftd fmt` does.
+ *
+ * - sections are separated by exactly one blank line, as are the block headers and sub sections
+ *   of a section, and the `-- end:` of a section
+ * - a section without sub sections has no `-- end:`, unless it is in a section of the same
+ *   name, whose `-- end:` would end it instead
+ * - section lines, headers and block headers are written as `-- kind name: value`, with single
+ *   spaces and no trailing whitespace
+ * - headers are written inline, right after the section line, as long as they can be: their
+ *   value fits on a line, and no header before them had to be written as a block header. Their
+ *   order is kept, it is part of the parsed document (e.g. conditional values of a header are
+ *   tried in order).
+ * - captions are written on the section line unless they span multiple lines
+ * - the body follows the headers after exactly one blank line, or is written as a
+ *   `-- <section>.body:` block header if the section has block headers
+ * - `;;` comments are kept: each comment line stays before the section, header or line of
+ *   value that followed it, comments in a body or in a header value stay where they were
+ *
+ * Formatting never changes the meaning of a document: `parse(format(x))` is the same as
+ * `parse(x)`, line numbers aside. This is checked, and an error returned if it does not hold.
+ */
+pub fn format(source: &str, doc_id: &str) -> ftd::p11::Result<String> {
+    use itertools::Itertools;
+
+    let sections = ftd::p11::parse(source, doc_id)?;
+    let mut printer = Printer {
+        comments: source
+            .split('\n')
+            .enumerate()
+            .filter(|(_, line)| line.starts_with(";;"))
+            .map(|(idx, line)| (idx + 1, line.trim_end()))
+            .collect(),
+        ..Default::default()
+    };
+    printer.sections(sections.as_slice(), None);
+    let formatted = printer.finish();
+
+    let without_line_number = |sections: &[ftd::p11::Section]| {
+        sections
+            .iter()
+            .map(|v| v.without_line_number())
+            .collect_vec()
+    };
+    match ftd::p11::parse(formatted.as_str(), doc_id) {
+        Ok(v) if without_line_number(&v) == without_line_number(&sections) => Ok(formatted),
+        _ => ftd::p11::utils::parse_error("formatting would change the parsed document", doc_id, 0),
+    }
+}
+
+#[derive(Debug, Default)]
+struct Printer<'a> {
+    /// `;;` lines of the document being formatted not printed yet, by line number
+    comments: std::collections::BTreeMap<usize, &'a str>,
+    out: Vec<String>,
+}
+
+impl<'a> Printer<'a> {
+    fn finish(mut self) -> String {
+        if !self.comments.is_empty() {
+            self.blank_line();
+            self.comments_before(usize::MAX);
+        }
+        let mut out = self.out.join("\n");
+        out.push('\n');
+        out
+    }
+
+    /// Prints the comments found before `line_number` in the source.
+    fn comments_before(&mut self, line_number: usize) {
+        if line_number == 0 {
+            return;
+        }
+        let rest = self.comments.split_off(&line_number);
+        let comments = std::mem::replace(&mut self.comments, rest);
+        self.out
+            .extend(comments.into_values().map(|v| v.to_string()));
+    }
+
+    fn blank_line(&mut self) {
+        if self.out.last().map_or(false, |v| !v.is_empty()) {
+            self.out.push("".to_string());
+        }
+    }
+
+    /// `ended_by` is the name in the `-- end:` printed after `sections`, if any.
+    fn sections(&mut self, sections: &[ftd::p11::Section], ended_by: Option<&str>) {
+        for section in sections {
+            self.blank_line();
+            self.section(section, ended_by);
+        }
+    }
+
+    fn section(&mut self, section: &ftd::p11::Section, ended_by: Option<&str>) {
+        self.comments_before(section.line_number);
+
+        let name = with_kind(section.kind.as_ref(), section.name.as_str());
+        let prefix = if section.is_commented { "/-- " } else { "-- " };
+        let caption = section.caption.as_ref().and_then(|v| match v {
+            ftd::p11::Header::KV(kv) => Some(kv),
+            ftd::p11::Header::Section(_) => None,
+        });
+        let block_caption = match caption.and_then(|v| v.value.as_ref()) {
+            Some(value) if is_inline_value(value) => {
+                self.out.push(format!("{}{}: {}", prefix, name, value));
+                None
+            }
+            Some(_) => caption,
+            None => {
+                self.out.push(format!("{}{}:", prefix, name));
+                None
+            }
+        };
+
+        let inline = section
+            .headers
+            .0
+            .iter()
+            .take_while(|v| is_inline(v))
+            .count();
+        for header in section.headers.0[..inline].iter() {
+            if let ftd::p11::Header::KV(kv) = header {
+                self.comments_before(kv.span.line);
+                self.out.push(
+                    format!(
+                        "{}{}:{}",
+                        with_kind(kv.kind.as_ref(), kv.key.as_str()),
+                        kv.condition
+                            .as_ref()
+                            .map(|v| format!("{}{}", ftd::p11::utils::INLINE_IF, v))
+                            .unwrap_or_default(),
+                        kv.value
+                            .as_ref()
+                            .map(|v| format!(" {}", v))
+                            .unwrap_or_default()
+                    )
+                    .trim_end()
+                    .to_string(),
+                );
+            }
+        }
+
+        let has_block_headers = block_caption.is_some() || inline < section.headers.0.len();
+        if let Some(caption) = block_caption {
+            self.blank_line();
+            self.comments_before(caption.span.line);
+            self.out
+                .push(format!("-- {}.{}:", section.name, BLOCK_CAPTION));
+            self.value(caption.value.as_deref().unwrap_or_default(), caption.span);
+        }
+        for header in section.headers.0[inline..].iter() {
+            self.blank_line();
+            self.block_header(section.name.as_str(), header);
+        }
+        if let Some(ref body) = section.body {
+            self.blank_line();
+            self.comments_before(body.span.line);
+            if has_block_headers {
+                self.out
+                    .push(format!("-- {}.{}:", section.name, BLOCK_BODY));
+            }
+            self.value(body.value.as_str(), body.span);
+        }
+
+        self.sections(section.sub_sections.as_slice(), Some(section.name.as_str()));
+        // the `-- end:` after a section without sub sections would end it instead of the
+        // section it is in, if they have the same name
+        if !section.sub_sections.is_empty() || ended_by == Some(section.name.as_str()) {
+            self.blank_line();
+            self.out.push(format!("-- end: {}", section.name));
+        }
+    }
+
+    fn block_header(&mut self, section_name: &str, header: &ftd::p11::Header) {
+        let key = format!("{}.{}", section_name, header.get_key());
+        let key = with_kind(header.get_kind().as_ref(), key.as_str());
+        self.comments_before(header.get_span().line);
+        match header {
+            ftd::p11::Header::KV(kv) => {
+                match kv.value {
+                    Some(ref value) if is_inline_value(value) => {
+                        self.out.push(format!("-- {}: {}", key, value))
+                    }
+                    _ => self.out.push(format!("-- {}:", key)),
+                }
+                self.condition(kv.condition.as_ref());
+                match kv.value {
+                    Some(ref value) if !is_inline_value(value) => {
+                        self.value(value.as_str(), kv.value_span)
+                    }
+                    _ => {}
+                }
+            }
+            ftd::p11::Header::Section(s) => {
+                self.out.push(format!("-- {}:", key));
+                self.condition(s.condition.as_ref());
+                let ended_by = format!("{}.{}", section_name, s.key);
+                self.sections(s.section.as_slice(), Some(ended_by.as_str()));
+                self.blank_line();
+                self.out.push(format!("-- end: {}", ended_by));
+            }
+        }
+    }
+
+    fn condition(&mut self, condition: Option<&String>) {
+        if let Some(condition) = condition {
+            self.out
+                .push(format!("{}: {}", ftd::p11::utils::IF, condition));
+        }
+    }
+
+    /// Prints a multi-line value, after a blank line, along with the comments in between its
+    /// lines. `span` is the span of the first line of the value in the source.
+    fn value(&mut self, value: &str, span: ftd::p11::Span) {
+        if value.is_empty() {
+            return;
+        }
+        self.blank_line();
+        self.comments_before(span.line);
+        let mut line_number = span.line;
+        for line in value.split('\n') {
+            if line_number > 0 {
+                while let Some(comment) = self.comments.remove(&line_number) {
+                    self.out.push(comment.to_string());
+                    line_number += 1;
+                }
+                line_number += 1;
+            }
+            self.out.push(escape(line));
+        }
+    }
+}
+
+const BLOCK_CAPTION: &str = "caption";
+const BLOCK_BODY: &str = "body";
+
+fn with_kind(kind: Option<&String>, name: &str) -> String {
+    match kind {
+        Some(kind) => format!("{} {}", kind, name),
+        None => name.to_string(),
+    }
+}
+
+fn is_inline_value(value: &str) -> bool {
+    !value.is_empty() && !value.contains('\n')
+}
+
+/// Checks if `header` can be written right after the section line, in the `key: value` form.
+fn is_inline(header: &ftd::p11::Header) -> bool {
+    match header {
+        ftd::p11::Header::KV(kv) => {
+            kv.value.as_deref().map_or(true, |v| !v.contains('\n'))
+                && kv
+                    .condition
+                    .as_deref()
+                    .map_or(true, |v| !v.contains(':') && !v.contains('\n'))
+                && !kv.key.contains(':')
+                && !kv.key.is_empty()
+        }
+        ftd::p11::Header::Section(_) => false,
+    }
+}
+
+/// Escapes a line of a multi-line value, so that it is not read as a section or a comment.
+fn escape(line: &str) -> String {
+    if line.starts_with("-- ") || line.starts_with(";;") {
+        format!("\\{}", line)
+    } else {
+        line.to_string()
+    }
+}
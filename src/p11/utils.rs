@@ -48,6 +48,7 @@ where
         message: m.into(),
         doc_id: doc_id.to_string(),
         line_number,
+        span: None,
     })
 }
 
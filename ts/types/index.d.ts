@@ -21,6 +21,64 @@ declare global {
         get_value(id: string, variable: string): any;
         is_empty(str: any): boolean;
         append(array: any[], value: any): any[]
+        length(array: any[]): number;
+        insert_at(array: any[], index: number, value: any): any[];
+        delete_at(array: any[], index: number): any[];
+        clear(array: any[]): any[];
+        filter(array: any[], ...args: any[]): any[];
+        contains(array: any[], value: any): boolean;
+        sort_by(array: any[], field: string): any[];
+        dependencies(id: string, dependencies: NodeDependency[]): void;
+        loop_template(id: string, template: LoopTemplate): void;
+    }
+
+    interface NodeDependency {
+        key: string;
+        id: string;
+        property: "text" | { style: string } | { attribute: string };
+        cases: [Expression, Expression][];
+        default: Expression;
+        template?: string;
+    }
+
+    type Expression =
+        | { value: any }
+        | { reference: string }
+        | { variable: string }
+        | { format: { pattern: string, eval: boolean, value: Expression } }
+        | { record: { [name: string]: Expression } }
+        | { function: Action }
+        | { call: { name: string, arguments: Expression[] } }
+        | { operation: { operator: string, operands: Expression[] } }
+        | { theme: { light: Expression, dark: Expression } }
+        | { device: { desktop: Expression, mobile: Expression } };
+
+    interface LoopTemplate {
+        id: string;
+        on: string;
+        item: string;
+        counter: string;
+        locals: string[];
+        instances: string[];
+        parent: string | null;
+        node_changes: ((data: any) => void)[];
+        initial_locals: { [name: string]: any };
+    }
+
+    interface LoopInstance {
+        template: LoopTemplate;
+        root: Element;
+        index: number;
+        key: string;
+        locals: { [name: string]: any };
+        loops: { [template: string]: LoopState };
+        build_time: boolean;
+    }
+
+    interface LoopState {
+        template: LoopTemplate;
+        rendered: string | null;
+        instances: LoopInstance[] | null;
     }
 
     interface String {